@@ -21,7 +21,7 @@ fn read_stdin() -> io::Result<Box<[u8]>> {
 
 fn main() -> io::Result<()> {
     let bytes = read_stdin()?;
-    let mut visitor = Names::default();
+    let mut visitor = Names;
     let mut parser = TPTPIterator::<()>::new(&bytes);
     for input in &mut parser {
         visitor.visit_tptp_input(&input.expect("syntax error"));
@@ -0,0 +1,540 @@
+use alloc::format;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::Arena;
+use crate::tfx;
+
+/// syntax which can be compared up to renaming of variables
+pub trait Canonical<'a>: Sized {
+    /// rename every variable to `X0`, `X1`, ... in order of first occurrence
+    ///
+    /// Each binding introduces a new variable, so `![X]:p(X)&![X]:q(X)` becomes `![X0]:p(X0)&![X1]:q(X1)`.
+    /// Free variables are renamed too, as if bound outside the formula.
+    fn canonical(&self, arena: &'a Arena) -> Self;
+
+    /// whether `self` and `other` are equal up to renaming of variables
+    fn alpha_eq(&self, other: &Self) -> bool;
+}
+
+struct Renaming<'a> {
+    arena: &'a Arena,
+    names: Vec<Variable<'a>>,
+    bound: Vec<(Variable<'a>, Variable<'a>)>,
+    free: Vec<(Variable<'a>, Variable<'a>)>,
+    count: usize,
+}
+
+impl<'a> Renaming<'a> {
+    fn new(arena: &'a Arena) -> Self {
+        Self {
+            arena,
+            names: vec![],
+            bound: vec![],
+            free: vec![],
+            count: 0,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.bound.clear();
+        self.free.clear();
+        self.count = 0;
+    }
+
+    fn next(&mut self) -> Variable<'a> {
+        let index = self.count;
+        self.count += 1;
+        if index == self.names.len() {
+            let name = self.arena.alloc(&format!("X{}", index));
+            self.names.push(Variable(UpperWord(name)));
+        }
+        self.names[index].clone()
+    }
+
+    fn bind(&mut self, variable: &mut Variable<'a>) {
+        let name = self.next();
+        let original = core::mem::replace(variable, name.clone());
+        self.bound.push((original, name));
+    }
+
+    fn variable(&mut self, variable: &mut Variable<'a>) {
+        let found = self
+            .bound
+            .iter()
+            .rev()
+            .chain(self.free.iter())
+            .find(|(original, _)| original == variable);
+        let name = match found {
+            Some((_, name)) => name.clone(),
+            None => {
+                let name = self.next();
+                self.free.push((variable.clone(), name.clone()));
+                name
+            }
+        };
+        *variable = name;
+    }
+
+    fn unbind(&mut self, depth: usize) {
+        self.bound.truncate(depth);
+    }
+
+    fn fof_arguments(&mut self, arguments: &mut fof::Arguments<'a>) {
+        for term in &mut arguments.0 {
+            self.fof_term(term);
+        }
+    }
+
+    fn fof_plain_term(&mut self, term: &mut fof::PlainTerm<'a>) {
+        if let fof::PlainTerm::Function(_, arguments) = term {
+            self.fof_arguments(arguments);
+        }
+    }
+
+    fn fof_defined_plain_term(
+        &mut self,
+        term: &mut fof::DefinedPlainTerm<'a>,
+    ) {
+        if let fof::DefinedPlainTerm::Function(_, arguments) = term {
+            self.fof_arguments(arguments);
+        }
+    }
+
+    fn fof_system_term(&mut self, term: &mut fof::SystemTerm<'a>) {
+        if let fof::SystemTerm::Function(_, arguments) = term {
+            self.fof_arguments(arguments);
+        }
+    }
+
+    fn fof_term(&mut self, term: &mut fof::Term<'a>) {
+        match term {
+            fof::Term::Variable(variable) => self.variable(variable),
+            fof::Term::Function(function) => match &mut **function {
+                fof::FunctionTerm::Plain(term) => self.fof_plain_term(term),
+                fof::FunctionTerm::System(term) => self.fof_system_term(term),
+                fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(term)) => {
+                    self.fof_defined_plain_term(&mut term.0)
+                }
+                fof::FunctionTerm::Defined(fof::DefinedTerm::Defined(_)) => {}
+            },
+        }
+    }
+
+    fn fof_atomic_formula(&mut self, atom: &mut fof::AtomicFormula<'a>) {
+        use fof::{AtomicFormula, DefinedAtomicFormula};
+        match atom {
+            AtomicFormula::Plain(plain) => self.fof_plain_term(&mut plain.0),
+            AtomicFormula::Defined(DefinedAtomicFormula::Plain(plain)) => {
+                self.fof_defined_plain_term(&mut plain.0)
+            }
+            AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix)) => {
+                self.fof_term(&mut infix.left);
+                self.fof_term(&mut infix.right);
+            }
+            AtomicFormula::System(system) => {
+                self.fof_system_term(&mut system.0)
+            }
+        }
+    }
+
+    fn fof_infix_unary(&mut self, infix: &mut fof::InfixUnary<'a>) {
+        self.fof_term(&mut infix.left);
+        self.fof_term(&mut infix.right);
+    }
+
+    fn fof_unit_formula(&mut self, formula: &mut fof::UnitFormula<'a>) {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => {
+                self.fof_unitary_formula(unitary)
+            }
+            fof::UnitFormula::Unary(unary) => self.fof_unary_formula(unary),
+        }
+    }
+
+    fn fof_unary_formula(&mut self, formula: &mut fof::UnaryFormula<'a>) {
+        match formula {
+            fof::UnaryFormula::Unary(_, unit) => self.fof_unit_formula(unit),
+            fof::UnaryFormula::InfixUnary(infix) => {
+                self.fof_infix_unary(infix)
+            }
+        }
+    }
+
+    fn fof_unitary_formula(&mut self, formula: &mut fof::UnitaryFormula<'a>) {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.bound.len();
+                for variable in &mut quantified.bound.0 {
+                    self.bind(variable);
+                }
+                self.fof_unit_formula(&mut quantified.formula);
+                self.unbind(depth);
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.fof_atomic_formula(atom),
+            fof::UnitaryFormula::Parenthesised(logic) => {
+                self.fof_logic_formula(logic)
+            }
+        }
+    }
+
+    fn fof_logic_formula(&mut self, formula: &mut fof::LogicFormula<'a>) {
+        match formula {
+            fof::LogicFormula::Binary(fof::BinaryFormula::Assoc(assoc)) => {
+                let units = match assoc {
+                    fof::BinaryAssoc::Or(or) => &mut or.0,
+                    fof::BinaryAssoc::And(and) => &mut and.0,
+                };
+                for unit in units {
+                    self.fof_unit_formula(unit);
+                }
+            }
+            fof::LogicFormula::Binary(fof::BinaryFormula::Nonassoc(
+                nonassoc,
+            )) => {
+                self.fof_unit_formula(&mut nonassoc.left);
+                self.fof_unit_formula(&mut nonassoc.right);
+            }
+            fof::LogicFormula::Unary(unary) => self.fof_unary_formula(unary),
+            fof::LogicFormula::Unitary(unitary) => {
+                self.fof_unitary_formula(unitary)
+            }
+        }
+    }
+
+    fn fof_formula(&mut self, formula: &mut fof::Formula<'a>) {
+        self.fof_logic_formula(&mut formula.0);
+    }
+
+    fn literal(&mut self, literal: &mut cnf::Literal<'a>) {
+        match literal {
+            cnf::Literal::Atomic(atom) | cnf::Literal::NegatedAtomic(atom) => {
+                self.fof_atomic_formula(atom)
+            }
+            cnf::Literal::Infix(infix) => self.fof_infix_unary(infix),
+        }
+    }
+
+    fn disjunction(&mut self, disjunction: &mut cnf::Disjunction<'a>) {
+        for literal in &mut disjunction.0 {
+            self.literal(literal);
+        }
+    }
+
+    fn cnf_formula(&mut self, formula: &mut cnf::Formula<'a>) {
+        match formula {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => {
+                self.disjunction(disjunction)
+            }
+        }
+    }
+
+    fn tfx_atomic_type(&mut self, typ: &mut tfx::AtomicType<'a>) {
+        match typ {
+            tfx::AtomicType::Variable(variable) => self.variable(variable),
+            tfx::AtomicType::Function(_, arguments) => {
+                for typ in &mut arguments.0 {
+                    self.tfx_atomic_type(typ);
+                }
+            }
+            tfx::AtomicType::Constant(_) | tfx::AtomicType::Defined(_) => {}
+        }
+    }
+
+    fn tfx_bind_variables(&mut self, list: &mut tfx::VariableList<'a>) {
+        for variable in &mut list.0 {
+            match variable {
+                tfx::Variable::Typed(typed) => {
+                    self.tfx_atomic_type(&mut typed.typ);
+                    self.bind(&mut typed.variable);
+                }
+                tfx::Variable::Untyped(variable) => self.bind(variable),
+            }
+        }
+    }
+
+    fn tfx_unitary_type(&mut self, typ: &mut tfx::UnitaryType<'a>) {
+        match typ {
+            tfx::UnitaryType::Atomic(atomic) => self.tfx_atomic_type(atomic),
+            tfx::UnitaryType::Product(product) => {
+                for typ in &mut product.0 {
+                    self.tfx_unitary_type(typ);
+                }
+            }
+        }
+    }
+
+    fn tfx_mapping_type(&mut self, typ: &mut tfx::MappingType<'a>) {
+        self.tfx_unitary_type(&mut typ.domain);
+        self.tfx_atomic_type(&mut typ.range);
+    }
+
+    fn tfx_quantified_type(&mut self, typ: &mut tfx::QuantifiedType<'a>) {
+        let depth = self.bound.len();
+        self.tfx_bind_variables(&mut typ.bound);
+        self.tfx_monotype(&mut typ.typ);
+        self.unbind(depth);
+    }
+
+    fn tfx_monotype(&mut self, typ: &mut tfx::Monotype<'a>) {
+        match typ {
+            tfx::Monotype::Atomic(atomic) => self.tfx_atomic_type(atomic),
+            tfx::Monotype::Mapping(mapping) => self.tfx_mapping_type(mapping),
+            tfx::Monotype::Quantified(quantified) => {
+                self.tfx_quantified_type(quantified)
+            }
+        }
+    }
+
+    fn tfx_non_atomic_type(&mut self, typ: &mut tfx::NonAtomicType<'a>) {
+        match typ {
+            tfx::NonAtomicType::Mapping(mapping) => {
+                self.tfx_mapping_type(mapping)
+            }
+            tfx::NonAtomicType::Quantified(quantified) => {
+                self.tfx_quantified_type(quantified)
+            }
+            tfx::NonAtomicType::Parenthesised(typ) => {
+                self.tfx_non_atomic_type(typ)
+            }
+        }
+    }
+
+    fn tfx_atom_typing(&mut self, typing: &mut tfx::AtomTyping<'a>) {
+        match typing {
+            tfx::AtomTyping::Typing(_, tfx::TopLevelType::Atomic(atomic)) => {
+                self.tfx_atomic_type(atomic)
+            }
+            tfx::AtomTyping::Typing(
+                _,
+                tfx::TopLevelType::NonAtomic(non_atomic),
+            ) => self.tfx_non_atomic_type(non_atomic),
+            tfx::AtomTyping::Parenthesised(typing) => {
+                self.tfx_atom_typing(typing)
+            }
+        }
+    }
+
+    fn tfx_arguments(&mut self, arguments: &mut tfx::Arguments<'a>) {
+        for term in &mut arguments.0 {
+            self.tfx_term(term);
+        }
+    }
+
+    fn tfx_term(&mut self, term: &mut tfx::Term<'a>) {
+        match term {
+            tfx::Term::Logic(logic) => self.tfx_logic_formula(logic),
+            tfx::Term::Defined(_) => {}
+        }
+    }
+
+    fn tfx_unitary_term(&mut self, term: &mut tfx::UnitaryTerm<'a>) {
+        match term {
+            tfx::UnitaryTerm::Atomic(atom) => self.tfx_atomic_formula(atom),
+            tfx::UnitaryTerm::Defined(_) => {}
+            tfx::UnitaryTerm::Variable(variable) => self.variable(variable),
+            tfx::UnitaryTerm::Logic(logic) => self.tfx_logic_formula(logic),
+        }
+    }
+
+    fn tfx_atomic_formula(&mut self, atom: &mut tfx::AtomicFormula<'a>) {
+        let arguments = match atom {
+            tfx::AtomicFormula::Plain(tfx::PlainAtomic::Function(
+                _,
+                arguments,
+            )) => arguments,
+            tfx::AtomicFormula::Defined(tfx::DefinedAtomic(
+                tfx::DefinedPlain::Function(_, arguments),
+            )) => arguments,
+            tfx::AtomicFormula::System(tfx::SystemAtomic::Function(
+                _,
+                arguments,
+            )) => arguments,
+            _ => return,
+        };
+        self.tfx_arguments(arguments);
+    }
+
+    fn tfx_defined_infix(&mut self, infix: &mut tfx::DefinedInfix<'a>) {
+        self.tfx_unitary_term(&mut infix.left);
+        self.tfx_unitary_term(&mut infix.right);
+    }
+
+    fn tfx_unary_formula(&mut self, formula: &mut tfx::UnaryFormula<'a>) {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix_unary(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                self.tfx_unitary_term(&mut infix.left);
+                self.tfx_unitary_term(&mut infix.right);
+            }
+        }
+    }
+
+    fn tfx_prefix_unary(&mut self, prefix: &mut tfx::PrefixUnary<'a>) {
+        match &mut *prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            tfx::PreunitFormula::Prefix(prefix) => {
+                self.tfx_prefix_unary(prefix)
+            }
+        }
+    }
+
+    fn tfx_unitary_formula(&mut self, formula: &mut tfx::UnitaryFormula<'a>) {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.bound.len();
+                self.tfx_bind_variables(&mut quantified.bound);
+                self.tfx_unit_formula(&mut quantified.formula);
+                self.unbind(depth);
+            }
+            tfx::UnitaryFormula::Atomic(atom) => self.tfx_atomic_formula(atom),
+            tfx::UnitaryFormula::Variable(variable) => self.variable(variable),
+            tfx::UnitaryFormula::Logic(logic) => self.tfx_logic_formula(logic),
+        }
+    }
+
+    fn tfx_unit_formula(&mut self, formula: &mut tfx::UnitFormula<'a>) {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary_formula(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_defined_infix(infix)
+            }
+        }
+    }
+
+    fn tfx_logic_formula(&mut self, formula: &mut tfx::LogicFormula<'a>) {
+        match formula {
+            tfx::LogicFormula::Unary(unary) => self.tfx_unary_formula(unary),
+            tfx::LogicFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            tfx::LogicFormula::Binary(tfx::BinaryFormula::Assoc(assoc)) => {
+                let units = match assoc {
+                    tfx::BinaryAssoc::Or(or) => &mut or.0,
+                    tfx::BinaryAssoc::And(and) => &mut and.0,
+                };
+                for unit in units {
+                    self.tfx_unit_formula(unit);
+                }
+            }
+            tfx::LogicFormula::Binary(tfx::BinaryFormula::Nonassoc(
+                nonassoc,
+            )) => {
+                self.tfx_unit_formula(&mut nonassoc.left);
+                self.tfx_unit_formula(&mut nonassoc.right);
+            }
+            tfx::LogicFormula::DefinedInfix(infix) => {
+                self.tfx_defined_infix(infix)
+            }
+        }
+    }
+
+    fn tfx_formula(&mut self, formula: &mut tfx::Formula<'a>) {
+        match formula {
+            tfx::Formula::Logic(logic) => self.tfx_logic_formula(logic),
+            tfx::Formula::AtomTyping(typing) => self.tfx_atom_typing(typing),
+        }
+    }
+}
+
+macro_rules! canonical {
+    ($dialect: ident :: $T: ident, $rename: ident) => {
+        impl<'a> Canonical<'a> for $dialect::$T<'a> {
+            fn canonical(&self, arena: &'a Arena) -> Self {
+                let mut canonical = self.clone();
+                Renaming::new(arena).$rename(&mut canonical);
+                canonical
+            }
+
+            fn alpha_eq(&self, other: &Self) -> bool {
+                let arena = Arena::new();
+                let mut renaming = Renaming::new(&arena);
+                let mut left: $dialect::$T = self.clone();
+                renaming.$rename(&mut left);
+                renaming.reset();
+                let mut right: $dialect::$T = other.clone();
+                renaming.$rename(&mut right);
+                left == right
+            }
+        }
+    };
+}
+
+canonical!(fof::Term, fof_term);
+canonical!(fof::LogicFormula, fof_logic_formula);
+canonical!(fof::Formula, fof_formula);
+canonical!(cnf::Literal, literal);
+canonical!(cnf::Disjunction, disjunction);
+canonical!(cnf::Formula, cnf_formula);
+canonical!(tfx::LogicFormula, tfx_logic_formula);
+canonical!(tfx::Formula, tfx_formula);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_fof_canonical() {
+        let arena = Arena::new();
+        let formula = check_parse(
+            fof::Formula::parse,
+            b"![Y]: (p(Y, Z) & ![Y]: q(Y)) & ?[W]: r(W, Y)\0",
+        );
+        assert_eq!(
+            formula.canonical(&arena).to_string(),
+            "![X0]:(p(X0,X1)&![X2]:q(X2))&?[X3]:r(X3,X4)"
+        );
+    }
+
+    #[test]
+    fn test_alpha_eq() {
+        let fof = |input| check_parse(fof::Formula::parse, input);
+        assert!(fof(b"![X]: p(X)\0").alpha_eq(&fof(b"![Y]: p(Y)\0")));
+        assert!(
+            fof(b"![X, Y]: p(X, Y)\0").alpha_eq(&fof(b"![Y, X]: p(Y, X)\0"))
+        );
+        assert!(
+            !fof(b"![X, Y]: p(X, Y)\0").alpha_eq(&fof(b"![X, Y]: p(Y, X)\0"))
+        );
+        assert!(!fof(b"![X]: p(X, Y)\0").alpha_eq(&fof(b"![X]: p(X, X)\0")));
+
+        let cnf = |input| check_parse(cnf::Formula::parse, input);
+        let clause = cnf(b"p(A) | ~q(A, B)\0");
+        assert!(clause.alpha_eq(&cnf(b"p(X) | ~q(X, Y)\0")));
+        assert!(!clause.alpha_eq(&cnf(b"p(X) | ~q(Y, X)\0")));
+    }
+
+    #[test]
+    fn test_tfx_canonical() {
+        let arena = Arena::new();
+        let formula = check_parse(
+            tfx::Formula::parse,
+            b"![A: $tType, X: A]: (p(X) & ![Y: A]: Y = X)\0",
+        );
+        assert_eq!(
+            formula.canonical(&arena).to_string(),
+            "![X0:$tType,X1:X0]:(p(X1)&![X2:X0]:X2=X1)"
+        );
+        let typing = check_parse(
+            tfx::Formula::parse,
+            b"map: !>[A: $tType, B: $tType]: ((list(A) * B) > list(B))\0",
+        );
+        let other = check_parse(
+            tfx::Formula::parse,
+            b"map: !>[S: $tType, T: $tType]: ((list(S) * T) > list(T))\0",
+        );
+        assert!(typing.alpha_eq(&other));
+    }
+}
@@ -0,0 +1,1020 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::{Arena, Fresh};
+use crate::nnf::{fof_assoc, fof_unit, truth_value, NegationNormalForm};
+use crate::scope::FreeVariables;
+use crate::skolem::Skolemiser;
+use crate::subst::{self, application};
+use crate::tfx;
+use crate::top::*;
+
+/// a formula which cannot be clausified
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ClausifyError<'a> {
+    /// a TFX formula goes beyond first-order logic, e.g. a formula as a term or a Boolean variable
+    #[display(fmt = "{}: not first-order", _0)]
+    NotFirstOrder(Name<'a>),
+}
+
+/// a formula in negation normal form, with one variable per quantifier
+enum Form<'a> {
+    Literal(cnf::Literal<'a>),
+    And(Vec<Form<'a>>),
+    Or(Vec<Form<'a>>),
+    Quantified(fof::Quantifier, Variable<'a>, Box<Form<'a>>),
+}
+
+type Clause<'a> = Vec<cnf::Literal<'a>>;
+
+type Bindings<'a> = BTreeMap<Variable<'a>, fof::Term<'a>>;
+
+impl<'a> Form<'a> {
+    fn occurs(&self, variable: &Variable<'a>) -> bool {
+        match self {
+            Form::Literal(literal) => {
+                literal.free_variables().contains(variable)
+            }
+            Form::And(forms) | Form::Or(forms) => {
+                forms.iter().any(|form| form.occurs(variable))
+            }
+            Form::Quantified(_, _, form) => form.occurs(variable),
+        }
+    }
+
+    /// the formula in TPTP syntax
+    fn formula(self) -> fof::LogicFormula<'a> {
+        use fof::{LogicFormula, UnaryFormula, UnitFormula, UnitaryFormula};
+        match self {
+            Form::Literal(cnf::Literal::Atomic(atom)) => {
+                LogicFormula::Unitary(UnitaryFormula::Atomic(Box::new(atom)))
+            }
+            Form::Literal(cnf::Literal::NegatedAtomic(atom)) => {
+                let atom = UnitaryFormula::Atomic(Box::new(atom));
+                let atom = Box::new(UnitFormula::Unitary(atom));
+                LogicFormula::Unary(UnaryFormula::Unary(UnaryConnective, atom))
+            }
+            Form::Literal(cnf::Literal::Infix(infix)) => {
+                LogicFormula::Unary(UnaryFormula::InfixUnary(infix))
+            }
+            Form::And(forms) => fof_assoc(
+                AssocConnective::And,
+                forms.into_iter().map(Form::formula).collect(),
+            ),
+            Form::Or(forms) => fof_assoc(
+                AssocConnective::Or,
+                forms.into_iter().map(Form::formula).collect(),
+            ),
+            Form::Quantified(quantifier, variable, form) => {
+                LogicFormula::Unitary(UnitaryFormula::Quantified(
+                    fof::QuantifiedFormula {
+                        quantifier,
+                        bound: fof::VariableList(vec![variable]),
+                        formula: Box::new(fof_unit(form.formula())),
+                    },
+                ))
+            }
+        }
+    }
+
+    /// push quantifiers as far inwards as possible
+    fn miniscope(self) -> Self {
+        match self {
+            Form::Literal(_) => self,
+            Form::And(forms) => {
+                Form::And(forms.into_iter().map(Form::miniscope).collect())
+            }
+            Form::Or(forms) => {
+                Form::Or(forms.into_iter().map(Form::miniscope).collect())
+            }
+            Form::Quantified(quantifier, variable, form) => {
+                Form::quantify(quantifier, variable, form.miniscope())
+            }
+        }
+    }
+
+    fn quantify(
+        quantifier: fof::Quantifier,
+        variable: Variable<'a>,
+        form: Self,
+    ) -> Self {
+        use fof::Quantifier::*;
+        if !form.occurs(&variable) {
+            return form;
+        }
+        let (forms, rebuild): (_, fn(Vec<Self>) -> Self) =
+            match (quantifier, form) {
+                (Forall, Form::And(forms)) | (Exists, Form::Or(forms)) => {
+                    let forms = forms
+                        .into_iter()
+                        .map(|form| {
+                            Form::quantify(quantifier, variable.clone(), form)
+                        })
+                        .collect();
+                    return match quantifier {
+                        Forall => Form::And(forms),
+                        Exists => Form::Or(forms),
+                    };
+                }
+                (Forall, Form::Or(forms)) => (forms, Form::Or),
+                (Exists, Form::And(forms)) => (forms, Form::And),
+                (_, form) => {
+                    return Form::Quantified(
+                        quantifier,
+                        variable,
+                        Box::new(form),
+                    )
+                }
+            };
+        // the quantified part takes the place of its first member
+        let position = forms.iter().position(|form| form.occurs(&variable));
+        let position = position.unwrap_or_default();
+        let (mut inside, mut outside): (Vec<_>, Vec<_>) =
+            forms.into_iter().partition(|form| form.occurs(&variable));
+        let inside = if inside.len() == 1 {
+            inside.pop().unwrap()
+        } else {
+            rebuild(inside)
+        };
+        let quantified =
+            Form::Quantified(quantifier, variable, Box::new(inside));
+        if outside.is_empty() {
+            return quantified;
+        }
+        outside.insert(position, quantified);
+        rebuild(outside)
+    }
+}
+
+fn word(word: &str) -> AtomicWord<'_> {
+    AtomicWord::Lower(LowerWord(word))
+}
+
+fn general_function<'a>(
+    name: &'a str,
+    terms: Vec<GeneralTerm<'a>>,
+) -> GeneralTerm<'a> {
+    GeneralTerm::Data(GeneralData::Function(Box::new(GeneralFunction {
+        word: word(name),
+        terms: GeneralTerms(terms),
+    })))
+}
+
+fn general_list(terms: Vec<GeneralTerm>) -> GeneralTerm {
+    if terms.is_empty() {
+        GeneralTerm::List(GeneralList(None))
+    } else {
+        GeneralTerm::List(GeneralList(Some(GeneralTerms(terms))))
+    }
+}
+
+fn general_name<'a>(name: &Name<'a>) -> GeneralTerm<'a> {
+    GeneralTerm::Data(match name {
+        Name::AtomicWord(word) => GeneralData::Atomic(word.clone()),
+        Name::Integer(integer) => {
+            GeneralData::Number(Number::Integer(integer.clone()))
+        }
+    })
+}
+
+/// `inference(rule, [status(status)], [parent])`
+fn inference<'a>(
+    rule: &'a str,
+    status: &'a str,
+    parent: GeneralTerm<'a>,
+) -> GeneralTerm<'a> {
+    let status = general_function("status", vec![general_data(status)]);
+    general_function(
+        "inference",
+        vec![
+            general_data(rule),
+            general_list(vec![status]),
+            general_list(vec![parent]),
+        ],
+    )
+}
+
+fn general_data(atom: &str) -> GeneralTerm<'_> {
+    GeneralTerm::Data(GeneralData::Atomic(word(atom)))
+}
+
+/// whether `typ` is `$tType`, i.e. a type variable is bound
+fn is_kind(typ: &tfx::AtomicType) -> bool {
+    matches!(
+        typ,
+        tfx::AtomicType::Defined(DefinedType(AtomicDefinedWord(DollarWord(
+            LowerWord("tType")
+        ))))
+    )
+}
+
+/// whether `literal` is `$true` or `$false`
+fn truth_literal(literal: &cnf::Literal) -> Option<bool> {
+    match literal {
+        cnf::Literal::Atomic(fof::AtomicFormula::Defined(
+            fof::DefinedAtomicFormula::Plain(fof::DefinedPlainFormula(
+                fof::DefinedPlainTerm::Constant(constant),
+            )),
+        )) => truth_value(constant).map(|word| word == "true"),
+        _ => None,
+    }
+}
+
+pub(crate) fn false_literal<'a>() -> cnf::Literal<'a> {
+    let constant = DefinedConstant(DefinedFunctor(AtomicDefinedWord(
+        DollarWord(LowerWord("false")),
+    )));
+    cnf::Literal::Atomic(fof::AtomicFormula::Defined(
+        fof::DefinedAtomicFormula::Plain(fof::DefinedPlainFormula(
+            fof::DefinedPlainTerm::Constant(constant),
+        )),
+    ))
+}
+
+/// remove `$false` and repeated literals, or `None` if `$true` occurs
+fn simplify(clause: Clause) -> Option<Clause> {
+    let mut simplified = vec![];
+    for literal in clause {
+        match truth_literal(&literal) {
+            Some(true) => return None,
+            Some(false) => {}
+            None => {
+                if !simplified.contains(&literal) {
+                    simplified.push(literal);
+                }
+            }
+        }
+    }
+    if simplified.is_empty() {
+        simplified.push(false_literal());
+    }
+    Some(simplified)
+}
+
+pub(crate) mod tfx_terms {
+    use super::*;
+
+    pub(super) fn arguments<'a>(
+        arguments: &tfx::Arguments<'a>,
+    ) -> Option<Box<fof::Arguments<'a>>> {
+        let terms = arguments.0.iter().map(term).collect::<Option<_>>()?;
+        Some(Box::new(fof::Arguments(terms)))
+    }
+
+    pub(super) fn atomic<'a>(
+        atom: &tfx::AtomicFormula<'a>,
+    ) -> Option<fof::FunctionTerm<'a>> {
+        use tfx::{DefinedPlain, PlainAtomic, SystemAtomic};
+        Some(match atom {
+            tfx::AtomicFormula::Plain(PlainAtomic::Constant(constant)) => {
+                fof::FunctionTerm::Plain(fof::PlainTerm::Constant(
+                    constant.clone(),
+                ))
+            }
+            tfx::AtomicFormula::Plain(PlainAtomic::Function(f, args)) => {
+                fof::FunctionTerm::Plain(fof::PlainTerm::Function(
+                    f.clone(),
+                    arguments(args)?,
+                ))
+            }
+            tfx::AtomicFormula::Defined(tfx::DefinedAtomic(defined)) => {
+                let defined = match defined {
+                    DefinedPlain::Constant(constant) => {
+                        fof::DefinedPlainTerm::Constant(constant.clone())
+                    }
+                    DefinedPlain::Function(f, args) => {
+                        fof::DefinedPlainTerm::Function(
+                            f.clone(),
+                            arguments(args)?,
+                        )
+                    }
+                };
+                fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(
+                    fof::DefinedAtomicTerm(defined),
+                ))
+            }
+            tfx::AtomicFormula::System(SystemAtomic::Constant(constant)) => {
+                fof::FunctionTerm::System(fof::SystemTerm::Constant(
+                    constant.clone(),
+                ))
+            }
+            tfx::AtomicFormula::System(SystemAtomic::Function(f, args)) => {
+                fof::FunctionTerm::System(fof::SystemTerm::Function(
+                    f.clone(),
+                    arguments(args)?,
+                ))
+            }
+        })
+    }
+
+    fn defined<'a>(defined: &DefinedTerm<'a>) -> fof::Term<'a> {
+        fof::Term::Function(Box::new(fof::FunctionTerm::Defined(
+            fof::DefinedTerm::Defined(defined.clone()),
+        )))
+    }
+
+    fn logic<'a>(formula: &tfx::LogicFormula<'a>) -> Option<fof::Term<'a>> {
+        match formula {
+            tfx::LogicFormula::Unitary(unitary) => unitary_formula(unitary),
+            _ => None,
+        }
+    }
+
+    fn unitary_formula<'a>(
+        formula: &tfx::UnitaryFormula<'a>,
+    ) -> Option<fof::Term<'a>> {
+        match formula {
+            tfx::UnitaryFormula::Atomic(atom) => {
+                Some(fof::Term::Function(Box::new(atomic(atom)?)))
+            }
+            tfx::UnitaryFormula::Variable(variable) => {
+                Some(fof::Term::Variable(variable.clone()))
+            }
+            tfx::UnitaryFormula::Logic(formula) => logic(formula),
+            tfx::UnitaryFormula::Quantified(_) => None,
+        }
+    }
+
+    pub(super) fn term<'a>(term: &tfx::Term<'a>) -> Option<fof::Term<'a>> {
+        match term {
+            tfx::Term::Logic(formula) => logic(formula),
+            tfx::Term::Defined(term) => Some(defined(term)),
+        }
+    }
+
+    pub(crate) fn unitary<'a>(
+        term: &tfx::UnitaryTerm<'a>,
+    ) -> Option<Box<fof::Term<'a>>> {
+        let term = match term {
+            tfx::UnitaryTerm::Atomic(atom) => {
+                fof::Term::Function(Box::new(atomic(atom)?))
+            }
+            tfx::UnitaryTerm::Defined(term) => defined(term),
+            tfx::UnitaryTerm::Variable(variable) => {
+                fof::Term::Variable(variable.clone())
+            }
+            tfx::UnitaryTerm::Logic(formula) => logic(formula)?,
+        };
+        Some(Box::new(term))
+    }
+
+    pub(crate) fn atom<'a>(
+        atom: &tfx::AtomicFormula<'a>,
+    ) -> Option<fof::AtomicFormula<'a>> {
+        Some(match atomic(atom)? {
+            fof::FunctionTerm::Plain(plain) => {
+                fof::AtomicFormula::Plain(fof::PlainAtomicFormula(plain))
+            }
+            fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(
+                fof::DefinedAtomicTerm(defined),
+            )) => {
+                fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Plain(
+                    fof::DefinedPlainFormula(defined),
+                ))
+            }
+            fof::FunctionTerm::System(system) => {
+                fof::AtomicFormula::System(fof::SystemAtomicFormula(system))
+            }
+            fof::FunctionTerm::Defined(fof::DefinedTerm::Defined(_)) => {
+                return None
+            }
+        })
+    }
+}
+
+/// bound variables renamed apart within one formula
+#[derive(Default)]
+struct Renaming<'a> {
+    used: BTreeSet<Variable<'a>>,
+    scope: Vec<(Variable<'a>, Variable<'a>)>,
+}
+
+impl<'a> Renaming<'a> {
+    fn bindings(&self) -> Bindings<'a> {
+        self.scope
+            .iter()
+            .filter(|(from, to)| from != to)
+            .map(|(from, to)| (from.clone(), fof::Term::Variable(to.clone())))
+            .collect()
+    }
+
+    fn literal(&self, literal: cnf::Literal<'a>) -> Form<'a> {
+        if self.scope.iter().all(|(from, to)| from == to) {
+            return Form::Literal(literal);
+        }
+        let bindings = self.bindings();
+        Form::Literal(subst::apply_literal(&literal, &|v| {
+            bindings.get(v).cloned()
+        }))
+    }
+}
+
+/// a clausifier for FOF and TFX problems
+///
+/// Formulas are put into negation normal form, quantifiers are miniscoped, existentials are replaced by Skolem terms from a `Skolemiser` and universals are dropped.
+/// Distributing `|` over `&` can produce exponentially many clauses, so large subformulas are optionally named by fresh `sP` predicates.
+/// Conjectures are negated.
+/// TFX formulas must be first-order, and their types are erased.
+pub struct Clausifier<'a> {
+    skolemiser: Skolemiser<'a>,
+    /// name a subformula when distributing would produce more clauses than this, or never name if `None`
+    pub definitions: Option<usize>,
+}
+
+impl<'a> Clausifier<'a> {
+    /// a clausifier storing fresh symbols in `arena`
+    pub fn new(arena: &'a Arena) -> Self {
+        let skolemiser = Skolemiser::new(arena);
+        let definitions = Some(32);
+        Self {
+            skolemiser,
+            definitions,
+        }
+    }
+
+    /// the fresh symbol generator, for reserving symbols used elsewhere
+    pub fn fresh(&mut self) -> &mut Fresh<'a> {
+        self.skolemiser.fresh()
+    }
+
+    /// the Skolemiser, for choosing its mode and prefix or recovering the Skolem functions introduced
+    pub fn skolemiser(&mut self) -> &mut Skolemiser<'a> {
+        &mut self.skolemiser
+    }
+
+    /// clausify an annotated formula
+    ///
+    /// CNF is returned unchanged and TFX type declarations produce no clauses.
+    /// Symbols in `annotated` are avoided, but symbols in other formulas of the same problem must be reserved with `fresh`: see `clausify_problem`.
+    pub fn clausify(
+        &mut self,
+        annotated: &AnnotatedFormula<'a>,
+    ) -> core::result::Result<Vec<CnfAnnotated<'a>>, ClausifyError<'a>> {
+        self.fresh().avoid_formula(annotated);
+        let name = annotated.name();
+        let role = annotated.role();
+        let conjecture = (role.0).0 == "conjecture";
+        let positive = !conjecture;
+        let mut renaming = Renaming::default();
+        let form = match annotated {
+            AnnotatedFormula::Cnf(cnf) => return Ok(vec![(**cnf).clone()]),
+            AnnotatedFormula::Fof(fof) => {
+                let formula = &(fof.0.formula).0;
+                renaming.used.extend(formula.free_variables());
+                let nnf = if positive {
+                    formula.nnf()
+                } else {
+                    formula.negated_nnf()
+                };
+                self.fof(&nnf, &mut renaming)
+            }
+            AnnotatedFormula::Tfx(tfx) => {
+                let formula = match &*tfx.0.formula {
+                    tfx::Formula::Logic(formula) => formula,
+                    tfx::Formula::AtomTyping(_) => return Ok(vec![]),
+                };
+                renaming.used.extend(formula.free_variables());
+                let nnf = if positive {
+                    formula.nnf()
+                } else {
+                    formula.negated_nnf()
+                };
+                self.tfx(&nnf, &mut renaming).ok_or_else(|| {
+                    ClausifyError::NotFirstOrder(name.clone())
+                })?
+            }
+        };
+
+        let formula = fof::Formula(form.miniscope().formula());
+        let formula = self.skolemiser.skolemise(&formula);
+        let form = self.fof(&formula.0, &mut Renaming::default());
+        let mut definitions = vec![];
+        let mut clauses = self.clauses(form, &mut definitions);
+        clauses.extend(definitions);
+
+        let (role, parent) = if conjecture {
+            let parent = general_name(name);
+            let parent = inference("negated_conjecture", "cth", parent);
+            (FormulaRole(LowerWord("negated_conjecture")), parent)
+        } else {
+            (role.clone(), general_name(name))
+        };
+        let source = Source(inference("clausify", "esa", parent));
+        let clauses = clauses
+            .into_iter()
+            .filter_map(simplify)
+            .map(|clause| {
+                let formula =
+                    cnf::Formula::Disjunction(cnf::Disjunction(clause));
+                let name = Name::AtomicWord(word(self.fresh().symbol("c")));
+                let annotations = (source.clone(), OptionalInfo(None));
+                CnfAnnotated(Annotated {
+                    name,
+                    role: role.clone(),
+                    formula: Box::new(formula),
+                    annotations: Annotations(Some(Box::new(annotations))),
+                })
+            })
+            .collect();
+        Ok(clauses)
+    }
+
+    fn bind(
+        &mut self,
+        renaming: &mut Renaming<'a>,
+        variable: &Variable<'a>,
+    ) -> Variable<'a> {
+        let renamed = if renaming.used.contains(variable) {
+            self.fresh().variable("X")
+        } else {
+            variable.clone()
+        };
+        renaming.used.insert(renamed.clone());
+        renaming.scope.push((variable.clone(), renamed.clone()));
+        renamed
+    }
+
+    fn quantified<I: Iterator<Item = Variable<'a>>>(
+        quantifier: fof::Quantifier,
+        variables: I,
+        body: Form<'a>,
+    ) -> Form<'a> {
+        let variables: Vec<_> = variables.collect();
+        variables.into_iter().rev().fold(body, |body, variable| {
+            Form::Quantified(quantifier, variable, Box::new(body))
+        })
+    }
+
+    fn fof(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Form<'a> {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::And(
+                and,
+            ))) => Form::And(
+                and.0
+                    .iter()
+                    .map(|unit| self.fof_unit(unit, renaming))
+                    .collect(),
+            ),
+            LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::Or(
+                or,
+            ))) => Form::Or(
+                or.0.iter()
+                    .map(|unit| self.fof_unit(unit, renaming))
+                    .collect(),
+            ),
+            LogicFormula::Binary(BinaryFormula::Nonassoc(_)) => {
+                unreachable!("not in negation normal form")
+            }
+            LogicFormula::Unary(unary) => self.fof_unary(unary, renaming),
+            LogicFormula::Unitary(unitary) => {
+                self.fof_unitary(unitary, renaming)
+            }
+        }
+    }
+
+    fn fof_unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Form<'a> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => {
+                self.fof_unitary(unitary, renaming)
+            }
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary, renaming),
+        }
+    }
+
+    fn fof_unary(
+        &mut self,
+        formula: &fof::UnaryFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Form<'a> {
+        match formula {
+            fof::UnaryFormula::Unary(_, formula) => match &**formula {
+                fof::UnitFormula::Unitary(fof::UnitaryFormula::Atomic(
+                    atom,
+                )) => renaming
+                    .literal(cnf::Literal::NegatedAtomic((**atom).clone())),
+                _ => unreachable!("not in negation normal form"),
+            },
+            fof::UnaryFormula::InfixUnary(infix) => {
+                renaming.literal(cnf::Literal::Infix(infix.clone()))
+            }
+        }
+    }
+
+    fn fof_unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Form<'a> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let depth = renaming.scope.len();
+                let variables: Vec<_> = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| self.bind(renaming, variable))
+                    .collect();
+                let body = self.fof_unit(&quantified.formula, renaming);
+                renaming.scope.truncate(depth);
+                Self::quantified(
+                    quantified.quantifier,
+                    variables.into_iter(),
+                    body,
+                )
+            }
+            fof::UnitaryFormula::Atomic(atom) => {
+                renaming.literal(cnf::Literal::Atomic((**atom).clone()))
+            }
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.fof(formula, renaming)
+            }
+        }
+    }
+
+    fn tfx(
+        &mut self,
+        formula: &tfx::LogicFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Option<Form<'a>> {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        Some(match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::And(
+                and,
+            ))) => Form::And(
+                and.0
+                    .iter()
+                    .map(|unit| self.tfx_unit(unit, renaming))
+                    .collect::<Option<_>>()?,
+            ),
+            LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::Or(
+                or,
+            ))) => Form::Or(
+                or.0.iter()
+                    .map(|unit| self.tfx_unit(unit, renaming))
+                    .collect::<Option<_>>()?,
+            ),
+            LogicFormula::Binary(BinaryFormula::Nonassoc(_)) => {
+                unreachable!("not in negation normal form")
+            }
+            LogicFormula::Unary(unary) => self.tfx_unary(unary, renaming)?,
+            LogicFormula::Unitary(unitary) => {
+                self.tfx_unitary(unitary, renaming)?
+            }
+            LogicFormula::DefinedInfix(infix) => {
+                self.tfx_equality(infix, renaming)?
+            }
+        })
+    }
+
+    fn tfx_unit(
+        &mut self,
+        formula: &tfx::UnitFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Option<Form<'a>> {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => {
+                self.tfx_unitary(unitary, renaming)
+            }
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary(unary, renaming),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_equality(infix, renaming)
+            }
+        }
+    }
+
+    fn tfx_equality(
+        &mut self,
+        infix: &tfx::DefinedInfix<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Option<Form<'a>> {
+        let infix = fof::DefinedInfixFormula {
+            left: tfx_terms::unitary(&infix.left)?,
+            op: infix.op,
+            right: tfx_terms::unitary(&infix.right)?,
+        };
+        let atom = fof::AtomicFormula::Defined(
+            fof::DefinedAtomicFormula::Infix(infix),
+        );
+        Some(renaming.literal(cnf::Literal::Atomic(atom)))
+    }
+
+    fn tfx_unary(
+        &mut self,
+        formula: &tfx::UnaryFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Option<Form<'a>> {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => match &*prefix.formula {
+                tfx::PreunitFormula::Unitary(tfx::UnitaryFormula::Atomic(
+                    atom,
+                )) => {
+                    let atom = tfx_terms::atom(atom)?;
+                    Some(renaming.literal(cnf::Literal::NegatedAtomic(atom)))
+                }
+                _ => None,
+            },
+            tfx::UnaryFormula::Infix(infix) => {
+                let infix = fof::InfixUnary {
+                    left: tfx_terms::unitary(&infix.left)?,
+                    op: infix.op,
+                    right: tfx_terms::unitary(&infix.right)?,
+                };
+                Some(renaming.literal(cnf::Literal::Infix(infix)))
+            }
+        }
+    }
+
+    fn tfx_unitary(
+        &mut self,
+        formula: &tfx::UnitaryFormula<'a>,
+        renaming: &mut Renaming<'a>,
+    ) -> Option<Form<'a>> {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let depth = renaming.scope.len();
+                let mut variables = vec![];
+                for variable in &quantified.bound.0 {
+                    let variable = match variable {
+                        tfx::Variable::Typed(typed) => {
+                            if is_kind(&typed.typ) {
+                                return None;
+                            }
+                            &typed.variable
+                        }
+                        tfx::Variable::Untyped(variable) => variable,
+                    };
+                    variables.push(self.bind(renaming, variable));
+                }
+                let body = self.tfx_unit(&quantified.formula, renaming)?;
+                renaming.scope.truncate(depth);
+                Some(Self::quantified(
+                    quantified.quantifier,
+                    variables.into_iter(),
+                    body,
+                ))
+            }
+            tfx::UnitaryFormula::Atomic(atom) => {
+                let atom = tfx_terms::atom(atom)?;
+                Some(renaming.literal(cnf::Literal::Atomic(atom)))
+            }
+            tfx::UnitaryFormula::Variable(_) => None,
+            tfx::UnitaryFormula::Logic(formula) => self.tfx(formula, renaming),
+        }
+    }
+
+    /// convert a Skolemised formula to clauses, naming subformulas if necessary
+    fn clauses(
+        &mut self,
+        form: Form<'a>,
+        definitions: &mut Vec<Clause<'a>>,
+    ) -> Vec<Clause<'a>> {
+        match form {
+            Form::Literal(literal) => vec![vec![literal]],
+            Form::And(forms) => forms
+                .into_iter()
+                .flat_map(|form| self.clauses(form, definitions))
+                .collect(),
+            Form::Or(forms) => {
+                let mut parts: Vec<Vec<Clause<'a>>> = forms
+                    .into_iter()
+                    .map(|form| self.clauses(form, definitions))
+                    .collect();
+                if let Some(limit) = self.definitions {
+                    let size = |parts: &[Vec<Clause>]| {
+                        parts.iter().fold(1usize, |product, part| {
+                            product.saturating_mul(part.len())
+                        })
+                    };
+                    while size(&parts) > limit {
+                        let largest = (0..parts.len())
+                            .rev()
+                            .max_by_key(|i| parts[*i].len())
+                            .unwrap();
+                        if parts[largest].len() <= 1 {
+                            break;
+                        }
+                        let named = mem::take(&mut parts[largest]);
+                        parts[largest] =
+                            vec![vec![self.name(named, definitions)]];
+                    }
+                }
+                parts.into_iter().fold(vec![vec![]], |clauses, part| {
+                    let mut product = vec![];
+                    for clause in &clauses {
+                        for other in &part {
+                            let mut clause = clause.clone();
+                            clause.extend(other.iter().cloned());
+                            product.push(clause);
+                        }
+                    }
+                    product
+                })
+            }
+            Form::Quantified(fof::Quantifier::Forall, _, form) => {
+                self.clauses(*form, definitions)
+            }
+            Form::Quantified(fof::Quantifier::Exists, _, _) => {
+                unreachable!("not skolemised")
+            }
+        }
+    }
+
+    /// introduce `sP(X, ...)` implying the conjunction of `clauses`, returning the atom
+    fn name(
+        &mut self,
+        clauses: Vec<Clause<'a>>,
+        definitions: &mut Vec<Clause<'a>>,
+    ) -> cnf::Literal<'a> {
+        let mut variables = vec![];
+        for literal in clauses.iter().flatten() {
+            for variable in literal.free_variables() {
+                if !variables.contains(&variable) {
+                    variables.push(variable);
+                }
+            }
+        }
+        let functor = self.fresh().functor("sP");
+        let atom = fof::AtomicFormula::Plain(fof::PlainAtomicFormula(
+            application(functor, &variables),
+        ));
+        for clause in clauses {
+            let mut definition =
+                vec![cnf::Literal::NegatedAtomic(atom.clone())];
+            definition.extend(clause);
+            definitions.push(definition);
+        }
+        cnf::Literal::Atomic(atom)
+    }
+}
+
+/// clausify a problem, reserving every symbol in the problem first
+pub fn clausify_problem<'a>(
+    arena: &'a Arena,
+    problem: &[AnnotatedFormula<'a>],
+) -> core::result::Result<Vec<CnfAnnotated<'a>>, ClausifyError<'a>> {
+    let mut clausifier = Clausifier::new(arena);
+    for annotated in problem {
+        clausifier.fresh().avoid_formula(annotated);
+    }
+    let mut clauses = vec![];
+    for annotated in problem {
+        clauses.extend(clausifier.clausify(annotated)?);
+    }
+    Ok(clauses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+    use alloc::string::{String, ToString};
+
+    fn clausify(input: &[u8], definitions: Option<usize>) -> Vec<String> {
+        let arena = Arena::new();
+        let problem = problem(input);
+        let mut clausifier = Clausifier::new(&arena);
+        clausifier.definitions = definitions;
+        for annotated in &problem {
+            clausifier.fresh().avoid_formula(annotated);
+        }
+        let mut clauses = vec![];
+        for annotated in &problem {
+            let result = clausifier.clausify(annotated).unwrap();
+            clauses.extend(result.iter().map(|clause| clause.to_string()));
+        }
+        clauses
+    }
+
+    #[test]
+    fn test_clausify() {
+        assert_eq!(
+            clausify(
+                b"fof(a, axiom, ![X]: (p(X) => ?[Y]: q(X, Y))).",
+                None
+            ),
+            ["cnf(c0,axiom,~p(X)|q(X,sK0(X)),inference(clausify,[status(esa)],[a]))."]
+        );
+        assert_eq!(
+            clausify(b"fof(g, conjecture, ?[X]: (p(X) & q)).", None),
+            [
+                "cnf(c0,negated_conjecture,~p(X)|~q,inference(clausify,[status(esa)],[inference(negated_conjecture,[status(cth)],[g])]))."
+            ]
+        );
+        assert_eq!(
+            clausify(b"fof(1, axiom, p <=> q). cnf(c0, axiom, r).", None),
+            [
+                "cnf(c1,axiom,~p|q,inference(clausify,[status(esa)],[1])).",
+                "cnf(c2,axiom,p|~q,inference(clausify,[status(esa)],[1])).",
+                "cnf(c0,axiom,r).",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quantifiers() {
+        let clauses = |input| {
+            clausify(input, None)
+                .iter()
+                .map(|clause| clause.split(',').nth(2).unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            clauses(b"fof(a, axiom, ![X]: ?[Y]: (p(X) & q(Y)))."),
+            ["p(X)", "q(sK0)"]
+        );
+        assert_eq!(
+            clauses(b"fof(a, axiom, (![X]: p(X)) | ![X]: q(X))."),
+            ["p(X)|q(X0)"]
+        );
+        assert_eq!(
+            clauses(b"fof(a, axiom, ~(a = b) & ($true | p) & ($false | q))."),
+            ["a!=b", "q"]
+        );
+        assert_eq!(clauses(b"fof(a, axiom, $false | $false)."), ["$false"]);
+    }
+
+    #[test]
+    fn test_definitions() {
+        let input = b"fof(a, axiom, (p1 & p2 & p3) | (q1 & q2 & q3)).";
+        assert_eq!(clausify(input, None).len(), 9);
+        let clauses: Vec<String> = clausify(input, Some(4))
+            .iter()
+            .map(|clause| clause.split(',').nth(2).unwrap().to_string())
+            .collect();
+        assert_eq!(
+            clauses,
+            ["sP0|q1", "sP0|q2", "sP0|q3", "~sP0|p1", "~sP0|p2", "~sP0|p3"]
+        );
+        let clauses: Vec<String> = clausify(
+            b"fof(a, axiom, ![X]: ((p(X) & q) | (r & s)) ).",
+            Some(1),
+        )
+        .iter()
+        .map(|clause| clause.split(',').nth(2).unwrap().to_string())
+        .collect();
+        assert_eq!(
+            clauses,
+            [
+                "sP0(X)|sP1",
+                "~sP0(X)|p(X)",
+                "~sP0(X)|q",
+                "~sP1|r",
+                "~sP1|s"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tfx() {
+        let clauses = clausify(
+            b"
+tff(f, type, f: $int > $int).
+tff(a, axiom, ![X: $int]: ?[Y: $int]: (X = f(Y) | $less(X, 1))).
+",
+            None,
+        );
+        assert_eq!(
+            clauses,
+            ["cnf(c0,axiom,X=f(sK0(X))|$less(X,1),inference(clausify,[status(esa)],[a]))."]
+        );
+        let arena = Arena::new();
+        let problem = problem(b"tff(a, axiom, ![B: $o]: (B | p)).");
+        assert_eq!(
+            clausify_problem(&arena, &problem).unwrap_err().to_string(),
+            "a: not first-order"
+        );
+    }
+
+    #[test]
+    fn test_skolemiser() {
+        let arena = Arena::new();
+        let annotated = &problem(b"fof(a, axiom, ![X]: ?[Y]: p(X, Y)).")[0];
+        let mut clausifier = Clausifier::new(&arena);
+        clausifier.skolemiser().prefix = "esk";
+        let clauses = clausifier.clausify(annotated).unwrap();
+        assert_eq!(clauses[0].0.formula.to_string(), "p(X,esk0(X))");
+        let introduced = clausifier.skolemiser().introduced();
+        assert_eq!(introduced.len(), 1);
+        assert_eq!(introduced[0].0.to_string(), "esk0");
+        assert_eq!(introduced[0].1, 1);
+    }
+}
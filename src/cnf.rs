@@ -37,7 +37,7 @@ impl<'a> LiteralTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for LiteralTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(fof::DefinedInfixFormulaTail::parse, LiteralTail::Equal),
             map(fof::InfixUnaryTail::parse, LiteralTail::NotEqual),
@@ -56,7 +56,7 @@ pub enum Literal<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Literal<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 preceded(
@@ -104,7 +104,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Literal<'a> {
 pub struct Disjunction<'a>(pub Vec<Literal<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Disjunction<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag("|"), ignored),
@@ -125,7 +125,7 @@ pub enum Formula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Formula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(parens, Self::Parenthesised),
             map(Disjunction::parse, Self::Disjunction),
@@ -0,0 +1,381 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+use nom::branch::alt;
+use nom::bytes::streaming::{tag, take_until};
+use nom::character::streaming::not_line_ending;
+use nom::combinator::{map, map_opt, success, value};
+use nom::sequence::{delimited, pair, preceded};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::whitespace;
+use crate::top::TPTPInput;
+use crate::{Error, Parse, Result};
+
+/// the kind of a comment, given by the `$`s after its opening delimiter
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CommentKind {
+    /// an ordinary comment
+    Ordinary,
+    /// [`defined_comment`](http://tptp.org/TPTP/SyntaxBNF.html#defined_comment): `%$` or `/*$`
+    Defined,
+    /// [`system_comment`](http://tptp.org/TPTP/SyntaxBNF.html#system_comment): `%$$` or `/*$$`
+    System,
+}
+
+impl CommentKind {
+    fn dollars(self) -> &'static str {
+        match self {
+            CommentKind::Ordinary => "",
+            CommentKind::Defined => "$",
+            CommentKind::System => "$$",
+        }
+    }
+}
+
+fn kind<'a, E: Error<'a>>(x: &'a [u8]) -> Result<'a, CommentKind, E> {
+    alt((
+        value(CommentKind::System, tag("$$")),
+        value(CommentKind::Defined, tag("$")),
+        success(CommentKind::Ordinary),
+    ))(x)
+}
+
+fn text<'a, E: Error<'a>>(x: &'a [u8]) -> Result<'a, &'a str, E> {
+    map_opt(not_line_ending, |text| str::from_utf8(text).ok())(x)
+}
+
+fn block_text<'a, E: Error<'a>>(x: &'a [u8]) -> Result<'a, &'a str, E> {
+    map_opt(take_until("*/"), |text| str::from_utf8(text).ok())(x)
+}
+
+/// a `% line` comment ended by the end of input rather than a line ending
+fn final_line_comment(x: &[u8]) -> Option<Comment<'_>> {
+    let x = x.strip_prefix(b"%")?;
+    let (kind, x) = if let Some(x) = x.strip_prefix(b"$$") {
+        (CommentKind::System, x)
+    } else if let Some(x) = x.strip_prefix(b"$") {
+        (CommentKind::Defined, x)
+    } else {
+        (CommentKind::Ordinary, x)
+    };
+    if x.iter().any(|byte| matches!(byte, b'\n' | b'\r')) {
+        return None;
+    }
+    let text = str::from_utf8(x).ok()?;
+    Some(Comment {
+        kind,
+        block: false,
+        text,
+    })
+}
+
+/// [`comment`](http://tptp.org/TPTP/SyntaxBNF.html#comment), including defined and system comments
+///
+/// A `% line` comment does not include its line ending.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Comment<'a> {
+    pub kind: CommentKind,
+    /// whether this is a `/* block */` rather than a `% line`
+    pub block: bool,
+    /// the text between the delimiters, after any `$`s
+    pub text: &'a str,
+}
+
+impl<'a> fmt::Display for Comment<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dollars = self.kind.dollars();
+        if self.block {
+            write!(f, "/*{}{}*/", dollars, self.text)
+        } else {
+            write!(f, "%{}{}", dollars, self.text)
+        }
+    }
+}
+
+impl<'a, E: Error<'a>> Parse<'a, E> for Comment<'a> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
+        alt((
+            map(preceded(tag("%"), pair(kind, text)), |(kind, text)| Self {
+                kind,
+                block: false,
+                text,
+            }),
+            map(
+                delimited(tag("/*"), pair(kind, block_text), tag("*/")),
+                |(kind, text)| Self {
+                    kind,
+                    block: true,
+                    text,
+                },
+            ),
+        ))(x)
+    }
+}
+
+/// a `TPTP_input` with the comments attached to it
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Commented<'a> {
+    /// comments immediately before the input, with no blank line between
+    pub leading: Vec<Comment<'a>>,
+    pub input: TPTPInput<'a>,
+    /// a comment after the input on the same line
+    pub trailing: Option<Comment<'a>>,
+}
+
+impl<'a> fmt::Display for Commented<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for comment in &self.leading {
+            writeln!(f, "{}", comment)?;
+        }
+        write!(f, "{}", self.input)?;
+        if let Some(comment) = &self.trailing {
+            write!(f, " {}", comment)?;
+        }
+        Ok(())
+    }
+}
+
+/// an item of a TPTP problem, including comments and blank lines
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Item<'a> {
+    /// a comment not attached to an input
+    Comment(Comment<'a>),
+    /// a run of blank lines
+    Blank(usize),
+    /// an input and its comments
+    Input(Commented<'a>),
+}
+
+/// iterator returning `Item`s from a byte slice, like `TPTPIterator` but keeping comments and blank lines
+///
+/// Comments immediately before an input are attached to it, as is a comment after it on the same line.
+/// Other comments are returned as items of their own.
+/// Comments inside an input, such as `p % note` on a line of a formula split over several, are skipped by the parser and not returned.
+pub struct CommentedIterator<'a, E> {
+    /// the current position of the iterator in the slice
+    pub remaining: &'a [u8],
+    /// whether anything has been consumed yet
+    started: bool,
+    /// the last input, held back in case a trailing comment follows
+    held: Option<Commented<'a>>,
+    /// comments which may lead the next input
+    comments: Vec<Comment<'a>>,
+    /// items ready to be returned
+    ready: VecDeque<core::result::Result<Item<'a>, E>>,
+}
+
+impl<'a, E> CommentedIterator<'a, E> {
+    pub fn new(remaining: &'a [u8]) -> Self {
+        Self {
+            remaining,
+            started: false,
+            held: None,
+            comments: Vec::new(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    fn release_held(&mut self) {
+        if let Some(held) = self.held.take() {
+            self.ready.push_back(Ok(Item::Input(held)));
+        }
+    }
+
+    fn release_comments(&mut self) {
+        for comment in self.comments.drain(..) {
+            self.ready.push_back(Ok(Item::Comment(comment)));
+        }
+    }
+
+    fn release(&mut self) {
+        self.release_held();
+        self.release_comments();
+    }
+
+    /// attach `comment` to the held input, or keep it for the next one
+    fn comment(&mut self, comment: Comment<'a>) {
+        self.started = true;
+        match &mut self.held {
+            Some(held) => {
+                held.trailing = Some(comment);
+                self.release_held();
+            }
+            None => self.comments.push(comment),
+        }
+    }
+}
+
+impl<'a, E: Error<'a>> Iterator for CommentedIterator<'a, E> {
+    type Item = core::result::Result<Item<'a>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.ready.is_empty() {
+            if self.remaining.is_empty() {
+                self.release();
+                break;
+            }
+
+            if let Ok((remaining, ())) = whitespace::<E>(self.remaining) {
+                let consumed =
+                    &self.remaining[..self.remaining.len() - remaining.len()];
+                let newlines =
+                    consumed.iter().filter(|byte| **byte == b'\n').count();
+                let blank = if self.started {
+                    newlines.saturating_sub(1)
+                } else {
+                    newlines
+                };
+                self.remaining = remaining;
+                self.started = true;
+                if newlines > 0 {
+                    self.release_held();
+                }
+                if blank > 0 {
+                    self.release_comments();
+                    self.ready.push_back(Ok(Item::Blank(blank)));
+                }
+                continue;
+            }
+
+            match <Comment as Parse<E>>::parse(self.remaining) {
+                Ok((remaining, comment)) => {
+                    self.remaining = remaining;
+                    self.comment(comment);
+                    continue;
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    if let Some(comment) = final_line_comment(self.remaining) {
+                        self.remaining = &[];
+                        self.comment(comment);
+                        continue;
+                    }
+                    self.release();
+                    break;
+                }
+                Err(_) => {}
+            }
+
+            match TPTPInput::parse(self.remaining) {
+                Ok((remaining, input)) => {
+                    self.remaining = remaining;
+                    self.started = true;
+                    self.release_held();
+                    let leading = core::mem::take(&mut self.comments);
+                    self.held = Some(Commented {
+                        leading,
+                        input,
+                        trailing: None,
+                    });
+                }
+                Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => {
+                    self.release();
+                    self.ready.push_back(Err(e));
+                }
+                Err(nom::Err::Incomplete(_)) => {
+                    self.release();
+                    break;
+                }
+            }
+        }
+        self.ready.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_comment() {
+        for (input, kind, block, text) in [
+            ("% plain\n", CommentKind::Ordinary, false, " plain"),
+            ("%$ defined\n", CommentKind::Defined, false, " defined"),
+            ("%$$ system\n", CommentKind::System, false, " system"),
+            ("/*$$ block\n */", CommentKind::System, true, " block\n "),
+        ] {
+            let (_, comment) = <Comment as Parse<()>>::parse(input.as_bytes())
+                .expect("parse error");
+            assert_eq!(comment.kind, kind);
+            assert_eq!(comment.block, block);
+            assert_eq!(comment.text, text);
+            assert!(input.starts_with(&comment.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_iterator() {
+        let problem = b"% header
+
+%$ leading
+fof(a,axiom,p). % trailing
+/* before b */ fof(b,axiom,q).
+
+
+%$$ orphan
+
+fof(c,axiom,r).
+";
+        let items: Vec<_> = CommentedIterator::<()>::new(problem)
+            .map(|item| item.expect("parse error"))
+            .collect();
+        let shape: Vec<_> = items
+            .iter()
+            .map(|item| match item {
+                Item::Comment(comment) => comment.to_string(),
+                Item::Blank(blank) => blank.to_string(),
+                Item::Input(commented) => commented.to_string(),
+            })
+            .collect();
+        assert_eq!(
+            shape,
+            [
+                "% header",
+                "1",
+                "%$ leading\nfof(a,axiom,p). % trailing",
+                "/* before b */\nfof(b,axiom,q).",
+                "2",
+                "%$$ orphan",
+                "1",
+                "fof(c,axiom,r).",
+            ]
+        );
+        match &items[2] {
+            Item::Input(commented) => {
+                assert_eq!(commented.leading[0].kind, CommentKind::Defined)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_no_final_newline() {
+        let mut items = CommentedIterator::<()>::new(b"fof(a,axiom,p). % end");
+        match items.next() {
+            Some(Ok(Item::Input(commented))) => {
+                assert_eq!(commented.to_string(), "fof(a,axiom,p). % end")
+            }
+            _ => unreachable!(),
+        }
+        assert!(items.next().is_none());
+        assert!(items.remaining.is_empty());
+
+        let items: Vec<_> = CommentedIterator::<()>::new(b"%$$ last")
+            .map(|item| item.expect("parse error"))
+            .collect();
+        match &items[..] {
+            [Item::Comment(comment)] => {
+                assert_eq!(comment.kind, CommentKind::System);
+                assert_eq!(comment.text, " last");
+            }
+            _ => unreachable!(),
+        }
+    }
+}
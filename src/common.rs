@@ -21,11 +21,11 @@ fn to_str(bytes: &[u8]) -> &str {
 }
 
 fn is_lower_alpha(c: u8) -> bool {
-    (b'a'..=b'z').contains(&c)
+    c.is_ascii_lowercase()
 }
 
 fn is_upper_alpha(c: u8) -> bool {
-    (b'A'..=b'Z').contains(&c)
+    c.is_ascii_uppercase()
 }
 
 fn is_alphanumeric(c: u8) -> bool {
@@ -90,7 +90,7 @@ pub fn alphanumeric<'a, E: Error<'a>>(x: &'a [u8]) -> Result<'a, &'a [u8], E> {
 pub struct Integer<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Integer<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             recognize(preceded(
                 opt(one_of("+-")),
@@ -107,7 +107,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Integer<'a> {
 pub struct Rational<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Rational<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             recognize(tuple((
                 Integer::parse,
@@ -125,8 +125,8 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Rational<'a> {
 pub struct Real<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Real<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
-        fn exponent_part<'a, E: Error<'a>>(x: &'a [u8]) -> Result<(), E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
+        fn exponent_part<'a, E: Error<'a>>(x: &'a [u8]) -> Result<'a, (), E> {
             preceded(
                 one_of("eE"),
                 preceded(opt(one_of("+-")), value((), digit1)),
@@ -155,7 +155,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Real<'a> {
 pub struct LowerWord<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for LowerWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(recognize(preceded(lower_alpha1, alphanumeric)), |w| {
             Self(to_str(w))
         })(x)
@@ -168,7 +168,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for LowerWord<'a> {
 pub struct UpperWord<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UpperWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(recognize(preceded(upper_alpha1, alphanumeric)), |w| {
             Self(to_str(w))
         })(x)
@@ -182,7 +182,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for UpperWord<'a> {
 pub struct DollarWord<'a>(pub LowerWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DollarWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(preceded(tag("$"), LowerWord::parse), Self)(x)
     }
 }
@@ -194,7 +194,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DollarWord<'a> {
 pub struct DollarDollarWord<'a>(pub LowerWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DollarDollarWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(preceded(tag("$$"), LowerWord::parse), Self)(x)
     }
 }
@@ -209,7 +209,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DollarDollarWord<'a> {
 pub struct SingleQuoted<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SingleQuoted<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             delimited(
                 tag("'"),
@@ -228,7 +228,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for SingleQuoted<'a> {
 pub struct DistinctObject<'a>(pub &'a str);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DistinctObject<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             delimited(
                 tag("\""),
@@ -246,7 +246,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DistinctObject<'a> {
 pub struct AtomicSystemWord<'a>(pub DollarDollarWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicSystemWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DollarDollarWord::parse, Self)(x)
     }
 }
@@ -257,7 +257,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AtomicSystemWord<'a> {
 pub struct SystemFunctor<'a>(pub AtomicSystemWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SystemFunctor<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AtomicSystemWord::parse, Self)(x)
     }
 }
@@ -268,7 +268,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for SystemFunctor<'a> {
 pub struct SystemConstant<'a>(pub SystemFunctor<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SystemConstant<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(SystemFunctor::parse, Self)(x)
     }
 }
@@ -283,7 +283,7 @@ pub enum Number<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Number<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(Real::parse, Self::Real),
             map(Rational::parse, Self::Rational),
@@ -301,7 +301,7 @@ pub enum AtomicWord<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(LowerWord::parse, Self::Lower),
             map(SingleQuoted::parse, Self::SingleQuoted),
@@ -318,7 +318,7 @@ pub enum Name<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Name<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(AtomicWord::parse, Self::AtomicWord),
             map(Integer::parse, Self::Integer),
@@ -332,7 +332,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Name<'a> {
 pub struct Variable<'a>(pub UpperWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Variable<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(UpperWord::parse, Self)(x)
     }
 }
@@ -343,7 +343,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Variable<'a> {
 pub struct Functor<'a>(pub AtomicWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Functor<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AtomicWord::parse, Self)(x)
     }
 }
@@ -354,7 +354,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Functor<'a> {
 pub struct TypeFunctor<'a>(pub AtomicWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TypeFunctor<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AtomicWord::parse, Self)(x)
     }
 }
@@ -365,7 +365,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for TypeFunctor<'a> {
 pub struct Constant<'a>(pub Functor<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Constant<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(Functor::parse, Self)(x)
     }
 }
@@ -376,7 +376,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Constant<'a> {
 pub struct TypeConstant<'a>(pub TypeFunctor<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TypeConstant<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(TypeFunctor::parse, Self)(x)
     }
 }
@@ -387,7 +387,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for TypeConstant<'a> {
 pub struct AtomicDefinedWord<'a>(pub DollarWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicDefinedWord<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DollarWord::parse, Self)(x)
     }
 }
@@ -398,7 +398,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AtomicDefinedWord<'a> {
 pub struct DefinedFunctor<'a>(pub AtomicDefinedWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedFunctor<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AtomicDefinedWord::parse, Self)(x)
     }
 }
@@ -409,7 +409,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DefinedFunctor<'a> {
 pub struct DefinedType<'a>(pub AtomicDefinedWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AtomicDefinedWord::parse, Self)(x)
     }
 }
@@ -420,7 +420,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DefinedType<'a> {
 pub struct DefinedConstant<'a>(pub DefinedFunctor<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedConstant<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DefinedFunctor::parse, Self)(x)
     }
 }
@@ -434,7 +434,7 @@ pub enum DefinedTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(Number::parse, Self::Number),
             map(DistinctObject::parse, Self::Distinct),
@@ -451,7 +451,7 @@ pub enum UntypedAtom<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UntypedAtom<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(Constant::parse, Self::Constant),
             map(SystemConstant::parse, Self::System),
@@ -468,7 +468,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for UntypedAtom<'a> {
 pub struct UnaryConnective;
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnaryConnective {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         value(Self, tag("~"))(x)
     }
 }
@@ -482,7 +482,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for UnaryConnective {
 pub struct InfixEquality;
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixEquality {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         value(Self, tag("="))(x)
     }
 }
@@ -496,7 +496,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for InfixEquality {
 pub struct InfixInequality;
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixInequality {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         value(Self, tag("!="))(x)
     }
 }
@@ -528,7 +528,7 @@ pub enum NonassocConnective {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for NonassocConnective {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             value(Self::LRImplies, tag("=>")),
             value(Self::Equivalent, tag("<=>")),
@@ -555,7 +555,7 @@ pub enum AssocConnective {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AssocConnective {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((value(Self::And, tag("&")), value(Self::Or, tag("|"))))(x)
     }
 }
@@ -568,20 +568,20 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AssocConnective {
 pub struct DefinedInfixPred(pub InfixEquality);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfixPred {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(InfixEquality::parse, DefinedInfixPred)(x)
     }
 }
 
 pub(crate) fn parens<'a, E: Error<'a>, T: Parse<'a, E>>(
     x: &'a [u8],
-) -> Result<T, E> {
+) -> Result<'a, T, E> {
     delimited(tag("("), delimited(ignored, T::parse, ignored), tag(")"))(x)
 }
 
 pub(crate) fn brackets<'a, E: Error<'a>, T: Parse<'a, E>>(
     x: &'a [u8],
-) -> Result<T, E> {
+) -> Result<'a, T, E> {
     delimited(tag("["), delimited(ignored, T::parse, ignored), tag("]"))(x)
 }
 
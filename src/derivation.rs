@@ -0,0 +1,430 @@
+use alloc::collections::btree_map::Entry;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec;
+use alloc::vec::Vec;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::nnf::truth_value;
+use crate::source::SourceKind;
+use crate::top::*;
+use crate::utils::Separated;
+use crate::{cnf, fof, tfx};
+
+/// a structural problem found while building a `Derivation`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum DerivationError<'a> {
+    /// a formula refers to a parent that does not exist
+    #[display(fmt = "{}: unknown parent {}", node, parent)]
+    Dangling { node: Name<'a>, parent: Name<'a> },
+    /// two formulas have the same name
+    #[display(fmt = "duplicate name {}", _0)]
+    Duplicate(Name<'a>),
+    /// formulas which (transitively) derive themselves, in parent order
+    #[display(fmt = "cycle through {}", "Separated(',', _0)")]
+    Cycle(Vec<Name<'a>>),
+}
+
+/// a formula in a `Derivation`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node<'a> {
+    pub formula: AnnotatedFormula<'a>,
+    /// the parsed source, if any
+    pub source: Option<SourceKind<'a>>,
+    /// indices of parent nodes, without repeats
+    pub parents: Vec<usize>,
+}
+
+impl<'a> Node<'a> {
+    /// the name of the formula
+    pub fn name(&self) -> &Name<'a> {
+        self.formula.name()
+    }
+
+    /// whether this formula is `$false`, i.e. a refutation
+    ///
+    /// Parentheses are ignored, so E's `($false)` counts.
+    pub fn is_false(&self) -> bool {
+        match &self.formula {
+            AnnotatedFormula::Fof(fof) => fof_false(&(fof.0.formula).0),
+            AnnotatedFormula::Cnf(cnf) => match &*cnf.0.formula {
+                cnf::Formula::Disjunction(disjunction)
+                | cnf::Formula::Parenthesised(disjunction) => {
+                    match &disjunction.0[..] {
+                        [cnf::Literal::Atomic(atom)] => fof_false_atom(atom),
+                        _ => false,
+                    }
+                }
+            },
+            AnnotatedFormula::Tfx(tfx) => match &*tfx.0.formula {
+                tfx::Formula::Logic(logic) => tfx_false(logic),
+                tfx::Formula::AtomTyping(_) => false,
+            },
+        }
+    }
+}
+
+fn is_false_constant(constant: &DefinedConstant) -> bool {
+    truth_value(constant) == Some("false")
+}
+
+fn fof_false_atom(atom: &fof::AtomicFormula) -> bool {
+    match atom {
+        fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Plain(
+            fof::DefinedPlainFormula(fof::DefinedPlainTerm::Constant(
+                constant,
+            )),
+        )) => is_false_constant(constant),
+        _ => false,
+    }
+}
+
+fn fof_false(formula: &fof::LogicFormula) -> bool {
+    match formula {
+        fof::LogicFormula::Unitary(fof::UnitaryFormula::Atomic(atom)) => {
+            fof_false_atom(atom)
+        }
+        fof::LogicFormula::Unitary(fof::UnitaryFormula::Parenthesised(
+            formula,
+        )) => fof_false(formula),
+        _ => false,
+    }
+}
+
+fn tfx_false(formula: &tfx::LogicFormula) -> bool {
+    match formula {
+        tfx::LogicFormula::Unitary(tfx::UnitaryFormula::Atomic(
+            tfx::AtomicFormula::Defined(tfx::DefinedAtomic(
+                tfx::DefinedPlain::Constant(constant),
+            )),
+        )) => is_false_constant(constant),
+        tfx::LogicFormula::Unitary(tfx::UnitaryFormula::Logic(formula)) => {
+            tfx_false(formula)
+        }
+        _ => false,
+    }
+}
+
+/// a derivation graph, such as a TSTP proof
+///
+/// Nodes are annotated formulas in input order, edges go from a formula to the parents named in its inference source.
+/// References to formulas in other files, e.g. `file('x.p', ax1)`, are not edges.
+#[derive(Clone, Debug)]
+pub struct Derivation<'a> {
+    nodes: Vec<Node<'a>>,
+    index: BTreeMap<Name<'a>, usize>,
+    order: Option<Vec<usize>>,
+    errors: Vec<DerivationError<'a>>,
+}
+
+impl<'a> Derivation<'a> {
+    /// build a derivation from a stream of annotated formulas
+    ///
+    /// Problems are not fatal: dangling references are dropped and the first of several formulas with the same name is used.
+    /// See `errors` for what was found.
+    pub fn new<I: IntoIterator<Item = AnnotatedFormula<'a>>>(
+        formulas: I,
+    ) -> Self {
+        let mut nodes = vec![];
+        let mut index = BTreeMap::new();
+        let mut errors = vec![];
+        for formula in formulas {
+            let name = formula.name().clone();
+            match index.entry(name) {
+                Entry::Occupied(entry) => errors
+                    .push(DerivationError::Duplicate(entry.key().clone())),
+                Entry::Vacant(entry) => {
+                    entry.insert(nodes.len());
+                }
+            }
+            let source = formula.annotations().source().map(Source::kind);
+            nodes.push(Node {
+                formula,
+                source,
+                parents: vec![],
+            });
+        }
+
+        for node in &mut nodes {
+            let parents = match &node.source {
+                Some(source) => source.parents(),
+                None => continue,
+            };
+            for parent in parents {
+                match index.get(&parent) {
+                    Some(&parent) => {
+                        if !node.parents.contains(&parent) {
+                            node.parents.push(parent);
+                        }
+                    }
+                    None => errors.push(DerivationError::Dangling {
+                        node: node.name().clone(),
+                        parent,
+                    }),
+                }
+            }
+        }
+
+        let mut derivation = Self {
+            nodes,
+            index,
+            order: None,
+            errors,
+        };
+        derivation.sort();
+        derivation
+    }
+
+    /// topologically sort the nodes, preferring input order, or find a cycle
+    fn sort(&mut self) {
+        let mut waiting: Vec<usize> =
+            self.nodes.iter().map(|node| node.parents.len()).collect();
+        let mut children = vec![vec![]; self.nodes.len()];
+        for (child, node) in self.nodes.iter().enumerate() {
+            for parent in &node.parents {
+                children[*parent].push(child);
+            }
+        }
+
+        let mut ready: BTreeSet<usize> =
+            (0..self.nodes.len()).filter(|i| waiting[*i] == 0).collect();
+        let mut order = vec![];
+        while let Some(next) = ready.iter().next().copied() {
+            ready.remove(&next);
+            order.push(next);
+            for child in &children[next] {
+                waiting[*child] -= 1;
+                if waiting[*child] == 0 {
+                    ready.insert(*child);
+                }
+            }
+        }
+
+        if order.len() == self.nodes.len() {
+            self.order = Some(order);
+            return;
+        }
+
+        // every unsorted node has an unsorted parent: follow them until one repeats
+        let mut current = (0..self.nodes.len())
+            .find(|i| waiting[*i] > 0)
+            .expect("unsorted node");
+        let mut path = vec![];
+        while !path.contains(&current) {
+            path.push(current);
+            current = *self.nodes[current]
+                .parents
+                .iter()
+                .find(|parent| waiting[**parent] > 0)
+                .expect("unsorted parent");
+        }
+        let start = path.iter().position(|i| *i == current).unwrap();
+        let cycle = path[start..]
+            .iter()
+            .map(|i| self.nodes[*i].name().clone())
+            .collect();
+        self.errors.push(DerivationError::Cycle(cycle));
+    }
+
+    /// all nodes, in input order
+    pub fn nodes(&self) -> &[Node<'a>] {
+        &self.nodes
+    }
+
+    /// the node with a given name
+    pub fn get(&self, name: &Name<'a>) -> Option<&Node<'a>> {
+        self.index.get(name).map(|i| &self.nodes[*i])
+    }
+
+    /// problems found while building the derivation
+    pub fn errors(&self) -> &[DerivationError<'a>] {
+        &self.errors
+    }
+
+    /// nodes with parents before children, or `None` if there is a cycle
+    ///
+    /// Ties are broken by input order.
+    pub fn topological_order(&self) -> Option<Vec<&Node<'a>>> {
+        let order = self.order.as_ref()?;
+        Some(order.iter().map(|i| &self.nodes[*i]).collect())
+    }
+
+    /// nodes without parents, i.e. the input formulas
+    pub fn leaves(&self) -> Vec<&Node<'a>> {
+        self.nodes
+            .iter()
+            .filter(|node| node.parents.is_empty())
+            .collect()
+    }
+
+    /// the last `$false` node, if any
+    pub fn refutation(&self) -> Option<&Node<'a>> {
+        self.refutation_index().map(|i| &self.nodes[i])
+    }
+
+    /// mark a node and its ancestors
+    fn reachable(&self, start: Option<usize>) -> Vec<bool> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut todo: Vec<usize> = start.into_iter().collect();
+        while let Some(next) = todo.pop() {
+            if !seen[next] {
+                seen[next] = true;
+                todo.extend(self.nodes[next].parents.iter().copied());
+            }
+        }
+        seen
+    }
+
+    fn marked(&self, seen: Vec<bool>) -> Vec<&Node<'a>> {
+        self.nodes
+            .iter()
+            .zip(seen)
+            .filter(|(_, seen)| *seen)
+            .map(|(node, _)| node)
+            .collect()
+    }
+
+    /// a node and all of its ancestors, in input order
+    pub fn ancestors(&self, name: &Name<'a>) -> Vec<&Node<'a>> {
+        self.marked(self.reachable(self.index.get(name).copied()))
+    }
+
+    fn refutation_index(&self) -> Option<usize> {
+        self.nodes.iter().rposition(Node::is_false)
+    }
+
+    /// the refutation and all of its ancestors, in input order
+    pub fn refutation_ancestors(&self) -> Vec<&Node<'a>> {
+        self.marked(self.reachable(self.refutation_index()))
+    }
+
+    /// axioms, hypotheses and definitions which the refutation does not use
+    ///
+    /// If there is no refutation, all of them are unused.
+    pub fn unused_axioms(&self) -> Vec<&Node<'a>> {
+        let used = self.reachable(self.refutation_index());
+        self.nodes
+            .iter()
+            .zip(used)
+            .filter(|(node, used)| {
+                let role = (node.formula.role().0).0;
+                !used && matches!(role, "axiom" | "hypothesis" | "definition")
+            })
+            .map(|(node, _)| node)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+    use alloc::string::{String, ToString};
+
+    fn build(input: &[u8]) -> Derivation<'_> {
+        Derivation::new(problem(input))
+    }
+
+    fn names(nodes: Vec<&Node>) -> Vec<String> {
+        nodes.iter().map(|node| node.name().to_string()).collect()
+    }
+
+    fn errors(derivation: &Derivation) -> Vec<String> {
+        derivation.errors().iter().map(|e| e.to_string()).collect()
+    }
+
+    const PROOF: &[u8] = b"
+fof(ax1, axiom, p, file('x.p', ax1)).
+fof(ax2, axiom, q, file('x.p', ax2)).
+fof(ax3, axiom, r => p).
+fof(goal, conjecture, p & q).
+fof(c1, negated_conjecture, ~(p & q), inference(negate, [], [goal])).
+cnf(c2, plain, ~p | ~q, inference(clausify, [status(esa)], [c1])).
+cnf(c3, plain, ~q, inference(rw, [status(thm)], [inference(sr, [], [c2, ax1]), ax1])).
+cnf(c4, plain, $false, inference(sr, [status(thm)], [c3, ax2])).
+";
+
+    #[test]
+    fn test_queries() {
+        let derivation = build(PROOF);
+        assert!(derivation.errors().is_empty());
+        assert_eq!(derivation.nodes().len(), 8);
+        assert_eq!(derivation.refutation().unwrap().name().to_string(), "c4");
+        assert_eq!(
+            names(derivation.refutation_ancestors()),
+            ["ax1", "ax2", "goal", "c1", "c2", "c3", "c4"]
+        );
+        assert_eq!(names(derivation.unused_axioms()), ["ax3"]);
+        assert_eq!(names(derivation.leaves()), ["ax1", "ax2", "ax3", "goal"]);
+        assert_eq!(
+            names(derivation.topological_order().unwrap()),
+            ["ax1", "ax2", "ax3", "goal", "c1", "c2", "c3", "c4"]
+        );
+        let c3 = derivation
+            .get(&Name::AtomicWord(AtomicWord::Lower(LowerWord("c3"))));
+        assert_eq!(c3.unwrap().parents, [5, 0]);
+    }
+
+    #[test]
+    fn test_parenthesised_false() {
+        let derivation = build(
+            b"
+cnf(c_0_1, axiom, p).
+cnf(c_0_2, negated_conjecture, ~p).
+cnf(c_0_3, plain, ($false), inference(sr, [status(thm)], [c_0_2, c_0_1])).
+fof(f, plain, (($false)), inference(r, [], [c_0_3])).
+tff(t, plain, ($false), inference(r, [], [f])).
+",
+        );
+        assert!(derivation.nodes()[2..].iter().all(Node::is_false));
+        assert_eq!(derivation.refutation().unwrap().name().to_string(), "t");
+        assert_eq!(
+            names(derivation.refutation_ancestors()),
+            ["c_0_1", "c_0_2", "c_0_3", "f", "t"]
+        );
+
+        let derivation = build(b"cnf(a, axiom, $false | p).");
+        assert!(derivation.refutation().is_none());
+    }
+
+    #[test]
+    fn test_order() {
+        let derivation = build(
+            b"cnf(b, plain, q, inference(r, [], [a])). cnf(a, axiom, p).",
+        );
+        assert_eq!(names(derivation.topological_order().unwrap()), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let derivation = build(
+            b"
+cnf(a, axiom, p).
+cnf(a, axiom, q).
+cnf(b, plain, r, inference(r, [], [a, missing])).
+",
+        );
+        assert_eq!(
+            errors(&derivation),
+            ["duplicate name a", "b: unknown parent missing"]
+        );
+        assert_eq!(
+            names(derivation.ancestors(&derivation.nodes()[2].name().clone())),
+            ["a", "b"]
+        );
+
+        let derivation = build(
+            b"
+cnf(a, axiom, p).
+cnf(b, plain, q, inference(r, [], [a, d])).
+cnf(c, plain, r, inference(r, [], [b])).
+cnf(d, plain, s, inference(r, [], [c])).
+",
+        );
+        assert_eq!(errors(&derivation), ["cycle through b,d,c"]);
+        assert!(derivation.topological_order().is_none());
+    }
+}
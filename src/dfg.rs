@@ -0,0 +1,659 @@
+use alloc::collections::BTreeMap;
+use alloc::fmt::Write;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::clausify::tfx_terms;
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::rename_symbols;
+use crate::scope::FreeVariables;
+use crate::subst::{atom_view, function_view, Arguments, Head};
+use crate::tfx;
+use crate::top::*;
+use crate::utils::{word_text, Separated};
+
+/// why a formula cannot be written in DFG syntax
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ExportErrorKind {
+    #[display(fmt = "unsupported type {}", _0)]
+    Typed(String),
+    #[display(fmt = "unsupported term {}", _0)]
+    Unsupported(String),
+    #[display(fmt = "symbol {} used with different arities", _0)]
+    Arity(String),
+}
+
+type Translation<T> = core::result::Result<T, ExportErrorKind>;
+
+const KEYWORDS: &[&str] = &[
+    "and",
+    "or",
+    "not",
+    "implies",
+    "implied",
+    "equiv",
+    "forall",
+    "exists",
+    "equal",
+    "true",
+    "false",
+    "formula",
+    "clause",
+    "functions",
+    "predicates",
+    "sorts",
+];
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty()
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// whether SPASS reads `text` as an ordinary symbol
+fn is_ordinary(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_lowercase())
+        && is_identifier(text)
+        && !KEYWORDS.contains(&text)
+        && !text.starts_with("list_of_")
+        && !text.starts_with("end_")
+}
+
+fn defined_type<'t>(typ: &tfx::AtomicType<'t>) -> Option<&'t str> {
+    match typ {
+        tfx::AtomicType::Defined(defined) => Some(((defined.0).0).0 .0),
+        _ => None,
+    }
+}
+
+/// whether a type over `$i` and `$o` is `$o`
+fn is_boolean(typ: &tfx::AtomicType) -> Option<bool> {
+    match defined_type(typ)? {
+        "i" => Some(false),
+        "o" => Some(true),
+        _ => None,
+    }
+}
+
+/// the arity of a type over `$i` and `$o`, and whether its range is `$o`
+fn arity(typ: &tfx::TopLevelType) -> Option<(usize, bool)> {
+    let mut non_atomic = match typ {
+        tfx::TopLevelType::Atomic(atomic) => {
+            return Some((0, is_boolean(atomic)?))
+        }
+        tfx::TopLevelType::NonAtomic(non_atomic) => &**non_atomic,
+    };
+    let mapping = loop {
+        match non_atomic {
+            tfx::NonAtomicType::Parenthesised(inner) => non_atomic = inner,
+            tfx::NonAtomicType::Mapping(mapping) => break mapping,
+            tfx::NonAtomicType::Quantified(_) => return None,
+        }
+    };
+    let domain = match &*mapping.domain {
+        tfx::UnitaryType::Atomic(atomic) => vec![atomic],
+        tfx::UnitaryType::Product(product) => product
+            .0
+            .iter()
+            .map(|unitary| match unitary {
+                tfx::UnitaryType::Atomic(atomic) => Some(atomic),
+                tfx::UnitaryType::Product(_) => None,
+            })
+            .collect::<Option<_>>()?,
+    };
+    for typ in &domain {
+        if is_boolean(typ)? {
+            return None;
+        }
+    }
+    Some((domain.len(), is_boolean(&mapping.range)?))
+}
+
+fn not(formula: String) -> String {
+    format!("not({})", formula)
+}
+
+fn nonassoc(op: NonassocConnective, left: String, right: String) -> String {
+    let (op, negated) = match op {
+        NonassocConnective::LRImplies => ("implies", false),
+        NonassocConnective::RLImplies => ("implied", false),
+        NonassocConnective::Equivalent => ("equiv", false),
+        NonassocConnective::NotEquivalent => ("equiv", true),
+        NonassocConnective::NotOr => ("or", true),
+        NonassocConnective::NotAnd => ("and", true),
+    };
+    let formula = format!("{}({},{})", op, left, right);
+    if negated {
+        not(formula)
+    } else {
+        formula
+    }
+}
+
+fn quantify(
+    quantifier: fof::Quantifier,
+    variables: &[Variable],
+    formula: String,
+) -> String {
+    let quantifier = match quantifier {
+        fof::Quantifier::Forall => "forall",
+        fof::Quantifier::Exists => "exists",
+    };
+    format!(
+        "{}([{}],{})",
+        quantifier,
+        Separated(',', variables),
+        formula
+    )
+}
+
+/// writes formulas, collecting the arity of each symbol
+struct Writer<'s, 'a> {
+    symbols: &'s BTreeMap<AtomicWord<'a>, String>,
+    /// the arity of each symbol, and whether it is a predicate
+    arities: BTreeMap<String, (usize, bool)>,
+}
+
+impl<'s, 'a> Writer<'s, 'a> {
+    fn declare(
+        &mut self,
+        name: &str,
+        arity: usize,
+        predicate: bool,
+    ) -> Translation<()> {
+        match self.arities.get(name) {
+            Some(declared) if *declared != (arity, predicate) => {
+                Err(ExportErrorKind::Arity(name.to_string()))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.arities.insert(name.to_string(), (arity, predicate));
+                Ok(())
+            }
+        }
+    }
+
+    fn term(&mut self, term: &fof::Term<'a>) -> Translation<String> {
+        match term {
+            fof::Term::Variable(variable) => Ok(variable.to_string()),
+            fof::Term::Function(function) => {
+                let (head, arguments) = function_view(function);
+                self.application(head, arguments, false, || term.to_string())
+            }
+        }
+    }
+
+    fn application(
+        &mut self,
+        head: Head<'_, 'a>,
+        arguments: Arguments<'_, 'a>,
+        predicate: bool,
+        text: impl FnOnce() -> String,
+    ) -> Translation<String> {
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.term(argument))
+            .collect::<Translation<Vec<_>>>()?;
+        let symbols = self.symbols;
+        let name = match head {
+            Head::Plain(functor) => {
+                let name = symbols[&functor.0].as_str();
+                self.declare(name, arguments.len(), predicate)?;
+                name
+            }
+            Head::Defined(functor) if predicate && arguments.is_empty() => {
+                match ((functor.0).0).0 .0 {
+                    "true" => "true",
+                    "false" => "false",
+                    _ => return Err(ExportErrorKind::Unsupported(text())),
+                }
+            }
+            Head::Equality => "equal",
+            _ => return Err(ExportErrorKind::Unsupported(text())),
+        };
+        if arguments.is_empty() {
+            Ok(name.to_string())
+        } else {
+            Ok(format!("{}({})", name, arguments.join(",")))
+        }
+    }
+
+    fn atom(&mut self, atom: &fof::AtomicFormula<'a>) -> Translation<String> {
+        let (head, arguments) = atom_view(atom);
+        self.application(head, arguments, true, || atom.to_string())
+    }
+
+    fn equality(
+        &mut self,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+    ) -> Translation<String> {
+        let left = self.term(left)?;
+        let right = self.term(right)?;
+        Ok(format!("equal({},{})", left, right))
+    }
+
+    fn clause(&mut self, formula: &cnf::Formula<'a>) -> Translation<String> {
+        let (cnf::Formula::Disjunction(disjunction)
+        | cnf::Formula::Parenthesised(disjunction)) = formula;
+        let mut literals = vec![];
+        for literal in &disjunction.0 {
+            literals.push(match literal {
+                cnf::Literal::Atomic(atom) => self.atom(atom)?,
+                cnf::Literal::NegatedAtomic(atom) => not(self.atom(atom)?),
+                cnf::Literal::Infix(infix) => {
+                    not(self.equality(&infix.left, &infix.right)?)
+                }
+            });
+        }
+        let clause = if literals.len() == 1 {
+            literals.remove(0)
+        } else {
+            format!("or({})", literals.join(","))
+        };
+        let free = formula.free_variables();
+        if free.is_empty() {
+            Ok(clause)
+        } else {
+            Ok(quantify(fof::Quantifier::Forall, &free, clause))
+        }
+    }
+
+    fn fof_unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.fof_unitary(unitary),
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary),
+        }
+    }
+
+    fn fof_unary(
+        &mut self,
+        formula: &fof::UnaryFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            fof::UnaryFormula::Unary(_, negated) => {
+                Ok(not(self.fof_unit(negated)?))
+            }
+            fof::UnaryFormula::InfixUnary(infix) => {
+                Ok(not(self.equality(&infix.left, &infix.right)?))
+            }
+        }
+    }
+
+    fn fof_unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let body = self.fof_unit(&quantified.formula)?;
+                Ok(quantify(quantified.quantifier, &quantified.bound.0, body))
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.atom(atom),
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.fof_logic(formula)
+            }
+        }
+    }
+
+    fn fof_logic(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+    ) -> Translation<String> {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::Or(or) => ("or", &or.0),
+                    BinaryAssoc::And(and) => ("and", &and.0),
+                };
+                let units = units
+                    .iter()
+                    .map(|unit| self.fof_unit(unit))
+                    .collect::<Translation<Vec<_>>>()?;
+                Ok(format!("{}({})", op, units.join(",")))
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.fof_unit(&binary.left)?;
+                let right = self.fof_unit(&binary.right)?;
+                Ok(nonassoc(binary.op, left, right))
+            }
+            LogicFormula::Unary(unary) => self.fof_unary(unary),
+            LogicFormula::Unitary(unitary) => self.fof_unitary(unitary),
+        }
+    }
+
+    fn tfx_equality(
+        &mut self,
+        left: &tfx::UnitaryTerm<'a>,
+        right: &tfx::UnitaryTerm<'a>,
+    ) -> Translation<String> {
+        let unsupported = |term: &tfx::UnitaryTerm| {
+            ExportErrorKind::Unsupported(term.to_string())
+        };
+        let left_term =
+            tfx_terms::unitary(left).ok_or_else(|| unsupported(left))?;
+        let right_term =
+            tfx_terms::unitary(right).ok_or_else(|| unsupported(right))?;
+        self.equality(&left_term, &right_term)
+    }
+
+    fn tfx_prefix(
+        &mut self,
+        prefix: &tfx::PrefixUnary<'a>,
+    ) -> Translation<String> {
+        let negated = match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => {
+                self.tfx_unitary(unitary)?
+            }
+            tfx::PreunitFormula::Prefix(prefix) => self.tfx_prefix(prefix)?,
+        };
+        Ok(not(negated))
+    }
+
+    fn tfx_unary(
+        &mut self,
+        formula: &tfx::UnaryFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                Ok(not(self.tfx_equality(&infix.left, &infix.right)?))
+            }
+        }
+    }
+
+    fn tfx_unitary(
+        &mut self,
+        formula: &tfx::UnitaryFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let mut variables = vec![];
+                for variable in &quantified.bound.0 {
+                    variables.push(match variable {
+                        tfx::Variable::Typed(typed)
+                            if is_boolean(&typed.typ) != Some(false) =>
+                        {
+                            let typ = typed.typ.to_string();
+                            return Err(ExportErrorKind::Typed(typ));
+                        }
+                        tfx::Variable::Typed(typed) => typed.variable.clone(),
+                        tfx::Variable::Untyped(variable) => variable.clone(),
+                    });
+                }
+                let body = self.tfx_unit(&quantified.formula)?;
+                Ok(quantify(quantified.quantifier, &variables, body))
+            }
+            tfx::UnitaryFormula::Atomic(atom) => {
+                let converted = tfx_terms::atom(atom).ok_or_else(|| {
+                    ExportErrorKind::Unsupported(atom.to_string())
+                })?;
+                self.atom(&converted)
+            }
+            tfx::UnitaryFormula::Variable(variable) => {
+                Err(ExportErrorKind::Unsupported(variable.to_string()))
+            }
+            tfx::UnitaryFormula::Logic(formula) => self.tfx_logic(formula),
+        }
+    }
+
+    fn tfx_unit(
+        &mut self,
+        formula: &tfx::UnitFormula<'a>,
+    ) -> Translation<String> {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_equality(&infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_logic(
+        &mut self,
+        formula: &tfx::LogicFormula<'a>,
+    ) -> Translation<String> {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::Or(or) => ("or", &or.0),
+                    BinaryAssoc::And(and) => ("and", &and.0),
+                };
+                let units = units
+                    .iter()
+                    .map(|unit| self.tfx_unit(unit))
+                    .collect::<Translation<Vec<_>>>()?;
+                Ok(format!("{}({})", op, units.join(",")))
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.tfx_unit(&binary.left)?;
+                let right = self.tfx_unit(&binary.right)?;
+                Ok(nonassoc(binary.op, left, right))
+            }
+            LogicFormula::Unary(unary) => self.tfx_unary(unary),
+            LogicFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            LogicFormula::DefinedInfix(infix) => {
+                self.tfx_equality(&infix.left, &infix.right)
+            }
+        }
+    }
+
+    /// declare the symbol of a type declaration over `$i` and `$o`
+    fn declaration(
+        &mut self,
+        typing: &tfx::AtomTyping<'a>,
+    ) -> Translation<()> {
+        let (atom, typ) = match typing {
+            tfx::AtomTyping::Parenthesised(inner) => {
+                return self.declaration(inner)
+            }
+            tfx::AtomTyping::Typing(atom, typ) => (atom, typ),
+        };
+        let constant = match atom {
+            UntypedAtom::Constant(constant) => constant,
+            UntypedAtom::System(system) => {
+                return Err(ExportErrorKind::Unsupported(system.to_string()))
+            }
+        };
+        let (arity, predicate) = arity(typ)
+            .ok_or_else(|| ExportErrorKind::Typed(typ.to_string()))?;
+        let symbols = self.symbols;
+        self.declare(&symbols[&(constant.0).0], arity, predicate)
+    }
+}
+
+/// the label of a formula named `name`, if DFG can represent it
+fn label(name: &Name) -> Option<String> {
+    let text = match name {
+        Name::AtomicWord(word) => word_text(word),
+        Name::Integer(integer) => integer.0.to_string(),
+    };
+    if is_identifier(&text) {
+        Some(text)
+    } else {
+        None
+    }
+}
+
+/// export FOF and CNF problems in SPASS's DFG syntax, naming the problem `problem`
+///
+/// Conjectures become `list_of_formulae(conjectures)` and all other formulas, including negated conjectures, `list_of_formulae(axioms)`.
+/// Clauses are written as universally-closed disjunctions.
+/// Symbols are declared with the arity at which they are used, and symbols which SPASS would read as something else are renamed with a `c_` prefix.
+/// TFX formulas are accepted if they only use the types `$i` and `$o`, and their type declarations are checked against the use of their symbols.
+pub fn export<'a>(
+    problem: &str,
+    formulas: &[AnnotatedFormula<'a>],
+) -> Result<String, ExportError<'a, ExportErrorKind>> {
+    let symbols = rename_symbols(formulas, is_ordinary);
+    let arities = BTreeMap::new();
+    let mut writer = Writer {
+        symbols: &symbols,
+        arities,
+    };
+    let mut axioms = String::new();
+    let mut conjectures = String::new();
+    for annotated in formulas {
+        let error = |kind| ExportError {
+            name: annotated.name().clone(),
+            kind,
+        };
+        let formula = match annotated {
+            AnnotatedFormula::Fof(fof) => writer.fof_logic(&fof.0.formula.0),
+            AnnotatedFormula::Cnf(cnf) => writer.clause(&cnf.0.formula),
+            AnnotatedFormula::Tfx(tfx) => match &*tfx.0.formula {
+                tfx::Formula::Logic(logic) => writer.tfx_logic(logic),
+                tfx::Formula::AtomTyping(typing) => {
+                    writer.declaration(typing).map_err(error)?;
+                    continue;
+                }
+            },
+        };
+        let formula = formula.map_err(error)?;
+        let list = if (annotated.role().0).0 == "conjecture" {
+            &mut conjectures
+        } else {
+            &mut axioms
+        };
+        match label(annotated.name()) {
+            Some(name) => writeln!(list, "formula({},{}).", formula, name),
+            None => writeln!(list, "formula({}).", formula),
+        }
+        .expect("write to string");
+    }
+
+    let mut problem: String = problem
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if problem.is_empty() {
+        problem.push_str("problem");
+    }
+    let mut functions = vec![];
+    let mut predicates = vec![];
+    for (name, (arity, predicate)) in &writer.arities {
+        let declaration = format!("({},{})", name, arity);
+        if *predicate {
+            predicates.push(declaration);
+        } else {
+            functions.push(declaration);
+        }
+    }
+
+    let mut output = String::new();
+    writeln!(output, "begin_problem({}).\n", problem)
+        .expect("write to string");
+    output.push_str("list_of_descriptions.\n");
+    writeln!(output, "name({{*{}*}}).", problem).expect("write to string");
+    output.push_str("author({*unknown*}).\n");
+    output.push_str("status(unknown).\n");
+    output.push_str("description({*exported from TPTP*}).\n");
+    output.push_str("end_of_list.\n\n");
+    output.push_str("list_of_symbols.\n");
+    if !functions.is_empty() {
+        writeln!(output, "functions[{}].", functions.join(","))
+            .expect("write to string");
+    }
+    if !predicates.is_empty() {
+        writeln!(output, "predicates[{}].", predicates.join(","))
+            .expect("write to string");
+    }
+    output.push_str("end_of_list.\n\n");
+    for (kind, list) in [("axioms", axioms), ("conjectures", conjectures)] {
+        writeln!(output, "list_of_formulae({}).", kind)
+            .expect("write to string");
+        output.push_str(&list);
+        output.push_str("end_of_list.\n\n");
+    }
+    output.push_str("end_problem.\n");
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    #[test]
+    fn test_export() {
+        let formulas = problem(
+            b"
+            fof(ax, axiom, ![X]: (p(X) => q(f(X, a)))).
+            cnf(cl, axiom, ~q(Y) | Y = a | 'odd name').
+            fof(neg, axiom, ~(p(a) <~> $true) & a != b).
+            tff(p_type, type, p: $i > $o).
+            tff(typed, axiom, ![X: $i]: (p(X) | ~r)).
+            fof('not a label', axiom, r | or).
+            fof(goal, conjecture, ?[X]: p(X)).
+        ",
+        );
+        assert_eq!(
+            export("PUZ001+1.p", &formulas).expect("export error"),
+            "\
+begin_problem(PUZ001_1_p).
+
+list_of_descriptions.
+name({*PUZ001_1_p*}).
+author({*unknown*}).
+status(unknown).
+description({*exported from TPTP*}).
+end_of_list.
+
+list_of_symbols.
+functions[(a,0),(b,0),(f,2)].
+predicates[(c_odd_name,0),(c_or,0),(p,1),(q,1),(r,0)].
+end_of_list.
+
+list_of_formulae(axioms).
+formula(forall([X],implies(p(X),q(f(X,a)))),ax).
+formula(forall([Y],or(not(q(Y)),equal(Y,a),c_odd_name)),cl).
+formula(and(not(not(equiv(p(a),true))),not(equal(a,b))),neg).
+formula(forall([X],or(p(X),not(r))),typed).
+formula(or(r,c_or)).
+end_of_list.
+
+list_of_formulae(conjectures).
+formula(exists([X],p(X)),goal).
+end_of_list.
+
+end_problem.
+"
+        );
+    }
+
+    #[test]
+    fn test_export_errors() {
+        let error =
+            |input| export("p", &problem(input)).unwrap_err().to_string();
+        assert_eq!(
+            error(b"tff(t, type, t: $tType)."),
+            "t: unsupported type $tType"
+        );
+        assert_eq!(
+            error(b"tff(a, axiom, ![X: $int]: p(X))."),
+            "a: unsupported type $int"
+        );
+        assert_eq!(
+            error(b"fof(a, axiom, p(a) & p)."),
+            "a: symbol p used with different arities"
+        );
+        assert_eq!(
+            error(b"tff(p_type, type, p: $i).\ntff(a, axiom, p)."),
+            "a: symbol p used with different arities"
+        );
+        assert_eq!(error(b"fof(a, axiom, p(1))."), "a: unsupported term 1");
+    }
+}
@@ -0,0 +1,360 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::str::FromStr;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::clausify::false_literal;
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::{unused, Arena};
+use crate::nnf::truth_value;
+use crate::scope::FreeVariables;
+use crate::top::*;
+use crate::utils::{word_text, Separated};
+
+/// a problem in DIMACS CNF format
+#[derive(Clone, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Dimacs {
+    /// the number of variables, which are numbered from 1
+    pub variables: usize,
+    /// clauses of literals, which are negative if negated
+    pub clauses: Vec<Vec<i64>>,
+}
+
+impl fmt::Display for Dimacs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "p cnf {} {}", self.variables, self.clauses.len())?;
+        for clause in &self.clauses {
+            if clause.is_empty() {
+                writeln!(f, "0")?;
+            } else {
+                writeln!(f, "{} 0", Separated(' ', clause))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// a DIMACS file which cannot be read
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ImportError {
+    #[display(fmt = "syntax error on line {}", _0)]
+    Syntax(usize),
+    #[display(fmt = "missing problem line")]
+    Header,
+    #[display(fmt = "undeclared variable on line {}", _0)]
+    Range(usize),
+    #[display(fmt = "expected {} clauses, found {}", _0, _1)]
+    Count(usize, usize),
+}
+
+impl FromStr for Dimacs {
+    type Err = ImportError;
+
+    /// read a DIMACS file, which may end with a SATLIB-style `%` line
+    fn from_str(input: &str) -> Result<Self, ImportError> {
+        let mut header = None;
+        let mut clauses = vec![];
+        let mut clause = vec![];
+        for (index, line) in input.lines().enumerate() {
+            let number = index + 1;
+            let line = line.trim();
+            if line.starts_with('%') {
+                break;
+            }
+            if line.is_empty() || line.starts_with('c') {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            if line.starts_with('p') {
+                let count = |field: Option<&str>| field?.parse().ok();
+                header = match (fields.next(), fields.next()) {
+                    (Some("p"), Some("cnf")) if header.is_none() => {
+                        let variables = count(fields.next());
+                        let clauses = count(fields.next());
+                        variables
+                            .zip(clauses)
+                            .filter(|_| fields.next().is_none())
+                    }
+                    _ => None,
+                };
+                if header.is_none() {
+                    return Err(ImportError::Syntax(number));
+                }
+                continue;
+            }
+            let (variables, _) = header.ok_or(ImportError::Header)?;
+            for field in fields {
+                let literal: i64 =
+                    field.parse().map_err(|_| ImportError::Syntax(number))?;
+                if literal == 0 {
+                    clauses.push(mem::take(&mut clause));
+                } else if literal.unsigned_abs() > variables as u64 {
+                    return Err(ImportError::Range(number));
+                } else {
+                    clause.push(literal);
+                }
+            }
+        }
+        let (variables, expected) = header.ok_or(ImportError::Header)?;
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+        if clauses.len() != expected {
+            return Err(ImportError::Count(expected, clauses.len()));
+        }
+        Ok(Self { variables, clauses })
+    }
+}
+
+/// why a formula is not ground propositional CNF
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ExportErrorKind {
+    #[display(fmt = "not a CNF formula")]
+    NotCnf,
+    #[display(fmt = "non-ground literal {}", _0)]
+    NonGround(String),
+    #[display(fmt = "equational literal {}", _0)]
+    Equational(String),
+    #[display(fmt = "non-propositional literal {}", _0)]
+    NonPropositional(String),
+}
+
+enum Atom<'t, 'a> {
+    Truth(bool),
+    Symbol(&'t AtomicWord<'a>),
+}
+
+/// the sign and atom of a ground propositional literal
+fn propositional<'t, 'a>(
+    literal: &'t cnf::Literal<'a>,
+) -> Result<(bool, Atom<'t, 'a>), ExportErrorKind> {
+    let (positive, atom) = match literal {
+        cnf::Literal::Atomic(atom) => (true, atom),
+        cnf::Literal::NegatedAtomic(atom) => (false, atom),
+        cnf::Literal::Infix(_) => {
+            return Err(ExportErrorKind::Equational(literal.to_string()))
+        }
+    };
+    if !literal.free_variables().is_empty() {
+        return Err(ExportErrorKind::NonGround(literal.to_string()));
+    }
+    match atom {
+        fof::AtomicFormula::Plain(fof::PlainAtomicFormula(
+            fof::PlainTerm::Constant(constant),
+        )) => return Ok((positive, Atom::Symbol(&(constant.0).0))),
+        fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Plain(
+            fof::DefinedPlainFormula(fof::DefinedPlainTerm::Constant(
+                constant,
+            )),
+        )) => {
+            if let Some(word) = truth_value(constant) {
+                return Ok((positive, Atom::Truth(word == "true")));
+            }
+        }
+        fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Infix(_)) => {
+            return Err(ExportErrorKind::Equational(literal.to_string()))
+        }
+        _ => {}
+    }
+    Err(ExportErrorKind::NonPropositional(literal.to_string()))
+}
+
+/// export a ground propositional CNF problem to DIMACS, with the symbol of each variable
+///
+/// Propositional constants become variables, numbered in order of first occurrence, so the symbol of variable `n` is `symbols[n - 1]`.
+/// Constants with the same text, such as `p` and `'p'`, are the same variable.
+/// Clauses containing a true literal such as `$true` are dropped, and false literals are removed.
+/// Roles are ignored, so CNF conjectures should already be negated.
+pub fn export<'a>(
+    formulas: &[AnnotatedFormula<'a>],
+) -> Result<(Dimacs, Vec<AtomicWord<'a>>), ExportError<'a, ExportErrorKind>> {
+    let mut symbols = vec![];
+    let mut variables: BTreeMap<String, i64> = BTreeMap::new();
+    let mut clauses = vec![];
+    'clauses: for annotated in formulas {
+        let error = |kind| ExportError {
+            name: annotated.name().clone(),
+            kind,
+        };
+        let disjunction = match annotated {
+            AnnotatedFormula::Cnf(cnf) => match &*cnf.0.formula {
+                cnf::Formula::Disjunction(disjunction)
+                | cnf::Formula::Parenthesised(disjunction) => disjunction,
+            },
+            _ => return Err(error(ExportErrorKind::NotCnf)),
+        };
+        let mut clause = vec![];
+        for literal in &disjunction.0 {
+            let (positive, atom) = propositional(literal).map_err(error)?;
+            let symbol = match atom {
+                Atom::Truth(value) if value == positive => continue 'clauses,
+                Atom::Truth(_) => continue,
+                Atom::Symbol(symbol) => symbol,
+            };
+            let text = word_text(symbol);
+            let variable = *variables.entry(text).or_insert_with(|| {
+                symbols.push(symbol.clone());
+                symbols.len() as i64
+            });
+            clause.push(if positive { variable } else { -variable });
+        }
+        clauses.push(clause);
+    }
+    let variables = symbols.len();
+    Ok((Dimacs { variables, clauses }, symbols))
+}
+
+/// import a DIMACS problem as CNF axioms, storing new symbols in `arena`
+///
+/// Variable `n` becomes the propositional constant `symbols[n - 1]` if there is one, or `pn` otherwise, with a numeric suffix if `pn` is already in `symbols`.
+/// Clauses are named `c1`, `c2` and so on, and the empty clause is `$false`.
+pub fn import<'a>(
+    dimacs: &Dimacs,
+    symbols: &[AtomicWord<'a>],
+    arena: &'a Arena,
+) -> Vec<AnnotatedFormula<'a>> {
+    let mut words: BTreeMap<u64, AtomicWord<'a>> = BTreeMap::new();
+    let mut used: BTreeSet<String> = symbols.iter().map(word_text).collect();
+    let mut atom = |variable: u64| {
+        let word = words.entry(variable).or_insert_with(|| {
+            let index = (variable as usize).checked_sub(1);
+            match index.and_then(|index| symbols.get(index)) {
+                Some(symbol) => symbol.clone(),
+                None => {
+                    let name = unused(&mut used, format!("p{}", variable));
+                    let word = arena.alloc(&name);
+                    AtomicWord::Lower(LowerWord(word))
+                }
+            }
+        });
+        let constant = Constant(Functor(word.clone()));
+        fof::AtomicFormula::Plain(fof::PlainAtomicFormula(
+            fof::PlainTerm::Constant(constant),
+        ))
+    };
+    let mut formulas = vec![];
+    for (index, clause) in dimacs.clauses.iter().enumerate() {
+        let mut literals = vec![];
+        for literal in clause {
+            let atom = atom(literal.unsigned_abs());
+            literals.push(if *literal < 0 {
+                cnf::Literal::NegatedAtomic(atom)
+            } else {
+                cnf::Literal::Atomic(atom)
+            });
+        }
+        if literals.is_empty() {
+            literals.push(false_literal());
+        }
+        let name = arena.alloc(&format!("c{}", index + 1));
+        let disjunction = cnf::Disjunction(literals);
+        let annotated = Annotated {
+            name: Name::AtomicWord(AtomicWord::Lower(LowerWord(name))),
+            role: FormulaRole(LowerWord("axiom")),
+            formula: Box::new(cnf::Formula::Disjunction(disjunction)),
+            annotations: Annotations(None),
+        };
+        formulas
+            .push(AnnotatedFormula::Cnf(Box::new(CnfAnnotated(annotated))));
+    }
+    formulas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    #[test]
+    fn test_export() {
+        let formulas = problem(
+            b"
+            cnf(a, axiom, p | ~q | $false).
+            cnf(b, axiom, 'q' | 'r s').
+            cnf(c, axiom, p | $true).
+            cnf(d, negated_conjecture, ~p | ~$true).
+            cnf(e, axiom, $false).
+        ",
+        );
+        let (dimacs, symbols) = export(&formulas).expect("export error");
+        assert_eq!(dimacs.to_string(), "p cnf 3 4\n1 -2 0\n2 3 0\n-1 0\n0\n");
+        let symbols: Vec<_> = symbols.iter().map(|s| s.to_string()).collect();
+        assert_eq!(symbols, ["p", "q", "'r s'"]);
+
+        let error = |input| export(&problem(input)).unwrap_err().to_string();
+        assert_eq!(error(b"fof(a, axiom, p)."), "a: not a CNF formula");
+        assert_eq!(
+            error(b"cnf(a, axiom, p(X))."),
+            "a: non-ground literal p(X)"
+        );
+        assert_eq!(
+            error(b"cnf(a, axiom, a = b)."),
+            "a: equational literal a=b"
+        );
+        assert_eq!(
+            error(b"cnf(a, axiom, p | a != b)."),
+            "a: equational literal a!=b"
+        );
+        assert_eq!(
+            error(b"cnf(a, axiom, p(a))."),
+            "a: non-propositional literal p(a)"
+        );
+    }
+
+    #[test]
+    fn test_import() {
+        let dimacs: Dimacs = "\
+c a comment
+p cnf 3 3
+1 -3 0
+2
+-1 0 0
+%
+0
+"
+        .parse()
+        .expect("syntax error");
+        assert_eq!(dimacs.clauses, [vec![1, -3], vec![2, -1], vec![]]);
+
+        let arena = Arena::new();
+        let formulas = problem(b"cnf(a, axiom, p | q).");
+        let (_, symbols) = export(&formulas).expect("export error");
+        let imported = import(&dimacs, &symbols, &arena);
+        let printed: Vec<_> = imported.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            printed,
+            [
+                "cnf(c1,axiom,p|~p3).",
+                "cnf(c2,axiom,q|~p).",
+                "cnf(c3,axiom,$false)."
+            ]
+        );
+
+        let formulas = problem(b"cnf(a, axiom, p3 | q).");
+        let (_, symbols) = export(&formulas).expect("export error");
+        let dimacs: Dimacs =
+            "p cnf 3 1\n1 -3 0".parse().expect("syntax error");
+        let imported = import(&dimacs, &symbols, &arena);
+        assert_eq!(imported[0].to_string(), "cnf(c1,axiom,p3|~p31).");
+
+        let error = |input: &str| input.parse::<Dimacs>().unwrap_err();
+        assert_eq!(error("1 0"), ImportError::Header);
+        assert_eq!(error("p cnf 1\n1 0"), ImportError::Syntax(1));
+        assert_eq!(error("p cnf 1 1\n1 x 0"), ImportError::Syntax(2));
+        assert_eq!(error("p cnf 1 1\n1 -2 0"), ImportError::Range(2));
+        assert_eq!(error("p cnf 1 2\n1 0"), ImportError::Count(2, 1));
+    }
+}
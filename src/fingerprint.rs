@@ -0,0 +1,979 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::tfx;
+use crate::top::*;
+use crate::utils::word_text;
+
+/// what structural hashing should ignore, besides names and annotations
+#[derive(
+    Clone, Copy, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct HashOptions {
+    /// treat `&`, `|`, `=` and `!=` as commutative
+    pub commutative: bool,
+    /// ignore the order of literals in clauses
+    pub unordered_literals: bool,
+}
+
+/// a 128-bit structural hash
+///
+/// Fingerprints are stable across runs and platforms, but not necessarily across versions of this crate.
+#[derive(
+    Clone, Copy, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[display(fmt = "{:032x}", _0)]
+pub struct Fingerprint(pub u128);
+
+/// syntax with a structural hash
+///
+/// Formulas which are equal up to renaming of variables have equal fingerprints.
+/// Parentheses are ignored, as are names and annotations of annotated formulas.
+/// With `HashOptions::commutative` or `HashOptions::unordered_literals`, reordered formulas almost always have equal fingerprints:
+/// the exception is reordering subformulas which differ only in the names of their free variables.
+pub trait StructuralHash {
+    /// the fingerprint of `self`
+    fn fingerprint(&self, options: HashOptions) -> Fingerprint;
+}
+
+#[derive(Clone, Copy)]
+enum Tag {
+    Bound,
+    Free,
+    Anonymous,
+    Symbol,
+    Equal,
+    NotEqual,
+    Not,
+    And,
+    Or,
+    Nonassoc,
+    Quantified,
+    Product,
+    Mapping,
+    QuantifiedType,
+    Typing,
+    Fof,
+    Cnf,
+    Tfx,
+}
+
+/// 128-bit FNV-1a
+#[derive(Clone, Copy)]
+struct Fnv(u128);
+
+impl Fnv {
+    const OFFSET: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+
+    fn new(tag: Tag) -> Self {
+        Self(Self::OFFSET).byte(tag as u8)
+    }
+
+    fn byte(self, byte: u8) -> Self {
+        Self((self.0 ^ u128::from(byte)).wrapping_mul(Self::PRIME))
+    }
+
+    fn bytes(self, bytes: &[u8]) -> Self {
+        bytes.iter().fold(self, |fnv, byte| fnv.byte(*byte))
+    }
+
+    fn count(self, count: usize) -> Self {
+        self.bytes(&(count as u64).to_le_bytes())
+    }
+
+    fn hash(self, hash: u128) -> Self {
+        self.bytes(&hash.to_le_bytes())
+    }
+
+    fn display(self, item: &dyn fmt::Display) -> Self {
+        let mut writer = Writer(self);
+        // writing to the hash never fails
+        let _ = write!(writer, "{}", item);
+        writer.0.byte(0xff)
+    }
+}
+
+struct Writer(Fnv);
+
+impl Write for Writer {
+    fn write_str(&mut self, string: &str) -> fmt::Result {
+        self.0 = self.0.bytes(string.as_bytes());
+        Ok(())
+    }
+}
+
+/// a plain symbol by its text, so that `p` and `'p'` are the same symbol
+struct Text<'t, 'a>(&'t AtomicWord<'a>);
+
+impl fmt::Display for Text<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&word_text(self.0))
+    }
+}
+
+struct Hashing<'a> {
+    options: HashOptions,
+    bound: Vec<Variable<'a>>,
+    /// free variables in order of first occurrence, or `None` to hash all free variables alike
+    free: Option<Vec<Variable<'a>>>,
+}
+
+impl<'a> Hashing<'a> {
+    fn new(options: HashOptions) -> Self {
+        Self {
+            options,
+            bound: Vec::new(),
+            free: Some(Vec::new()),
+        }
+    }
+
+    fn variable(&mut self, variable: &Variable<'a>) -> u128 {
+        if let Some(index) =
+            self.bound.iter().rev().position(|bound| bound == variable)
+        {
+            return Fnv::new(Tag::Bound).count(index).0;
+        }
+        match &mut self.free {
+            Some(free) => {
+                let index = match free.iter().position(|free| free == variable)
+                {
+                    Some(index) => index,
+                    None => {
+                        free.push(variable.clone());
+                        free.len() - 1
+                    }
+                };
+                Fnv::new(Tag::Free).count(index).0
+            }
+            None => Fnv::new(Tag::Anonymous).0,
+        }
+    }
+
+    /// hash `children` in order, or sorted if `commutative`
+    ///
+    /// Sorting uses hashes in which free variables are anonymous, so that free variables are then numbered in an order which does not depend on the original order of the children.
+    fn children<'t, T: 't>(
+        &mut self,
+        fnv: Fnv,
+        commutative: bool,
+        children: impl IntoIterator<Item = &'t T>,
+        hash: fn(&mut Self, &T) -> u128,
+    ) -> Fnv {
+        let children: Vec<&T> = children.into_iter().collect();
+        let mut fnv = fnv.count(children.len());
+        if !commutative {
+            for child in children {
+                fnv = fnv.hash(hash(self, child));
+            }
+            return fnv;
+        }
+        let free = self.free.take();
+        let mut keyed: Vec<(u128, &T)> = children
+            .into_iter()
+            .map(|child| (hash(self, child), child))
+            .collect();
+        self.free = free;
+        keyed.sort_by_key(|(key, _)| *key);
+        for (key, child) in keyed {
+            let hash = if self.free.is_some() {
+                hash(self, child)
+            } else {
+                key
+            };
+            fnv = fnv.hash(hash);
+        }
+        fnv
+    }
+
+    fn symbol(symbol: &dyn fmt::Display) -> u128 {
+        Fnv::new(Tag::Symbol).display(symbol).count(0).0
+    }
+
+    fn fof_application(
+        &mut self,
+        symbol: &dyn fmt::Display,
+        arguments: &fof::Arguments<'a>,
+    ) -> u128 {
+        let fnv = Fnv::new(Tag::Symbol).display(symbol);
+        self.children(fnv, false, &arguments.0, Self::fof_term).0
+    }
+
+    fn fof_plain_term(&mut self, term: &fof::PlainTerm<'a>) -> u128 {
+        match term {
+            fof::PlainTerm::Constant(constant) => {
+                Self::symbol(&Text(&(constant.0).0))
+            }
+            fof::PlainTerm::Function(functor, arguments) => {
+                self.fof_application(&Text(&functor.0), arguments)
+            }
+        }
+    }
+
+    fn fof_defined_plain_term(
+        &mut self,
+        term: &fof::DefinedPlainTerm<'a>,
+    ) -> u128 {
+        match term {
+            fof::DefinedPlainTerm::Constant(constant) => {
+                Self::symbol(constant)
+            }
+            fof::DefinedPlainTerm::Function(functor, arguments) => {
+                self.fof_application(functor, arguments)
+            }
+        }
+    }
+
+    fn fof_system_term(&mut self, term: &fof::SystemTerm<'a>) -> u128 {
+        match term {
+            fof::SystemTerm::Constant(constant) => Self::symbol(constant),
+            fof::SystemTerm::Function(functor, arguments) => {
+                self.fof_application(functor, arguments)
+            }
+        }
+    }
+
+    fn fof_term(&mut self, term: &fof::Term<'a>) -> u128 {
+        let function = match term {
+            fof::Term::Variable(variable) => return self.variable(variable),
+            fof::Term::Function(function) => function,
+        };
+        match &**function {
+            fof::FunctionTerm::Plain(term) => self.fof_plain_term(term),
+            fof::FunctionTerm::System(term) => self.fof_system_term(term),
+            fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(term)) => {
+                self.fof_defined_plain_term(&term.0)
+            }
+            fof::FunctionTerm::Defined(fof::DefinedTerm::Defined(defined)) => {
+                Self::symbol(defined)
+            }
+        }
+    }
+
+    fn fof_infix(
+        &mut self,
+        tag: Tag,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+    ) -> u128 {
+        let commutative = self.options.commutative;
+        let terms = [left, right];
+        let fnv = Fnv::new(tag);
+        self.children(fnv, commutative, terms.iter().copied(), Self::fof_term)
+            .0
+    }
+
+    fn fof_atomic_formula(&mut self, atom: &fof::AtomicFormula<'a>) -> u128 {
+        use fof::{AtomicFormula, DefinedAtomicFormula};
+        match atom {
+            AtomicFormula::Plain(plain) => self.fof_plain_term(&plain.0),
+            AtomicFormula::Defined(DefinedAtomicFormula::Plain(plain)) => {
+                self.fof_defined_plain_term(&plain.0)
+            }
+            AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix)) => {
+                self.fof_infix(Tag::Equal, &infix.left, &infix.right)
+            }
+            AtomicFormula::System(system) => self.fof_system_term(&system.0),
+        }
+    }
+
+    fn fof_unit_formula(&mut self, formula: &fof::UnitFormula<'a>) -> u128 {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => {
+                self.fof_unitary_formula(unitary)
+            }
+            fof::UnitFormula::Unary(unary) => self.fof_unary_formula(unary),
+        }
+    }
+
+    fn fof_unary_formula(&mut self, formula: &fof::UnaryFormula<'a>) -> u128 {
+        match formula {
+            fof::UnaryFormula::Unary(_, unit) => {
+                let hash = self.fof_unit_formula(unit);
+                Fnv::new(Tag::Not).hash(hash).0
+            }
+            fof::UnaryFormula::InfixUnary(infix) => {
+                self.fof_infix(Tag::NotEqual, &infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn fof_unitary_formula(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+    ) -> u128 {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.bound.len();
+                let bound = &quantified.bound.0;
+                self.bound.extend(bound.iter().cloned());
+                let hash = self.fof_unit_formula(&quantified.formula);
+                self.bound.truncate(depth);
+                Fnv::new(Tag::Quantified)
+                    .display(&quantified.quantifier)
+                    .count(bound.len())
+                    .hash(hash)
+                    .0
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.fof_atomic_formula(atom),
+            fof::UnitaryFormula::Parenthesised(logic) => {
+                self.fof_logic_formula(logic)
+            }
+        }
+    }
+
+    fn fof_logic_formula(&mut self, formula: &fof::LogicFormula<'a>) -> u128 {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (tag, units) = match assoc {
+                    BinaryAssoc::Or(or) => (Tag::Or, &or.0),
+                    BinaryAssoc::And(and) => (Tag::And, &and.0),
+                };
+                let commutative = self.options.commutative;
+                let fnv = Fnv::new(tag);
+                self.children(fnv, commutative, units, Self::fof_unit_formula)
+                    .0
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(nonassoc)) => {
+                let left = self.fof_unit_formula(&nonassoc.left);
+                let right = self.fof_unit_formula(&nonassoc.right);
+                Fnv::new(Tag::Nonassoc)
+                    .display(&nonassoc.op)
+                    .hash(left)
+                    .hash(right)
+                    .0
+            }
+            LogicFormula::Unary(unary) => self.fof_unary_formula(unary),
+            LogicFormula::Unitary(unitary) => {
+                self.fof_unitary_formula(unitary)
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &cnf::Literal<'a>) -> u128 {
+        match literal {
+            cnf::Literal::Atomic(atom) => self.fof_atomic_formula(atom),
+            cnf::Literal::NegatedAtomic(atom) => {
+                let hash = self.fof_atomic_formula(atom);
+                Fnv::new(Tag::Not).hash(hash).0
+            }
+            cnf::Literal::Infix(infix) => {
+                self.fof_infix(Tag::NotEqual, &infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn clause<'t>(
+        &mut self,
+        literals: impl IntoIterator<Item = &'t cnf::Literal<'a>>,
+    ) -> u128
+    where
+        'a: 't,
+    {
+        let unordered = self.options.unordered_literals;
+        let fnv = Fnv::new(Tag::Or);
+        self.children(fnv, unordered, literals, Self::literal).0
+    }
+
+    fn cnf_formula(&mut self, formula: &cnf::Formula<'a>) -> u128 {
+        match formula {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => {
+                self.clause(&disjunction.0)
+            }
+        }
+    }
+
+    fn tfx_application(
+        &mut self,
+        symbol: &dyn fmt::Display,
+        arguments: &tfx::Arguments<'a>,
+    ) -> u128 {
+        let fnv = Fnv::new(Tag::Symbol).display(symbol);
+        self.children(fnv, false, &arguments.0, Self::tfx_term).0
+    }
+
+    fn tfx_atomic_formula(&mut self, atom: &tfx::AtomicFormula<'a>) -> u128 {
+        use tfx::{AtomicFormula, DefinedAtomic, DefinedPlain};
+        use tfx::{PlainAtomic, SystemAtomic};
+        match atom {
+            AtomicFormula::Plain(PlainAtomic::Constant(constant)) => {
+                Self::symbol(&Text(&(constant.0).0))
+            }
+            AtomicFormula::Plain(PlainAtomic::Function(
+                functor,
+                arguments,
+            )) => self.tfx_application(&Text(&functor.0), arguments),
+            AtomicFormula::Defined(DefinedAtomic(DefinedPlain::Constant(
+                constant,
+            ))) => Self::symbol(constant),
+            AtomicFormula::Defined(DefinedAtomic(DefinedPlain::Function(
+                functor,
+                arguments,
+            ))) => self.tfx_application(functor, arguments),
+            AtomicFormula::System(SystemAtomic::Constant(constant)) => {
+                Self::symbol(constant)
+            }
+            AtomicFormula::System(SystemAtomic::Function(
+                functor,
+                arguments,
+            )) => self.tfx_application(functor, arguments),
+        }
+    }
+
+    fn tfx_term(&mut self, term: &tfx::Term<'a>) -> u128 {
+        match term {
+            tfx::Term::Logic(logic) => self.tfx_logic_formula(logic),
+            tfx::Term::Defined(defined) => Self::symbol(defined),
+        }
+    }
+
+    fn tfx_unitary_term(&mut self, term: &tfx::UnitaryTerm<'a>) -> u128 {
+        match term {
+            tfx::UnitaryTerm::Atomic(atom) => self.tfx_atomic_formula(atom),
+            tfx::UnitaryTerm::Defined(defined) => Self::symbol(defined),
+            tfx::UnitaryTerm::Variable(variable) => self.variable(variable),
+            tfx::UnitaryTerm::Logic(logic) => self.tfx_logic_formula(logic),
+        }
+    }
+
+    fn tfx_infix(
+        &mut self,
+        tag: Tag,
+        left: &tfx::UnitaryTerm<'a>,
+        right: &tfx::UnitaryTerm<'a>,
+    ) -> u128 {
+        let commutative = self.options.commutative;
+        let terms = [left, right];
+        let fnv = Fnv::new(tag);
+        let hash = Self::tfx_unitary_term;
+        self.children(fnv, commutative, terms.iter().copied(), hash)
+            .0
+    }
+
+    fn tfx_prefix_unary(&mut self, prefix: &tfx::PrefixUnary<'a>) -> u128 {
+        let hash = match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            tfx::PreunitFormula::Prefix(prefix) => {
+                self.tfx_prefix_unary(prefix)
+            }
+        };
+        Fnv::new(Tag::Not).hash(hash).0
+    }
+
+    fn tfx_unary_formula(&mut self, formula: &tfx::UnaryFormula<'a>) -> u128 {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix_unary(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                self.tfx_infix(Tag::NotEqual, &infix.left, &infix.right)
+            }
+        }
+    }
+
+    /// hash the types of bound variables and bind them
+    fn tfx_bind(&mut self, fnv: Fnv, list: &tfx::VariableList<'a>) -> Fnv {
+        let mut fnv = fnv.count(list.0.len());
+        for variable in &list.0 {
+            match variable {
+                tfx::Variable::Typed(typed) => {
+                    fnv = fnv.byte(1).hash(self.tfx_atomic_type(&typed.typ));
+                    self.bound.push(typed.variable.clone());
+                }
+                tfx::Variable::Untyped(variable) => {
+                    fnv = fnv.byte(0);
+                    self.bound.push(variable.clone());
+                }
+            }
+        }
+        fnv
+    }
+
+    fn tfx_unitary_formula(
+        &mut self,
+        formula: &tfx::UnitaryFormula<'a>,
+    ) -> u128 {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.bound.len();
+                let fnv =
+                    Fnv::new(Tag::Quantified).display(&quantified.quantifier);
+                let fnv = self.tfx_bind(fnv, &quantified.bound);
+                let hash = self.tfx_unit_formula(&quantified.formula);
+                self.bound.truncate(depth);
+                fnv.hash(hash).0
+            }
+            tfx::UnitaryFormula::Atomic(atom) => self.tfx_atomic_formula(atom),
+            tfx::UnitaryFormula::Variable(variable) => self.variable(variable),
+            tfx::UnitaryFormula::Logic(logic) => self.tfx_logic_formula(logic),
+        }
+    }
+
+    fn tfx_unit_formula(&mut self, formula: &tfx::UnitFormula<'a>) -> u128 {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary_formula(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_infix(Tag::Equal, &infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_logic_formula(&mut self, formula: &tfx::LogicFormula<'a>) -> u128 {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Unary(unary) => self.tfx_unary_formula(unary),
+            LogicFormula::Unitary(unitary) => {
+                self.tfx_unitary_formula(unitary)
+            }
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (tag, units) = match assoc {
+                    BinaryAssoc::Or(or) => (Tag::Or, &or.0),
+                    BinaryAssoc::And(and) => (Tag::And, &and.0),
+                };
+                let commutative = self.options.commutative;
+                let fnv = Fnv::new(tag);
+                self.children(fnv, commutative, units, Self::tfx_unit_formula)
+                    .0
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(nonassoc)) => {
+                let left = self.tfx_unit_formula(&nonassoc.left);
+                let right = self.tfx_unit_formula(&nonassoc.right);
+                Fnv::new(Tag::Nonassoc)
+                    .display(&nonassoc.op)
+                    .hash(left)
+                    .hash(right)
+                    .0
+            }
+            LogicFormula::DefinedInfix(infix) => {
+                self.tfx_infix(Tag::Equal, &infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_atomic_type(&mut self, typ: &tfx::AtomicType<'a>) -> u128 {
+        match typ {
+            tfx::AtomicType::Constant(constant) => {
+                Self::symbol(&Text(&(constant.0).0))
+            }
+            tfx::AtomicType::Defined(defined) => Self::symbol(defined),
+            tfx::AtomicType::Variable(variable) => self.variable(variable),
+            tfx::AtomicType::Function(functor, arguments) => {
+                let fnv = Fnv::new(Tag::Symbol).display(&Text(&functor.0));
+                let hash = Self::tfx_atomic_type;
+                self.children(fnv, false, &arguments.0, hash).0
+            }
+        }
+    }
+
+    fn tfx_unitary_type(&mut self, typ: &tfx::UnitaryType<'a>) -> u128 {
+        match typ {
+            tfx::UnitaryType::Atomic(atomic) => self.tfx_atomic_type(atomic),
+            tfx::UnitaryType::Product(product) => {
+                let fnv = Fnv::new(Tag::Product);
+                let hash = Self::tfx_unitary_type;
+                self.children(fnv, false, &product.0, hash).0
+            }
+        }
+    }
+
+    fn tfx_mapping_type(&mut self, typ: &tfx::MappingType<'a>) -> u128 {
+        let domain = self.tfx_unitary_type(&typ.domain);
+        let range = self.tfx_atomic_type(&typ.range);
+        Fnv::new(Tag::Mapping).hash(domain).hash(range).0
+    }
+
+    fn tfx_quantified_type(&mut self, typ: &tfx::QuantifiedType<'a>) -> u128 {
+        let depth = self.bound.len();
+        let fnv = self.tfx_bind(Fnv::new(Tag::QuantifiedType), &typ.bound);
+        let hash = self.tfx_monotype(&typ.typ);
+        self.bound.truncate(depth);
+        fnv.hash(hash).0
+    }
+
+    fn tfx_monotype(&mut self, typ: &tfx::Monotype<'a>) -> u128 {
+        match typ {
+            tfx::Monotype::Atomic(atomic) => self.tfx_atomic_type(atomic),
+            tfx::Monotype::Mapping(mapping) => self.tfx_mapping_type(mapping),
+            tfx::Monotype::Quantified(quantified) => {
+                self.tfx_quantified_type(quantified)
+            }
+        }
+    }
+
+    fn tfx_non_atomic_type(&mut self, typ: &tfx::NonAtomicType<'a>) -> u128 {
+        match typ {
+            tfx::NonAtomicType::Mapping(mapping) => {
+                self.tfx_mapping_type(mapping)
+            }
+            tfx::NonAtomicType::Quantified(quantified) => {
+                self.tfx_quantified_type(quantified)
+            }
+            tfx::NonAtomicType::Parenthesised(typ) => {
+                self.tfx_non_atomic_type(typ)
+            }
+        }
+    }
+
+    fn tfx_atom_typing(&mut self, typing: &tfx::AtomTyping<'a>) -> u128 {
+        let (atom, typ) = match typing {
+            tfx::AtomTyping::Typing(atom, typ) => (atom, typ),
+            tfx::AtomTyping::Parenthesised(typing) => {
+                return self.tfx_atom_typing(typing)
+            }
+        };
+        let hash = match typ {
+            tfx::TopLevelType::Atomic(atomic) => self.tfx_atomic_type(atomic),
+            tfx::TopLevelType::NonAtomic(non_atomic) => {
+                self.tfx_non_atomic_type(non_atomic)
+            }
+        };
+        let fnv = match atom {
+            UntypedAtom::Constant(constant) => {
+                Fnv::new(Tag::Typing).display(&Text(&(constant.0).0))
+            }
+            UntypedAtom::System(system) => {
+                Fnv::new(Tag::Typing).display(system)
+            }
+        };
+        fnv.hash(hash).0
+    }
+
+    fn tfx_formula(&mut self, formula: &tfx::Formula<'a>) -> u128 {
+        match formula {
+            tfx::Formula::Logic(logic) => self.tfx_logic_formula(logic),
+            tfx::Formula::AtomTyping(typing) => self.tfx_atom_typing(typing),
+        }
+    }
+}
+
+impl<'a> StructuralHash for fof::Formula<'a> {
+    fn fingerprint(&self, options: HashOptions) -> Fingerprint {
+        Fingerprint(Hashing::new(options).fof_logic_formula(&self.0))
+    }
+}
+
+impl<'a> StructuralHash for cnf::Formula<'a> {
+    fn fingerprint(&self, options: HashOptions) -> Fingerprint {
+        Fingerprint(Hashing::new(options).cnf_formula(self))
+    }
+}
+
+impl<'a> StructuralHash for tfx::Formula<'a> {
+    fn fingerprint(&self, options: HashOptions) -> Fingerprint {
+        Fingerprint(Hashing::new(options).tfx_formula(self))
+    }
+}
+
+/// Formulas in different dialects have different fingerprints.
+impl<'a> StructuralHash for AnnotatedFormula<'a> {
+    fn fingerprint(&self, options: HashOptions) -> Fingerprint {
+        let (tag, hash) = match self {
+            AnnotatedFormula::Fof(fof) => {
+                (Tag::Fof, fof.0.formula.fingerprint(options))
+            }
+            AnnotatedFormula::Cnf(cnf) => {
+                (Tag::Cnf, cnf.0.formula.fingerprint(options))
+            }
+            AnnotatedFormula::Tfx(tfx) => {
+                (Tag::Tfx, tfx.0.formula.fingerprint(options))
+            }
+        };
+        Fingerprint(Fnv::new(tag).hash(hash.0).0)
+    }
+}
+
+/// why an input is redundant, with the index of the earlier input responsible
+#[derive(
+    Clone, Copy, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Redundancy {
+    /// the input has the same fingerprint as an earlier input
+    #[display(fmt = "duplicate of input {}", _0)]
+    Duplicate(usize),
+    /// the input is a conjunct of an earlier formula,
+    /// or a clause containing a renaming of an earlier clause
+    #[display(fmt = "subsumed by input {}", _0)]
+    Subsumed(usize),
+}
+
+/// clauses with more literals than this are only checked for duplicates
+const MAX_SUBSUMED_LITERALS: usize = 12;
+
+/// duplicate detection over a stream of inputs
+///
+/// Only fingerprints are stored, so memory use does not depend on the size of the inputs.
+/// Subsumption is detected only up to renaming of variables:
+/// a clause is not reported as subsumed by a more general clause.
+#[derive(Clone, Debug, Default)]
+pub struct Deduplicator {
+    options: HashOptions,
+    formulas: BTreeMap<Fingerprint, usize>,
+    conjuncts: BTreeMap<Fingerprint, usize>,
+    count: usize,
+}
+
+fn fof_conjuncts<'t, 'a>(
+    formula: &'t fof::LogicFormula<'a>,
+) -> &'t [fof::UnitFormula<'a>] {
+    use fof::{BinaryAssoc, BinaryFormula, LogicFormula, UnitaryFormula};
+    match formula {
+        LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::And(and))) => {
+            &and.0
+        }
+        LogicFormula::Unitary(UnitaryFormula::Parenthesised(formula)) => {
+            fof_conjuncts(formula)
+        }
+        _ => &[],
+    }
+}
+
+fn tfx_conjuncts<'t, 'a>(
+    formula: &'t tfx::LogicFormula<'a>,
+) -> &'t [tfx::UnitFormula<'a>] {
+    use tfx::{BinaryAssoc, BinaryFormula, LogicFormula, UnitaryFormula};
+    match formula {
+        LogicFormula::Binary(BinaryFormula::Assoc(BinaryAssoc::And(and))) => {
+            &and.0
+        }
+        LogicFormula::Unitary(UnitaryFormula::Logic(formula)) => {
+            tfx_conjuncts(formula)
+        }
+        _ => &[],
+    }
+}
+
+impl Deduplicator {
+    pub fn new(options: HashOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// the number of inputs checked so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    fn conjunct_fingerprints(
+        &self,
+        annotated: &AnnotatedFormula,
+    ) -> Vec<Fingerprint> {
+        let (tag, hashes) = match annotated {
+            AnnotatedFormula::Fof(fof) => {
+                let conjuncts = fof_conjuncts(&fof.0.formula.0);
+                let hashes = conjuncts
+                    .iter()
+                    .map(|conjunct| {
+                        Hashing::new(self.options).fof_unit_formula(conjunct)
+                    })
+                    .collect();
+                (Tag::Fof, hashes)
+            }
+            AnnotatedFormula::Tfx(tfx) => {
+                let conjuncts = match &*tfx.0.formula {
+                    tfx::Formula::Logic(logic) => tfx_conjuncts(logic),
+                    tfx::Formula::AtomTyping(_) => &[],
+                };
+                let hashes = conjuncts
+                    .iter()
+                    .map(|conjunct| {
+                        Hashing::new(self.options).tfx_unit_formula(conjunct)
+                    })
+                    .collect();
+                (Tag::Tfx, hashes)
+            }
+            AnnotatedFormula::Cnf(_) => (Tag::Cnf, Vec::new()),
+        };
+        hashes
+            .into_iter()
+            .map(|hash: u128| Fingerprint(Fnv::new(tag).hash(hash).0))
+            .collect()
+    }
+
+    /// the earliest clause which is a renaming of some literals of `clause`
+    fn subsuming_clause(&self, clause: &cnf::Formula) -> Option<usize> {
+        let literals = match clause {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => &disjunction.0,
+        };
+        if literals.len() > MAX_SUBSUMED_LITERALS {
+            return None;
+        }
+        let all = (1usize << literals.len()) - 1;
+        (1..all)
+            .filter_map(|mask| {
+                let subset = literals
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| mask & (1 << index) != 0)
+                    .map(|(_, literal)| literal);
+                let hash = Hashing::new(self.options).clause(subset);
+                let fingerprint = Fingerprint(Fnv::new(Tag::Cnf).hash(hash).0);
+                self.formulas.get(&fingerprint).copied()
+            })
+            .min()
+    }
+
+    /// check an input against all those before it, then remember it
+    ///
+    /// Inputs are numbered from 0 in the order they are checked.
+    pub fn check(
+        &mut self,
+        annotated: &AnnotatedFormula,
+    ) -> Option<Redundancy> {
+        let index = self.count;
+        self.count += 1;
+        let fingerprint = annotated.fingerprint(self.options);
+        if let Some(earlier) = self.formulas.get(&fingerprint) {
+            return Some(Redundancy::Duplicate(*earlier));
+        }
+        let redundancy = self
+            .conjuncts
+            .get(&fingerprint)
+            .copied()
+            .or_else(|| match annotated {
+                AnnotatedFormula::Cnf(cnf) => {
+                    self.subsuming_clause(&cnf.0.formula)
+                }
+                _ => None,
+            })
+            .map(Redundancy::Subsumed);
+        self.formulas.insert(fingerprint, index);
+        for conjunct in self.conjunct_fingerprints(annotated) {
+            self.conjuncts.entry(conjunct).or_insert(index);
+        }
+        redundancy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::ToString;
+
+    const COMMUTATIVE: HashOptions = HashOptions {
+        commutative: true,
+        unordered_literals: true,
+    };
+
+    fn fof(input: &[u8], options: HashOptions) -> Fingerprint {
+        check_parse(fof::Formula::parse, input).fingerprint(options)
+    }
+
+    fn cnf(input: &[u8], options: HashOptions) -> Fingerprint {
+        check_parse(cnf::Formula::parse, input).fingerprint(options)
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let default = HashOptions::default();
+        let hash = fof(b"![X]: (p(X) & q(X, a))\0", default);
+        assert_eq!(hash, fof(b"![Y]: (p(Y) & (q(Y, a)))\0", default));
+        assert_ne!(hash, fof(b"![X]: (p(X) & q(a, X))\0", default));
+        assert_ne!(hash, fof(b"![X]: (q(X, a) & p(X))\0", default));
+        assert_eq!(
+            fof(b"![X]: (p(X) & q(X, a))\0", COMMUTATIVE),
+            fof(b"![X]: (q(X, a) & p(X))\0", COMMUTATIVE),
+        );
+        assert_eq!(
+            fof(b"![X]: ?[Y]: X = f(Y)\0", COMMUTATIVE),
+            fof(b"![X]: ?[Y]: f(Y) = X\0", COMMUTATIVE),
+        );
+        assert_ne!(
+            fof(b"![X]: ?[Y]: p(X, Y)\0", default),
+            fof(b"![X]: ?[Y]: p(Y, X)\0", default),
+        );
+        assert_eq!(
+            fof(b"p(a) & ~'q'(b)\0", default),
+            fof(b"'p'('a') & ~q('b')\0", default),
+        );
+        assert_ne!(fof(b"p\0", default), fof(b"'P'\0", default));
+        assert_eq!(hash.to_string().len(), 32);
+    }
+
+    #[test]
+    fn test_clause_fingerprint() {
+        let default = HashOptions::default();
+        let hash = cnf(b"p(X) | ~q(X, Y)\0", default);
+        assert_eq!(hash, cnf(b"(p(A) | ~q(A, B))\0", default));
+        assert_ne!(hash, cnf(b"~q(X, Y) | p(X)\0", default));
+        assert_ne!(hash, cnf(b"p(X) | ~q(Y, X)\0", default));
+        assert_eq!(
+            cnf(b"p(X) | ~q(X, Y)\0", COMMUTATIVE),
+            cnf(b"~q(B, C) | p(B)\0", COMMUTATIVE),
+        );
+        assert_ne!(
+            cnf(b"p(X) | ~q(X, Y)\0", COMMUTATIVE),
+            cnf(b"~q(B, C) | p(C)\0", COMMUTATIVE),
+        );
+    }
+
+    #[test]
+    fn test_tfx_fingerprint() {
+        let default = HashOptions::default();
+        let tfx = |input| check_parse(tfx::Formula::parse, input);
+        assert_eq!(
+            tfx(b"![X: $int]: $less(X, 3)\0").fingerprint(default),
+            tfx(b"![Y: $int]: $less(Y, 3)\0").fingerprint(default),
+        );
+        assert_ne!(
+            tfx(b"![X: $int]: $less(X, 3)\0").fingerprint(default),
+            tfx(b"![X: $real]: $less(X, 3)\0").fingerprint(default),
+        );
+        assert_eq!(
+            tfx(b"c: 'list'(t) > $o\0").fingerprint(default),
+            tfx(b"'c': list('t') > $o\0").fingerprint(default),
+        );
+    }
+
+    #[test]
+    fn test_deduplicator() {
+        let inputs = b"
+            fof(a, axiom, ![X]: (p(X) & q(X))).
+            fof(b, axiom, ![Y]: (p(Y) & q(Y))).
+            fof(c, axiom, r & s).
+            fof(d, axiom, s).
+            cnf(e, axiom, p(X) | q(X)).
+            cnf(f, axiom, r(Y) | p(Y) | q(Y)).
+            cnf(g, axiom, p(X) | r(X)).
+            tff(h, axiom, s).
+        ";
+        let mut deduplicator = Deduplicator::new(COMMUTATIVE);
+        let verdicts: Vec<_> = problem(inputs)
+            .iter()
+            .map(|annotated| deduplicator.check(annotated))
+            .collect();
+        assert_eq!(
+            verdicts,
+            [
+                None,
+                Some(Redundancy::Duplicate(0)),
+                None,
+                Some(Redundancy::Subsumed(2)),
+                None,
+                Some(Redundancy::Subsumed(4)),
+                None,
+                None,
+            ]
+        );
+        assert_eq!(deduplicator.count(), 8);
+    }
+}
@@ -20,7 +20,7 @@ use crate::{Error, Parse, Result};
 pub struct Arguments<'a>(pub Vec<Term<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Arguments<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -41,7 +41,7 @@ pub enum SystemTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SystemTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(SystemFunctor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -62,7 +62,7 @@ pub enum PlainTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for PlainTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(Functor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -83,7 +83,7 @@ pub enum DefinedPlainTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedPlainTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(DefinedFunctor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -100,7 +100,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DefinedPlainTerm<'a> {
 pub struct DefinedAtomicTerm<'a>(pub DefinedPlainTerm<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedAtomicTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DefinedPlainTerm::parse, Self)(x)
     }
 }
@@ -114,7 +114,7 @@ pub enum DefinedTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(crate::common::DefinedTerm::parse, Self::Defined),
             map(DefinedAtomicTerm::parse, Self::Atomic),
@@ -132,7 +132,7 @@ pub enum FunctionTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FunctionTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(PlainTerm::parse, Self::Plain),
             map(SystemTerm::parse, Self::System),
@@ -150,7 +150,7 @@ pub enum Term<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Term<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(Variable::parse, Self::Variable),
             map(map(FunctionTerm::parse, Box::new), Self::Function),
@@ -173,7 +173,7 @@ pub enum Quantifier {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Quantifier {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((value(Self::Forall, tag("!")), value(Self::Exists, tag("?"))))(x)
     }
 }
@@ -184,7 +184,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Quantifier {
 pub struct SystemAtomicFormula<'a>(pub SystemTerm<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SystemAtomicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(SystemTerm::parse, Self)(x)
     }
 }
@@ -195,7 +195,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for SystemAtomicFormula<'a> {
 pub struct PlainAtomicFormula<'a>(pub PlainTerm<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for PlainAtomicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(PlainTerm::parse, Self)(x)
     }
 }
@@ -206,7 +206,7 @@ pub(crate) struct DefinedInfixFormulaTail<'a>(
 );
 
 impl<'a> DefinedInfixFormulaTail<'a> {
-    pub(crate) fn finish(self, left: Term<'a>) -> DefinedInfixFormula {
+    pub(crate) fn finish(self, left: Term<'a>) -> DefinedInfixFormula<'a> {
         let left = Box::new(left);
         let op = self.0;
         let right = self.1;
@@ -215,7 +215,7 @@ impl<'a> DefinedInfixFormulaTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfixFormulaTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 DefinedInfixPred::parse,
@@ -237,7 +237,7 @@ pub struct DefinedInfixFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfixFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 Term::parse,
@@ -254,7 +254,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfixFormula<'a> {
 pub struct DefinedPlainFormula<'a>(pub DefinedPlainTerm<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedPlainFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DefinedPlainTerm::parse, Self)(x)
     }
 }
@@ -268,7 +268,7 @@ pub enum DefinedAtomicFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedAtomicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(DefinedInfixFormula::parse, Self::Infix),
             map(DefinedPlainFormula::parse, Self::Plain),
@@ -286,7 +286,7 @@ pub enum AtomicFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -317,7 +317,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AtomicFormula<'a> {
 pub struct VariableList<'a>(pub Vec<Variable<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for VariableList<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -339,7 +339,7 @@ pub struct QuantifiedFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for QuantifiedFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             tuple((
                 Quantifier::parse,
@@ -370,7 +370,7 @@ impl<'a> InfixUnaryTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixUnaryTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 InfixInequality::parse,
@@ -392,7 +392,7 @@ pub struct InfixUnary<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixUnary<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(Term::parse, preceded(ignored, InfixUnaryTail::parse)),
             |(left, tail)| tail.finish(left),
@@ -410,7 +410,7 @@ pub enum UnaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -435,7 +435,7 @@ pub enum UnitaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(QuantifiedFormula::parse, Self::Quantified),
             map(parens, |f| Self::Parenthesised(Box::new(f))),
@@ -473,7 +473,7 @@ impl<'a> UnitFormulaTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitFormulaTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -502,7 +502,7 @@ pub enum UnitFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -535,7 +535,7 @@ impl<'a, const SEP: u8> AssocTail<'a, SEP> {
 }
 
 impl<'a, E: Error<'a>, const SEP: u8> Parse<'a, E> for AssocTail<'a, SEP> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         let (x, second) =
             preceded(tag(&[SEP]), preceded(ignored, UnitFormula::parse))(x)?;
         let mut result = GarbageFirstVec::default();
@@ -564,7 +564,7 @@ impl<'a> OrTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for OrTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AssocTail::parse, Self)(x)
     }
 }
@@ -576,7 +576,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for OrTail<'a> {
 pub struct OrFormula<'a>(pub Vec<UnitFormula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for OrFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitFormula::parse, preceded(ignored, OrTail::parse)),
             |(first, tail)| tail.finish(first),
@@ -593,7 +593,7 @@ impl<'a> AndTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AndTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(AssocTail::parse, Self)(x)
     }
 }
@@ -605,7 +605,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AndTail<'a> {
 pub struct AndFormula<'a>(pub Vec<UnitFormula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AndFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitFormula::parse, preceded(ignored, AndTail::parse)),
             |(first, tail)| tail.finish(first),
@@ -619,7 +619,7 @@ enum BinaryAssocTail<'a> {
 }
 
 impl<'a> BinaryAssocTail<'a> {
-    fn finish(self, left: UnitFormula<'a>) -> BinaryAssoc {
+    fn finish(self, left: UnitFormula<'a>) -> BinaryAssoc<'a> {
         match self {
             Self::Or(tail) => BinaryAssoc::Or(tail.finish(left)),
             Self::And(tail) => BinaryAssoc::And(tail.finish(left)),
@@ -628,7 +628,7 @@ impl<'a> BinaryAssocTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryAssocTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(OrTail::parse, BinaryAssocTail::Or),
             map(AndTail::parse, BinaryAssocTail::And),
@@ -645,7 +645,7 @@ pub enum BinaryAssoc<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryAssoc<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -668,7 +668,7 @@ impl<'a> BinaryNonassocTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryNonassocTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 NonassocConnective::parse,
@@ -690,7 +690,7 @@ pub struct BinaryNonassoc<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryNonassoc<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -716,7 +716,7 @@ impl<'a> BinaryFormulaTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryFormulaTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(BinaryAssocTail::parse, Self::Assoc),
             map(BinaryNonassocTail::parse, Self::Nonassoc),
@@ -733,7 +733,7 @@ pub enum BinaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -754,7 +754,7 @@ pub enum LogicFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for LogicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -777,7 +777,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for LogicFormula<'a> {
 pub struct Formula<'a>(pub LogicFormula<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Formula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(LogicFormula::parse, Formula)(x)
     }
 }
@@ -0,0 +1,185 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ptr::NonNull;
+
+use crate::common::*;
+use crate::fof;
+use crate::sine::signature;
+use crate::top::AnnotatedFormula;
+use crate::utils::word_text;
+use crate::visitor::Visitor;
+
+/// storage for symbols invented by transformations
+///
+/// Syntax trees borrow their symbols from the input, so new symbols must be stored somewhere that outlives the trees which use them.
+#[derive(Debug, Default)]
+pub struct Arena(RefCell<Vec<NonNull<str>>>);
+
+impl Arena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// copy a string into the arena
+    pub fn alloc(&self, string: &str) -> &str {
+        let ptr = NonNull::from(Box::leak(Box::<str>::from(string)));
+        self.0.borrow_mut().push(ptr);
+        // safety: the allocation is only freed when the arena is dropped
+        unsafe { &*ptr.as_ptr() }
+    }
+}
+
+impl Drop for Arena {
+    fn drop(&mut self) {
+        for ptr in self.0.get_mut().drain(..) {
+            // safety: created by `Box::leak` and only freed here
+            drop(unsafe { Box::from_raw(ptr.as_ptr()) });
+        }
+    }
+}
+
+struct Symbols<'s, 'a>(&'s mut BTreeSet<&'a str>);
+
+impl<'s, 'a> Visitor<'a> for Symbols<'s, 'a> {
+    fn visit_lower_word(&mut self, lower_word: &LowerWord<'a>) {
+        self.0.insert(lower_word.0);
+    }
+
+    fn visit_upper_word(&mut self, upper_word: &UpperWord<'a>) {
+        self.0.insert(upper_word.0);
+    }
+
+    fn visit_single_quoted(&mut self, single_quoted: &SingleQuoted<'a>) {
+        self.0.insert(single_quoted.0);
+    }
+}
+
+/// a generator of fresh symbols
+///
+/// Symbols are a prefix followed by a number, e.g. `sK0`, skipping any which are already in use.
+#[derive(Debug)]
+pub struct Fresh<'a> {
+    arena: &'a Arena,
+    used: BTreeSet<&'a str>,
+    next: BTreeMap<String, usize>,
+}
+
+impl<'a> Fresh<'a> {
+    pub fn new(arena: &'a Arena) -> Self {
+        let used = BTreeSet::new();
+        let next = BTreeMap::new();
+        Self { arena, used, next }
+    }
+
+    /// never generate `symbol`
+    pub fn avoid(&mut self, symbol: &'a str) {
+        self.used.insert(symbol);
+    }
+
+    /// never generate any word occurring in `annotated`, including names and variables
+    pub fn avoid_formula(&mut self, annotated: &AnnotatedFormula<'a>) {
+        Symbols(&mut self.used).visit_annotated_formula(annotated);
+    }
+
+    /// never generate any word occurring in a FOF formula
+    pub fn avoid_fof_formula(&mut self, formula: &fof::Formula<'a>) {
+        Symbols(&mut self.used).visit_fof_formula(formula);
+    }
+
+    /// whether `symbol` is in use
+    pub fn is_used(&self, symbol: &str) -> bool {
+        self.used.contains(symbol)
+    }
+
+    /// a new symbol starting with `prefix`
+    ///
+    /// The prefix determines what kind of word this is: `sK` makes functors, `X` makes variables.
+    pub fn symbol(&mut self, prefix: &str) -> &'a str {
+        let next = self.next.entry(String::from(prefix)).or_insert(0);
+        loop {
+            let candidate = format!("{}{}", prefix, next);
+            *next += 1;
+            if !self.used.contains(candidate.as_str()) {
+                let symbol = self.arena.alloc(&candidate);
+                self.used.insert(symbol);
+                return symbol;
+            }
+        }
+    }
+
+    /// a new lower-case functor starting with `prefix`
+    pub fn functor(&mut self, prefix: &str) -> Functor<'a> {
+        Functor(AtomicWord::Lower(LowerWord(self.symbol(prefix))))
+    }
+
+    /// a new variable starting with `prefix`
+    pub fn variable(&mut self, prefix: &str) -> Variable<'a> {
+        Variable(UpperWord(self.symbol(prefix)))
+    }
+}
+
+/// `base`, or `base` with the smallest numeric suffix which is not `used`, which it then is
+pub(crate) fn unused(used: &mut BTreeSet<String>, base: String) -> String {
+    let mut name = base.clone();
+    let mut suffix = 1;
+    while used.contains(&name) {
+        name = format!("{}{}", base, suffix);
+        suffix += 1;
+    }
+    used.insert(name.clone());
+    name
+}
+
+/// a name for each symbol of `formulas` in another syntax: its text if `keep` accepts it, or `c_` and its sanitised text otherwise
+pub(crate) fn rename_symbols<'a>(
+    formulas: &[AnnotatedFormula<'a>],
+    keep: fn(&str) -> bool,
+) -> BTreeMap<AtomicWord<'a>, String> {
+    let words: BTreeSet<_> = formulas.iter().flat_map(signature).collect();
+    let texts: Vec<_> = words.iter().map(word_text).collect();
+    let mut used: BTreeSet<String> =
+        texts.iter().filter(|text| keep(text)).cloned().collect();
+    let mut names = BTreeMap::new();
+    for (word, text) in words.into_iter().zip(texts) {
+        let name = if keep(&text) {
+            text
+        } else {
+            let sanitised: String = text
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+            unused(&mut used, format!("c_{}", sanitised))
+        };
+        names.insert(word, name);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_fresh() {
+        let arena = Arena::new();
+        let annotated = check_parse(
+            AnnotatedFormula::parse,
+            b"fof(sK1, axiom, ![X0]: p(X0, sK0, 'sK2')).\0",
+        );
+        let mut fresh = Fresh::new(&arena);
+        fresh.avoid_formula(&annotated);
+        fresh.avoid("sK4");
+        assert!(fresh.is_used("sK1"));
+        assert_eq!(fresh.symbol("sK"), "sK3");
+        assert_eq!(fresh.symbol("sK"), "sK5");
+        assert_eq!(fresh.variable("X").0 .0, "X1");
+        assert_eq!(fresh.functor("sP").0.to_string(), "sP0");
+    }
+}
@@ -0,0 +1,382 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::derivation::{Derivation, Node};
+use crate::pretty::Pretty;
+use crate::top::AnnotatedFormula;
+
+/// options for exporting a `Derivation` as a graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct GraphOptions {
+    /// the layout of formulas in node labels
+    pub pretty: Pretty,
+    /// merge chains of inferences, each with one parent and one child, into their last node
+    pub collapse_chains: bool,
+    /// highlight conjectures and the refutation
+    pub highlight: bool,
+}
+
+impl Default for GraphOptions {
+    fn default() -> Self {
+        Self {
+            pretty: Pretty::new(60, 2, true, true),
+            collapse_chains: false,
+            highlight: true,
+        }
+    }
+}
+
+/// why a node is highlighted
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Highlight {
+    Conjecture,
+    Refutation,
+}
+
+impl Highlight {
+    fn name(self) -> &'static str {
+        match self {
+            Highlight::Conjecture => "conjecture",
+            Highlight::Refutation => "refutation",
+        }
+    }
+
+    fn colour(self) -> &'static str {
+        match self {
+            Highlight::Conjecture => "lightblue",
+            Highlight::Refutation => "lightcoral",
+        }
+    }
+}
+
+/// a node of the exported graph, standing for a chain of derivation nodes
+struct Vertex<'d, 'a> {
+    node: &'d Node<'a>,
+    /// names of earlier nodes collapsed into this one
+    collapsed: Vec<String>,
+    formula: String,
+    highlight: Option<Highlight>,
+}
+
+/// an edge of the exported graph
+struct Edge {
+    source: usize,
+    target: usize,
+    /// inference rules along the edge, separated by commas
+    rules: String,
+}
+
+fn formula(formula: &AnnotatedFormula, pretty: &Pretty) -> String {
+    match formula {
+        AnnotatedFormula::Fof(fof) => pretty.fof_formula(&fof.0.formula),
+        AnnotatedFormula::Cnf(cnf) => pretty.cnf_formula(&cnf.0.formula),
+        AnnotatedFormula::Tfx(tfx) => pretty.tfx_formula(&tfx.0.formula),
+    }
+}
+
+/// vertices and edges, with vertices indexed as derivation nodes
+fn graph<'d, 'a>(
+    derivation: &'d Derivation<'a>,
+    options: &GraphOptions,
+) -> (Vec<(usize, Vertex<'d, 'a>)>, Vec<Edge>) {
+    let nodes = derivation.nodes();
+    let mut children = vec![vec![]; nodes.len()];
+    for (child, node) in nodes.iter().enumerate() {
+        for parent in &node.parents {
+            children[*parent].push(child);
+        }
+    }
+    let absorbed: Vec<bool> = (0..nodes.len())
+        .map(|index| {
+            options.collapse_chains
+                && !nodes[index].parents.is_empty()
+                && children[index].len() == 1
+                && nodes[children[index][0]].parents.len() == 1
+        })
+        .collect();
+    let refutation = derivation.refutation().map(Node::name);
+
+    let mut vertices = vec![];
+    let mut edges = vec![];
+    for (index, node) in nodes.iter().enumerate() {
+        if absorbed[index] {
+            continue;
+        }
+
+        let mut chain = vec![index];
+        while let [parent] = nodes[chain[0]].parents[..] {
+            if !absorbed[parent] {
+                break;
+            }
+            chain.insert(0, parent);
+        }
+        let rules: Vec<_> = chain
+            .iter()
+            .filter_map(|index| nodes[*index].source.as_ref()?.rule())
+            .map(|rule| rule.to_string())
+            .collect();
+        let rules = rules.join(", ");
+        for parent in &nodes[chain[0]].parents {
+            let rules = rules.clone();
+            edges.push(Edge {
+                source: *parent,
+                target: index,
+                rules,
+            });
+        }
+
+        let chained = || chain.iter().map(|index| &nodes[*index]);
+        let conjecture = chained().any(|node| {
+            let role = (node.formula.role().0).0;
+            matches!(role, "conjecture" | "negated_conjecture")
+        });
+        let highlight = if !options.highlight {
+            None
+        } else if chained().any(|node| Some(node.name()) == refutation) {
+            Some(Highlight::Refutation)
+        } else if conjecture {
+            Some(Highlight::Conjecture)
+        } else {
+            None
+        };
+        let collapsed = chain[..chain.len() - 1]
+            .iter()
+            .map(|index| nodes[*index].name().to_string())
+            .collect();
+        let formula = formula(&node.formula, &options.pretty);
+        vertices.push((
+            index,
+            Vertex {
+                node,
+                collapsed,
+                formula,
+                highlight,
+            },
+        ));
+    }
+    (vertices, edges)
+}
+
+/// the first line of a node label: name, role and any collapsed nodes
+fn heading(vertex: &Vertex) -> String {
+    let name = vertex.node.name();
+    let role = vertex.node.formula.role();
+    if vertex.collapsed.is_empty() {
+        format!("{} ({})", name, role)
+    } else {
+        let collapsed = vertex.collapsed.join(", ");
+        format!("{} ({}) [collapsed {}]", name, role, collapsed)
+    }
+}
+
+fn dot_string(text: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\l"),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// export a derivation in Graphviz DOT format
+///
+/// Nodes are labelled with the name, role and formula, edges go from parents to children and are labelled with inference rules.
+pub fn dot(derivation: &Derivation, options: &GraphOptions) -> String {
+    let (vertices, edges) = graph(derivation, options);
+    let mut output = String::new();
+    writeln!(output, "digraph derivation {{").expect("write to string");
+    writeln!(output, "  node [shape=box, fontname=\"monospace\"];")
+        .expect("write to string");
+    for (index, vertex) in &vertices {
+        let label = format!("{}\n{}\n", heading(vertex), vertex.formula);
+        write!(output, "  n{} [label={}", index, dot_string(&label))
+            .expect("write to string");
+        if let Some(highlight) = vertex.highlight {
+            write!(output, ", style=filled, fillcolor={}", highlight.colour())
+                .expect("write to string");
+        }
+        writeln!(output, "];").expect("write to string");
+    }
+    for edge in &edges {
+        write!(output, "  n{} -> n{}", edge.source, edge.target)
+            .expect("write to string");
+        if !edge.rules.is_empty() {
+            write!(output, " [label={}]", dot_string(&edge.rules))
+                .expect("write to string");
+        }
+        writeln!(output, ";").expect("write to string");
+    }
+    writeln!(output, "}}").expect("write to string");
+    output
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const GRAPHML_KEYS: &[(&str, &str)] = &[
+    ("name", "node"),
+    ("role", "node"),
+    ("formula", "node"),
+    ("collapsed", "node"),
+    ("highlight", "node"),
+    ("rule", "edge"),
+];
+
+/// export a derivation in GraphML format
+///
+/// Nodes have `name`, `role` and `formula` data, and `collapsed` and `highlight` if applicable.
+/// Edges go from parents to children and have `rule` data.
+pub fn graphml(derivation: &Derivation, options: &GraphOptions) -> String {
+    let (vertices, edges) = graph(derivation, options);
+    let mut output = String::new();
+    writeln!(output, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+        .expect("write to string");
+    writeln!(
+        output,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )
+    .expect("write to string");
+    for (key, domain) in GRAPHML_KEYS {
+        writeln!(
+            output,
+            "  <key id=\"{0}\" for=\"{1}\" attr.name=\"{0}\" attr.type=\"string\"/>",
+            key, domain
+        )
+        .expect("write to string");
+    }
+    writeln!(
+        output,
+        "  <graph id=\"derivation\" edgedefault=\"directed\">"
+    )
+    .expect("write to string");
+    for (index, vertex) in &vertices {
+        writeln!(output, "    <node id=\"n{}\">", index)
+            .expect("write to string");
+        let mut data = vec![
+            ("name", vertex.node.name().to_string()),
+            ("role", vertex.node.formula.role().to_string()),
+            ("formula", vertex.formula.clone()),
+        ];
+        if !vertex.collapsed.is_empty() {
+            data.push(("collapsed", vertex.collapsed.join(", ")));
+        }
+        if let Some(highlight) = vertex.highlight {
+            data.push(("highlight", highlight.name().to_string()));
+        }
+        for (key, value) in data {
+            writeln!(
+                output,
+                "      <data key=\"{}\">{}</data>",
+                key,
+                xml_escape(&value)
+            )
+            .expect("write to string");
+        }
+        writeln!(output, "    </node>").expect("write to string");
+    }
+    for edge in &edges {
+        write!(
+            output,
+            "    <edge source=\"n{}\" target=\"n{}\"",
+            edge.source, edge.target
+        )
+        .expect("write to string");
+        if edge.rules.is_empty() {
+            writeln!(output, "/>").expect("write to string");
+        } else {
+            writeln!(
+                output,
+                "><data key=\"rule\">{}</data></edge>",
+                xml_escape(&edge.rules)
+            )
+            .expect("write to string");
+        }
+    }
+    writeln!(output, "  </graph>").expect("write to string");
+    writeln!(output, "</graphml>").expect("write to string");
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    const PROOF: &[u8] = b"
+fof(ax, axiom, p => q, file('x.p', ax)).
+fof(goal, conjecture, q, file('x.p', goal)).
+fof(negated, negated_conjecture, ~q, inference(negate, [], [goal])).
+fof(flat, plain, ~q, inference(flattening, [], [negated])).
+cnf(c1, plain, ~p | q, inference(clausify, [], [ax])).
+cnf(c2, plain, ~q, inference(clausify, [], [flat])).
+cnf(refute, plain, ($false), inference(resolution, [], [c1, c2])).
+";
+
+    fn derivation() -> Derivation<'static> {
+        Derivation::new(problem(PROOF))
+    }
+
+    #[test]
+    fn test_dot() {
+        let derivation = derivation();
+        let options = GraphOptions::default();
+        let dot = dot(&derivation, &options);
+        assert!(dot.starts_with("digraph derivation {\n"));
+        assert!(dot.contains("  n0 [label=\"ax (axiom)\\lp => q\\l\"];\n"));
+        assert!(dot.contains("fillcolor=lightblue"));
+        assert!(dot.contains(
+            "  n6 [label=\"refute (plain)\\l( $false )\\l\", style=filled, fillcolor=lightcoral];\n"
+        ));
+        assert!(dot.contains("  n4 -> n6 [label=\"resolution\"];\n"));
+        assert_eq!(dot.matches("->").count(), 6);
+
+        let collapsed = GraphOptions {
+            collapse_chains: true,
+            ..options
+        };
+        let dot = super::dot(&derivation, &collapsed);
+        assert!(dot.contains(
+            "  n5 [label=\"c2 (plain) [collapsed negated, flat]\\l~ q\\l\", style=filled, fillcolor=lightblue];\n"
+        ));
+        assert!(dot.contains(
+            "  n1 -> n5 [label=\"negate, flattening, clausify\"];\n"
+        ));
+        assert_eq!(dot.matches("->").count(), 4);
+    }
+
+    #[test]
+    fn test_graphml() {
+        let derivation = derivation();
+        let graphml = graphml(&derivation, &GraphOptions::default());
+        assert!(graphml.contains("<data key=\"formula\">p =&gt; q</data>"));
+        assert!(graphml.contains("<data key=\"highlight\">refutation</data>"));
+        assert!(graphml.contains(
+            "<edge source=\"n1\" target=\"n2\"><data key=\"rule\">negate</data></edge>"
+        ));
+        assert_eq!(graphml.matches("<node ").count(), 7);
+        assert_eq!(graphml.matches("<edge ").count(), 6);
+    }
+}
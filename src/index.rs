@@ -0,0 +1,385 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::fof;
+use crate::subst::{atom_view, function_view, Arguments, Head, Substitution};
+
+/// one symbol in the flattened form of a term
+///
+/// Terms are flattened in pre-order: `f(X, g(a))` becomes `f/2, *, g/1, a/0`.
+/// All variables are the same key.
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Key<'a> {
+    Variable,
+    Functor(Functor<'a>, usize),
+    Defined(DefinedFunctor<'a>, usize),
+    System(SystemFunctor<'a>, usize),
+    Value(DefinedTerm<'a>),
+    Equality,
+}
+
+impl<'a> Key<'a> {
+    pub(crate) fn new(head: Head<'_, 'a>, arity: usize) -> Self {
+        match head {
+            Head::Plain(functor) => Key::Functor(functor.clone(), arity),
+            Head::Defined(functor) => Key::Defined(functor.clone(), arity),
+            Head::System(functor) => Key::System(functor.clone(), arity),
+            Head::Value(value) => Key::Value(value.clone()),
+            Head::Equality => Key::Equality,
+        }
+    }
+
+    /// the number of arguments following this key
+    pub fn arity(&self) -> usize {
+        match self {
+            Key::Variable | Key::Value(_) => 0,
+            Key::Functor(_, arity)
+            | Key::Defined(_, arity)
+            | Key::System(_, arity) => *arity,
+            Key::Equality => 2,
+        }
+    }
+}
+
+fn flatten_arguments<'a>(
+    arguments: Arguments<'_, 'a>,
+    keys: &mut Vec<Key<'a>>,
+) {
+    for argument in arguments.iter() {
+        flatten(argument, keys);
+    }
+}
+
+fn flatten<'a>(term: &fof::Term<'a>, keys: &mut Vec<Key<'a>>) {
+    match term {
+        fof::Term::Variable(_) => keys.push(Key::Variable),
+        fof::Term::Function(function) => {
+            let (head, arguments) = function_view(function);
+            keys.push(Key::new(head, arguments.iter().count()));
+            flatten_arguments(arguments, keys);
+        }
+    }
+}
+
+/// syntax which can be stored in a `DiscriminationTree`
+pub trait Indexable<'a> {
+    /// the flattened form of `self`
+    fn keys(&self) -> Vec<Key<'a>>;
+
+    /// the substitution mapping `self` to `other`, if there is one
+    fn matching(&self, other: &Self) -> Option<Substitution<'a>>;
+
+    /// a most general unifier of `self` and `other`, if there is one
+    fn unifier(&self, other: &Self) -> Option<Substitution<'a>>;
+}
+
+impl<'a> Indexable<'a> for fof::Term<'a> {
+    fn keys(&self) -> Vec<Key<'a>> {
+        let mut keys = vec![];
+        flatten(self, &mut keys);
+        keys
+    }
+
+    fn matching(&self, other: &Self) -> Option<Substitution<'a>> {
+        crate::subst::matching(self, other)
+    }
+
+    fn unifier(&self, other: &Self) -> Option<Substitution<'a>> {
+        crate::subst::unify(self, other)
+    }
+}
+
+impl<'a> Indexable<'a> for fof::AtomicFormula<'a> {
+    fn keys(&self) -> Vec<Key<'a>> {
+        let (head, arguments) = atom_view(self);
+        let mut keys = vec![Key::new(head, arguments.iter().count())];
+        flatten_arguments(arguments, &mut keys);
+        keys
+    }
+
+    fn matching(&self, other: &Self) -> Option<Substitution<'a>> {
+        let mut substitution = Substitution::new();
+        if substitution.match_atom(self, other) {
+            Some(substitution)
+        } else {
+            None
+        }
+    }
+
+    fn unifier(&self, other: &Self) -> Option<Substitution<'a>> {
+        let mut substitution = Substitution::new();
+        if substitution.unify_atoms(self, other) {
+            Some(substitution)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Retrieval {
+    Generalisations,
+    Instances,
+    Unifiable,
+}
+
+#[derive(Clone, Debug)]
+struct Node<'a, T, V> {
+    children: BTreeMap<Key<'a>, Node<'a, T, V>>,
+    entries: Vec<(T, V)>,
+}
+
+impl<'a, T, V> Default for Node<'a, T, V> {
+    fn default() -> Self {
+        Self {
+            children: BTreeMap::new(),
+            entries: vec![],
+        }
+    }
+}
+
+impl<'a, T, V> Node<'a, T, V> {
+    /// nodes reached by skipping `pending` terms from here
+    fn skip<'s>(&'s self, pending: usize, nodes: &mut Vec<&'s Self>) {
+        if pending == 0 {
+            nodes.push(self);
+            return;
+        }
+        for (key, child) in &self.children {
+            child.skip(pending - 1 + key.arity(), nodes);
+        }
+    }
+
+    /// leaves which may store a term related to `query`
+    ///
+    /// `ends[i]` is the position in `query` after the subterm starting at `i`.
+    fn candidates<'s>(
+        &'s self,
+        query: &[Key<'a>],
+        ends: &[usize],
+        position: usize,
+        retrieval: Retrieval,
+        leaves: &mut Vec<&'s Self>,
+    ) {
+        if position == query.len() {
+            leaves.push(self);
+            return;
+        }
+        let key = &query[position];
+        let next = position + 1;
+        if *key == Key::Variable {
+            if retrieval == Retrieval::Generalisations {
+                if let Some(child) = self.children.get(&Key::Variable) {
+                    child.candidates(query, ends, next, retrieval, leaves);
+                }
+                return;
+            }
+            let mut skipped = vec![];
+            self.skip(1, &mut skipped);
+            for node in skipped {
+                node.candidates(query, ends, next, retrieval, leaves);
+            }
+            return;
+        }
+        if let Some(child) = self.children.get(key) {
+            child.candidates(query, ends, next, retrieval, leaves);
+        }
+        if retrieval != Retrieval::Instances {
+            if let Some(child) = self.children.get(&Key::Variable) {
+                let end = ends[position];
+                child.candidates(query, ends, end, retrieval, leaves);
+            }
+        }
+    }
+}
+
+/// a discrimination tree, indexing terms or atoms to retrieve related ones
+///
+/// The tree is imperfect, as all variables share one key, but retrieved entries are checked with matching or unification, so results are exact.
+/// Variables in queries are not renamed apart from those in the index: for unification, use disjoint variables.
+#[derive(Clone, Debug)]
+pub struct DiscriminationTree<'a, T, V> {
+    root: Node<'a, T, V>,
+    len: usize,
+}
+
+impl<'a, T, V> Default for DiscriminationTree<'a, T, V> {
+    fn default() -> Self {
+        Self {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+}
+
+impl<'a, T: Indexable<'a>, V> DiscriminationTree<'a, T, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the number of stored entries
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// whether there are no stored entries
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// store `item` with an associated `value`
+    pub fn insert(&mut self, item: T, value: V) {
+        let mut node = &mut self.root;
+        for key in item.keys() {
+            node = node.children.entry(key).or_default();
+        }
+        node.entries.push((item, value));
+        self.len += 1;
+    }
+
+    fn retrieve(
+        &self,
+        query: &T,
+        retrieval: Retrieval,
+    ) -> Vec<(&T, &V, Substitution<'a>)> {
+        let keys = query.keys();
+        let mut ends = vec![0; keys.len()];
+        for position in (0..keys.len()).rev() {
+            let mut end = position + 1;
+            for _ in 0..keys[position].arity() {
+                end = ends[end];
+            }
+            ends[position] = end;
+        }
+        let mut leaves = vec![];
+        self.root
+            .candidates(&keys, &ends, 0, retrieval, &mut leaves);
+        let mut results = vec![];
+        for leaf in leaves {
+            for (item, value) in &leaf.entries {
+                let substitution = match retrieval {
+                    Retrieval::Generalisations => item.matching(query),
+                    Retrieval::Instances => query.matching(item),
+                    Retrieval::Unifiable => item.unifier(query),
+                };
+                if let Some(substitution) = substitution {
+                    results.push((item, value, substitution));
+                }
+            }
+        }
+        results
+    }
+
+    /// stored entries which match `query`, with the matching substitution
+    pub fn generalisations(
+        &self,
+        query: &T,
+    ) -> Vec<(&T, &V, Substitution<'a>)> {
+        self.retrieve(query, Retrieval::Generalisations)
+    }
+
+    /// stored entries which `query` matches, with the matching substitution
+    pub fn instances(&self, query: &T) -> Vec<(&T, &V, Substitution<'a>)> {
+        self.retrieve(query, Retrieval::Instances)
+    }
+
+    /// stored entries which unify with `query`, with a most general unifier
+    pub fn unifiable(&self, query: &T) -> Vec<(&T, &V, Substitution<'a>)> {
+        self.retrieve(query, Retrieval::Unifiable)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cnf;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn term(input: &[u8]) -> fof::Term<'_> {
+        check_parse(fof::Term::parse, input)
+    }
+
+    fn names<T, V: ToString>(
+        results: Vec<(&T, &V, Substitution)>,
+    ) -> Vec<String> {
+        let mut names: Vec<_> = results
+            .into_iter()
+            .map(|(_, value, _)| value.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_terms() {
+        let mut index = DiscriminationTree::new();
+        let terms: [&[u8]; 6] = [
+            b"f(X, a)\0",
+            b"f(b, a)\0",
+            b"f(g(X), Y)\0",
+            b"g(X)\0",
+            b"X\0",
+            b"f(X, X)\0",
+        ];
+        for (number, input) in terms.iter().enumerate() {
+            index.insert(term(input), number);
+        }
+        assert_eq!(index.len(), 6);
+        assert_eq!(
+            names(index.generalisations(&term(b"f(b, a)\0"))),
+            ["0", "1", "4"]
+        );
+        assert_eq!(
+            names(index.generalisations(&term(b"f(g(a), g(a))\0"))),
+            ["2", "4", "5"]
+        );
+        assert_eq!(names(index.instances(&term(b"f(Z, a)\0"))), ["0", "1"]);
+        assert_eq!(
+            names(index.instances(&term(b"f(Z, W)\0"))),
+            ["0", "1", "2", "5"]
+        );
+        assert_eq!(
+            names(index.unifiable(&term(b"f(Z, b)\0"))),
+            ["2", "4", "5"]
+        );
+    }
+
+    #[test]
+    fn test_atoms() {
+        let mut index = DiscriminationTree::new();
+        let clauses = [
+            &b"p(X, f(X)) | ~q(X)\0"[..],
+            &b"X = f(a)\0"[..],
+            &b"~p(a, Y)\0"[..],
+        ];
+        for input in &clauses {
+            let clause = check_parse(cnf::Disjunction::parse, input);
+            for literal in clause.0 {
+                let atom = match literal {
+                    cnf::Literal::Atomic(atom)
+                    | cnf::Literal::NegatedAtomic(atom) => atom,
+                    cnf::Literal::Infix(_) => continue,
+                };
+                let name = atom.to_string();
+                index.insert(atom, name);
+            }
+        }
+        let query = check_parse(fof::AtomicFormula::parse, b"p(a, Z)\0");
+        let results = index.unifiable(&query);
+        assert_eq!(names(results), ["p(X,f(X))", "p(a,Y)"]);
+        let query = check_parse(fof::AtomicFormula::parse, b"b = f(a)\0");
+        let results = index.generalisations(&query);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].2.get(&Variable(UpperWord("X"))),
+            Some(&term(b"b\0"))
+        );
+    }
+}
@@ -0,0 +1,1531 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::{rename_symbols, unused, Arena, Fresh};
+use crate::nnf::fof_nnf::{assoc, unit};
+use crate::scope::FreeVariables;
+use crate::subst::{atom_view, function_view, Arguments, Head};
+use crate::top::*;
+use crate::utils::{word_text, Separated};
+
+/// why a formula cannot be written in LADR syntax
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ExportErrorKind {
+    #[display(fmt = "typed formulas are not supported")]
+    Typed,
+    #[display(fmt = "unsupported term {}", _0)]
+    Unsupported(String),
+}
+
+type Translation<T> = core::result::Result<T, ExportErrorKind>;
+
+fn is_ordinary_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '$')
+}
+
+/// whether LADR reads `text` as an ordinary constant, rather than a variable, keyword or operator
+fn is_ordinary(text: &str) -> bool {
+    match text.chars().next() {
+        Some(first) if first.is_ascii_alphanumeric() => {
+            !matches!(first, 'u'..='z')
+                && text.chars().all(is_ordinary_char)
+                && !matches!(text, "all" | "exists")
+        }
+        _ => false,
+    }
+}
+
+/// how tightly a formula binds, which decides where it needs parentheses
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Level {
+    /// atoms, negations and parenthesised formulas
+    Atom,
+    /// equations and disequations
+    Equation,
+    /// binary and quantified formulas
+    Compound,
+}
+
+/// `text` as the operand of a binary connective
+fn operand((text, level): (String, Level)) -> String {
+    if level == Level::Compound {
+        format!("({})", text)
+    } else {
+        text
+    }
+}
+
+/// `text` as the operand of a negation or quantifier
+fn prefix_operand((text, level): (String, Level)) -> String {
+    if level == Level::Atom && !text.starts_with('-') {
+        text
+    } else {
+        format!("({})", text)
+    }
+}
+
+/// writes a formula, renaming its variables
+struct Writer<'s, 'a> {
+    symbols: &'s BTreeMap<AtomicWord<'a>, String>,
+    variables: BTreeMap<Variable<'a>, String>,
+    used: BTreeSet<String>,
+}
+
+impl<'s, 'a> Writer<'s, 'a> {
+    fn new(symbols: &'s BTreeMap<AtomicWord<'a>, String>) -> Self {
+        let variables = BTreeMap::new();
+        let used = BTreeSet::new();
+        Self {
+            symbols,
+            variables,
+            used,
+        }
+    }
+
+    /// a LADR variable for `variable`, starting with `u` to `z`
+    fn variable(&mut self, variable: &Variable<'a>) -> String {
+        if let Some(name) = self.variables.get(variable) {
+            return name.clone();
+        }
+        let text = (variable.0).0;
+        let base = match text.chars().next() {
+            Some(first @ 'U'..='Z') => {
+                format!("{}{}", first.to_ascii_lowercase(), &text[1..])
+            }
+            _ => format!("v{}", text),
+        };
+        let name = unused(&mut self.used, base);
+        self.variables.insert(variable.clone(), name.clone());
+        name
+    }
+
+    fn term(&mut self, term: &fof::Term<'a>) -> Translation<String> {
+        match term {
+            fof::Term::Variable(variable) => Ok(self.variable(variable)),
+            fof::Term::Function(function) => {
+                let (head, arguments) = function_view(function);
+                self.application(head, arguments, || term.to_string())
+            }
+        }
+    }
+
+    fn application(
+        &mut self,
+        head: Head<'_, 'a>,
+        arguments: Arguments<'_, 'a>,
+        text: impl FnOnce() -> String,
+    ) -> Translation<String> {
+        let arguments = arguments
+            .iter()
+            .map(|argument| self.term(argument))
+            .collect::<Translation<Vec<_>>>()?;
+        let symbols = self.symbols;
+        let name = match head {
+            Head::Plain(functor) => symbols[&functor.0].as_str(),
+            Head::Defined(functor) if arguments.is_empty() => {
+                match ((functor.0).0).0 .0 {
+                    "true" => "$T",
+                    "false" => "$F",
+                    _ => return Err(ExportErrorKind::Unsupported(text())),
+                }
+            }
+            Head::Equality => {
+                return Ok(format!("{} = {}", arguments[0], arguments[1]))
+            }
+            _ => return Err(ExportErrorKind::Unsupported(text())),
+        };
+        if arguments.is_empty() {
+            Ok(name.to_string())
+        } else {
+            Ok(format!("{}({})", name, arguments.join(",")))
+        }
+    }
+
+    fn atom(
+        &mut self,
+        atom: &fof::AtomicFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        let (head, arguments) = atom_view(atom);
+        let level = if head == Head::Equality {
+            Level::Equation
+        } else {
+            Level::Atom
+        };
+        let text = self.application(head, arguments, || atom.to_string())?;
+        Ok((text, level))
+    }
+
+    /// a negated atom, as a disequation if it is an equation
+    fn negated(
+        &mut self,
+        atom: &fof::AtomicFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        if let (Head::Equality, Arguments::Pair([left, right])) =
+            atom_view(atom)
+        {
+            return self.disequation(left, right);
+        }
+        let text = prefix_operand(self.atom(atom)?);
+        Ok((format!("-{}", text), Level::Atom))
+    }
+
+    fn disequation(
+        &mut self,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+    ) -> Translation<(String, Level)> {
+        let left = self.term(left)?;
+        let right = self.term(right)?;
+        Ok((format!("{} != {}", left, right), Level::Equation))
+    }
+
+    fn clause(&mut self, formula: &cnf::Formula<'a>) -> Translation<String> {
+        let (cnf::Formula::Disjunction(disjunction)
+        | cnf::Formula::Parenthesised(disjunction)) = formula;
+        let mut literals = vec![];
+        for literal in &disjunction.0 {
+            let (text, _) = match literal {
+                cnf::Literal::Atomic(atom) => self.atom(atom)?,
+                cnf::Literal::NegatedAtomic(atom) => self.negated(atom)?,
+                cnf::Literal::Infix(infix) => {
+                    self.disequation(&infix.left, &infix.right)?
+                }
+            };
+            literals.push(text);
+        }
+        Ok(literals.join(" | "))
+    }
+
+    fn fof_unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.fof_unitary(unitary),
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary),
+        }
+    }
+
+    fn fof_unary(
+        &mut self,
+        formula: &fof::UnaryFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        match formula {
+            fof::UnaryFormula::Unary(_, negated) => match &**negated {
+                fof::UnitFormula::Unitary(fof::UnitaryFormula::Atomic(
+                    atom,
+                )) => self.negated(atom),
+                negated => {
+                    let text = prefix_operand(self.fof_unit(negated)?);
+                    Ok((format!("-{}", text), Level::Atom))
+                }
+            },
+            fof::UnaryFormula::InfixUnary(infix) => {
+                self.disequation(&infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn fof_unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let quantifier = match quantified.quantifier {
+                    fof::Quantifier::Forall => "all",
+                    fof::Quantifier::Exists => "exists",
+                };
+                let mut text = String::new();
+                for variable in &quantified.bound.0 {
+                    let variable = self.variable(variable);
+                    write!(text, "{} {} ", quantifier, variable)
+                        .expect("write to string");
+                }
+                let body = self.fof_unit(&quantified.formula)?;
+                text.push_str(&prefix_operand(body));
+                Ok((text, Level::Compound))
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.atom(atom),
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.fof_logic(formula)
+            }
+        }
+    }
+
+    fn fof_logic(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+    ) -> Translation<(String, Level)> {
+        use fof::{BinaryAssoc, BinaryFormula};
+        let binary = match formula {
+            fof::LogicFormula::Binary(binary) => binary,
+            fof::LogicFormula::Unary(unary) => return self.fof_unary(unary),
+            fof::LogicFormula::Unitary(unitary) => {
+                return self.fof_unitary(unitary)
+            }
+        };
+        let (units, op) = match binary {
+            BinaryFormula::Assoc(BinaryAssoc::Or(or)) => (&or.0, " | "),
+            BinaryFormula::Assoc(BinaryAssoc::And(and)) => (&and.0, " & "),
+            BinaryFormula::Nonassoc(nonassoc) => {
+                let left = operand(self.fof_unit(&nonassoc.left)?);
+                let right = operand(self.fof_unit(&nonassoc.right)?);
+                let (op, negated) = match nonassoc.op {
+                    NonassocConnective::LRImplies => ("->", false),
+                    NonassocConnective::RLImplies => ("<-", false),
+                    NonassocConnective::Equivalent => ("<->", false),
+                    NonassocConnective::NotEquivalent => ("<->", true),
+                    NonassocConnective::NotOr => ("|", true),
+                    NonassocConnective::NotAnd => ("&", true),
+                };
+                let text = format!("{} {} {}", left, op, right);
+                return Ok(if negated {
+                    (format!("-({})", text), Level::Atom)
+                } else {
+                    (text, Level::Compound)
+                });
+            }
+        };
+        let mut operands = vec![];
+        for unit in units {
+            operands.push(operand(self.fof_unit(unit)?));
+        }
+        Ok((operands.join(op), Level::Compound))
+    }
+}
+
+/// the argument of a `label` attribute for `name`, if LADR can represent it
+fn label(name: &Name) -> Option<String> {
+    let text = match name {
+        Name::AtomicWord(word) => word_text(word),
+        Name::Integer(integer) => integer.0.to_string(),
+    };
+    if is_ordinary(&text) {
+        Some(text)
+    } else if text.contains('"') {
+        None
+    } else {
+        Some(format!("\"{}\"", text))
+    }
+}
+
+/// export FOF and CNF formulas as LADR `formulas` lists, for Prover9 or Mace4
+///
+/// Conjectures become `formulas(goals)` and all other formulas, including negated conjectures, `formulas(assumptions)`.
+/// Variables are renamed to start with `u` to `z` as LADR requires, and symbols which LADR would read as something else are renamed with a `c_` prefix.
+/// Formulas are labelled with their names.
+pub fn export<'a>(
+    formulas: &[AnnotatedFormula<'a>],
+) -> Result<String, ExportError<'a, ExportErrorKind>> {
+    let symbols = rename_symbols(formulas, is_ordinary);
+    let mut assumptions = String::new();
+    let mut goals = String::new();
+    for annotated in formulas {
+        let error = |kind| ExportError {
+            name: annotated.name().clone(),
+            kind,
+        };
+        let mut writer = Writer::new(&symbols);
+        let text = match annotated {
+            AnnotatedFormula::Fof(fof) => {
+                writer.fof_logic(&fof.0.formula.0).map(|(text, _)| text)
+            }
+            AnnotatedFormula::Cnf(cnf) => writer.clause(&cnf.0.formula),
+            AnnotatedFormula::Tfx(_) => {
+                return Err(error(ExportErrorKind::Typed))
+            }
+        };
+        let text = text.map_err(error)?;
+        let section = if (annotated.role().0).0 == "conjecture" {
+            &mut goals
+        } else {
+            &mut assumptions
+        };
+        section.push_str(&text);
+        if let Some(argument) = label(annotated.name()) {
+            write!(section, " # label({})", argument)
+                .expect("write to string");
+        }
+        section.push_str(".\n");
+    }
+    let mut output = String::new();
+    for (list, section) in [("assumptions", assumptions), ("goals", goals)] {
+        if section.is_empty() {
+            continue;
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        write!(output, "formulas({}).\n{}end_of_list.\n", list, section)
+            .expect("write to string");
+    }
+    Ok(output)
+}
+
+/// what went wrong in `import` or `import_models`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ImportError {
+    #[display(fmt = "syntax error at byte {}", _0)]
+    Syntax(usize),
+    #[display(fmt = "unsupported {}", _0)]
+    Unsupported(String),
+    #[display(fmt = "malformed interpretation {}", _0)]
+    Interpretation(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Token<'t> {
+    Symbol(&'t str),
+    Quoted(&'t str),
+    Open,
+    Close,
+    OpenList,
+    CloseList,
+    Comma,
+    End,
+    Eof,
+}
+
+/// a LADR term, with operators as applications
+#[derive(Clone, Debug)]
+enum Tree<'t> {
+    Node(&'t str, Vec<Tree<'t>>),
+    Quoted(&'t str),
+    List(Vec<Tree<'t>>),
+}
+
+impl<'t> fmt::Display for Tree<'t> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Tree::Node(symbol, arguments) if arguments.is_empty() => {
+                write!(f, "{}", symbol)
+            }
+            Tree::Node(symbol, arguments) => {
+                write!(f, "{}({})", symbol, Separated(',', arguments))
+            }
+            Tree::Quoted(text) => write!(f, "\"{}\"", text),
+            Tree::List(items) => write!(f, "[{}]", Separated(',', items)),
+        }
+    }
+}
+
+fn node<'t>(symbol: &'t str, arguments: Vec<Tree<'t>>) -> Tree<'t> {
+    Tree::Node(symbol, arguments)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Fixity {
+    Xfx,
+    Xfy,
+    Yfx,
+}
+
+/// the precedence and fixity of a LADR infix operator, following LADR's defaults
+fn infix(symbol: &str) -> Option<(u32, Fixity)> {
+    Some(match symbol {
+        "#" => (810, Fixity::Xfy),
+        "<->" | "<-" => (800, Fixity::Xfx),
+        "->" => (800, Fixity::Xfy),
+        "|" => (790, Fixity::Xfy),
+        "&" => (780, Fixity::Xfy),
+        "=" | "!=" | "==" | "<" | "<=" | ">" | ">=" => (700, Fixity::Xfx),
+        "+" => (500, Fixity::Yfx),
+        "*" | "/" | "\\" | "@" => (400, Fixity::Yfx),
+        "^" => (300, Fixity::Xfy),
+        _ => return None,
+    })
+}
+
+/// the precedence of the operand of a prefix negation or quantifier
+const PREFIX: u32 = 700;
+/// the precedence of the postfix `'` operator
+const POSTFIX: u32 = 200;
+/// the precedence of a whole term
+const TERM: u32 = 1000;
+
+/// a reader of LADR terms
+struct Parser<'t> {
+    input: &'t str,
+    position: usize,
+    /// where the last token read starts
+    start: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn new(input: &'t str, position: usize) -> Self {
+        let start = position;
+        Self {
+            input,
+            position,
+            start,
+        }
+    }
+
+    fn error(&self) -> ImportError {
+        ImportError::Syntax(self.start)
+    }
+
+    fn rest(&self) -> &'t str {
+        &self.input[self.position..]
+    }
+
+    fn skip(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if !trimmed.starts_with('%') {
+                return;
+            }
+            self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    fn next(&mut self) -> Result<Token<'t>, ImportError> {
+        self.skip();
+        self.start = self.position;
+        let rest = self.rest();
+        let first = match rest.chars().next() {
+            Some(first) => first,
+            None => return Ok(Token::Eof),
+        };
+        let special = |c: char| "+-*/\\^<>=`~?@&|!#';:".contains(c);
+        let length = if first == '"' {
+            match rest[1..].find('"') {
+                Some(end) => end + 2,
+                None => return Err(self.error()),
+            }
+        } else if is_ordinary_char(first) {
+            rest.find(|c| !is_ordinary_char(c)).unwrap_or(rest.len())
+        } else if special(first) {
+            rest.find(|c| !special(c)).unwrap_or(rest.len())
+        } else {
+            1
+        };
+        self.position += length;
+        let text = &rest[..length];
+        Ok(match first {
+            '"' => Token::Quoted(&text[1..length - 1]),
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '[' => Token::OpenList,
+            ']' => Token::CloseList,
+            ',' => Token::Comma,
+            '.' => Token::End,
+            _ if is_ordinary_char(first) || special(first) => {
+                Token::Symbol(text)
+            }
+            _ => return Err(self.error()),
+        })
+    }
+
+    fn peek(&mut self) -> Result<Token<'t>, ImportError> {
+        let (position, start) = (self.position, self.start);
+        let token = self.next()?;
+        self.position = position;
+        self.start = start;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ImportError> {
+        if self.next()? == expected {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// comma-separated terms up to `close`
+    fn arguments(
+        &mut self,
+        close: Token,
+    ) -> Result<Vec<Tree<'t>>, ImportError> {
+        let mut arguments = vec![];
+        loop {
+            arguments.push(self.term(TERM)?);
+            match self.next()? {
+                Token::Comma => {}
+                token if token == close => return Ok(arguments),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    /// a term without infix or postfix operators, and its precedence
+    fn primary(&mut self) -> Result<(Tree<'t>, u32), ImportError> {
+        let token = self.next()?;
+        let applied = self.rest().starts_with('(');
+        match token {
+            Token::Symbol(quantifier @ ("all" | "exists")) if !applied => {
+                let variable = match self.next()? {
+                    Token::Symbol(variable)
+                        if variable.chars().all(is_ordinary_char) =>
+                    {
+                        variable
+                    }
+                    _ => return Err(self.error()),
+                };
+                let body = self.term(PREFIX)?;
+                let variable = node(variable, vec![]);
+                Ok((node(quantifier, vec![variable, body]), PREFIX))
+            }
+            Token::Symbol("-") if !applied => {
+                let negated = self.term(PREFIX)?;
+                Ok((node("-", vec![negated]), PREFIX))
+            }
+            Token::Symbol(symbol) if applied => {
+                self.expect(Token::Open)?;
+                Ok((node(symbol, self.arguments(Token::Close)?), 0))
+            }
+            Token::Symbol(symbol) => Ok((node(symbol, vec![]), 0)),
+            Token::Quoted(text) => Ok((Tree::Quoted(text), 0)),
+            Token::Open => {
+                let term = self.term(TERM)?;
+                self.expect(Token::Close)?;
+                Ok((term, 0))
+            }
+            Token::OpenList => {
+                if self.peek()? == Token::CloseList {
+                    self.next()?;
+                    return Ok((Tree::List(vec![]), 0));
+                }
+                Ok((Tree::List(self.arguments(Token::CloseList)?), 0))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// a term whose operators have precedence at most `maximum`
+    fn term(&mut self, maximum: u32) -> Result<Tree<'t>, ImportError> {
+        let (mut term, mut precedence) = self.primary()?;
+        loop {
+            let symbol = match self.peek()? {
+                Token::Symbol(symbol) => symbol,
+                _ => return Ok(term),
+            };
+            if symbol == "'" && precedence <= POSTFIX && POSTFIX <= maximum {
+                self.next()?;
+                term = node(symbol, vec![term]);
+                precedence = POSTFIX;
+                continue;
+            }
+            let (op, fixity) = match infix(symbol) {
+                Some(infix) => infix,
+                None => return Ok(term),
+            };
+            let left = if fixity == Fixity::Yfx { op } else { op - 1 };
+            if op > maximum || precedence > left {
+                return Ok(term);
+            }
+            self.next()?;
+            let right = if fixity == Fixity::Xfy { op } else { op - 1 };
+            term = node(symbol, vec![term, self.term(right)?]);
+            precedence = op;
+        }
+    }
+
+    /// a term terminated by a period, or nothing at the end of the input
+    fn statement(&mut self) -> Result<Option<Tree<'t>>, ImportError> {
+        if self.peek()? == Token::Eof {
+            return Ok(None);
+        }
+        let term = self.term(TERM)?;
+        self.expect(Token::End)?;
+        Ok(Some(term))
+    }
+}
+
+/// a formula and its attributes
+fn split_attributes<'s, 't>(
+    statement: &'s Tree<'t>,
+) -> (&'s Tree<'t>, Vec<&'s Tree<'t>>) {
+    let (formula, mut rest) = match statement {
+        Tree::Node("#", arguments) => (&arguments[0], &arguments[1]),
+        _ => return (statement, vec![]),
+    };
+    let mut attributes = vec![];
+    while let Tree::Node("#", arguments) = rest {
+        attributes.push(&arguments[0]);
+        rest = &arguments[1];
+    }
+    attributes.push(rest);
+    (formula, attributes)
+}
+
+fn is_connective(symbol: &str) -> bool {
+    matches!(
+        symbol,
+        "<->" | "->" | "<-" | "|" | "&" | "-" | "all" | "exists"
+    )
+}
+
+fn is_literal(tree: &Tree) -> bool {
+    match tree {
+        Tree::Node("-", arguments) => match &arguments[0] {
+            Tree::Node(symbol, _) => !is_connective(symbol),
+            tree => !matches!(tree, Tree::List(_)),
+        },
+        Tree::Node(symbol, _) => !is_connective(symbol),
+        tree => !matches!(tree, Tree::List(_)),
+    }
+}
+
+fn is_clause(tree: &Tree) -> bool {
+    match tree {
+        Tree::Node("|", arguments) => arguments.iter().all(is_clause),
+        tree => is_literal(tree),
+    }
+}
+
+fn defined_constant(word: &str) -> DefinedConstant<'_> {
+    DefinedConstant(DefinedFunctor(AtomicDefinedWord(DollarWord(LowerWord(
+        word,
+    )))))
+}
+
+fn equation<'a>(
+    left: Box<fof::Term<'a>>,
+    right: Box<fof::Term<'a>>,
+) -> fof::AtomicFormula<'a> {
+    let op = DefinedInfixPred(InfixEquality);
+    fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Infix(
+        fof::DefinedInfixFormula { left, op, right },
+    ))
+}
+
+/// a TPTP word for a LADR symbol: a lower word if possible, or single-quoted
+fn quote<'a>(
+    arena: &'a Arena,
+    text: &str,
+) -> Result<AtomicWord<'a>, ImportError> {
+    let mut chars = text.chars();
+    let lower = match chars.next() {
+        Some(first) => first.is_ascii_lowercase(),
+        None => false,
+    };
+    if lower && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Ok(AtomicWord::Lower(LowerWord(arena.alloc(text))));
+    }
+    if text.is_empty() || !text.chars().all(|c| matches!(c, ' '..='~')) {
+        return Err(ImportError::Unsupported(format!("\"{}\"", text)));
+    }
+    let escaped = text.replace('\\', "\\\\").replace('\'', "\\'");
+    let quoted = SingleQuoted(arena.alloc(&escaped));
+    Ok(AtomicWord::SingleQuoted(quoted))
+}
+
+/// translates LADR terms, naming each variable consistently within a formula
+struct Importer<'t, 'a> {
+    arena: &'a Arena,
+    variables: BTreeMap<&'t str, Variable<'a>>,
+    used: BTreeSet<String>,
+}
+
+impl<'t, 'a> Importer<'t, 'a> {
+    fn new(arena: &'a Arena) -> Self {
+        let variables = BTreeMap::new();
+        let used = BTreeSet::new();
+        Self {
+            arena,
+            variables,
+            used,
+        }
+    }
+
+    /// the TPTP variable for a LADR variable, which starts with a capital letter
+    fn variable(&mut self, text: &'t str) -> Variable<'a> {
+        if let Some(variable) = self.variables.get(text) {
+            return variable.clone();
+        }
+        let mut base = String::new();
+        for (index, c) in text.chars().enumerate() {
+            base.push(match c {
+                _ if index == 0 => c.to_ascii_uppercase(),
+                '$' => '_',
+                c => c,
+            });
+        }
+        if !base.starts_with(|c: char| c.is_ascii_uppercase()) {
+            base.insert(0, 'V');
+        }
+        let name = unused(&mut self.used, base);
+        let variable = Variable(UpperWord(self.arena.alloc(&name)));
+        self.variables.insert(text, variable.clone());
+        variable
+    }
+
+    fn term(
+        &mut self,
+        tree: &Tree<'t>,
+        bound: &[&'t str],
+    ) -> Result<fof::Term<'a>, ImportError> {
+        let (symbol, arguments) = match tree {
+            Tree::Node(symbol, arguments) => (*symbol, arguments.as_slice()),
+            Tree::Quoted(text) => (*text, [].as_slice()),
+            Tree::List(_) => {
+                return Err(ImportError::Unsupported(tree.to_string()))
+            }
+        };
+        let variable = match symbol.chars().next() {
+            Some(first) => {
+                matches!(first, 'u'..='z') || bound.contains(&symbol)
+            }
+            None => false,
+        };
+        if variable && arguments.is_empty() && !matches!(tree, Tree::Quoted(_))
+        {
+            return Ok(fof::Term::Variable(self.variable(symbol)));
+        }
+        let plain = self.plain(symbol, arguments, bound)?;
+        Ok(fof::Term::Function(Box::new(fof::FunctionTerm::Plain(
+            plain,
+        ))))
+    }
+
+    fn plain(
+        &mut self,
+        symbol: &str,
+        arguments: &[Tree<'t>],
+        bound: &[&'t str],
+    ) -> Result<fof::PlainTerm<'a>, ImportError> {
+        let functor = Functor(quote(self.arena, symbol)?);
+        if arguments.is_empty() {
+            return Ok(fof::PlainTerm::Constant(Constant(functor)));
+        }
+        let mut terms = vec![];
+        for argument in arguments {
+            terms.push(self.term(argument, bound)?);
+        }
+        let arguments = Box::new(fof::Arguments(terms));
+        Ok(fof::PlainTerm::Function(functor, arguments))
+    }
+
+    /// an atom, or `None` for a disequation
+    fn atom(
+        &mut self,
+        tree: &Tree<'t>,
+        bound: &[&'t str],
+    ) -> Result<Option<fof::AtomicFormula<'a>>, ImportError> {
+        let (symbol, arguments) = match tree {
+            Tree::Node(symbol, arguments) => (*symbol, arguments.as_slice()),
+            Tree::Quoted(text) => (*text, [].as_slice()),
+            Tree::List(_) => {
+                return Err(ImportError::Unsupported(tree.to_string()))
+            }
+        };
+        Ok(Some(match (symbol, arguments) {
+            ("$T", []) | ("$F", []) if matches!(tree, Tree::Node(..)) => {
+                let word = if symbol == "$T" { "true" } else { "false" };
+                fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Plain(
+                    fof::DefinedPlainFormula(fof::DefinedPlainTerm::Constant(
+                        defined_constant(word),
+                    )),
+                ))
+            }
+            ("=", [left, right]) if matches!(tree, Tree::Node(..)) => {
+                let left = Box::new(self.term(left, bound)?);
+                let right = Box::new(self.term(right, bound)?);
+                equation(left, right)
+            }
+            ("!=", [_, _]) if matches!(tree, Tree::Node(..)) => {
+                return Ok(None)
+            }
+            _ => fof::AtomicFormula::Plain(fof::PlainAtomicFormula(
+                self.plain(symbol, arguments, bound)?,
+            )),
+        }))
+    }
+
+    fn disequation(
+        &mut self,
+        tree: &Tree<'t>,
+        bound: &[&'t str],
+    ) -> Result<fof::InfixUnary<'a>, ImportError> {
+        let (left, right) = match tree {
+            Tree::Node(_, arguments) => (&arguments[0], &arguments[1]),
+            _ => unreachable!("disequations are nodes"),
+        };
+        let left = Box::new(self.term(left, bound)?);
+        let right = Box::new(self.term(right, bound)?);
+        let op = InfixInequality;
+        Ok(fof::InfixUnary { left, op, right })
+    }
+
+    fn literal(
+        &mut self,
+        tree: &Tree<'t>,
+    ) -> Result<cnf::Literal<'a>, ImportError> {
+        let (mut positive, tree) = match tree {
+            Tree::Node("-", arguments) => (false, &arguments[0]),
+            tree => (true, tree),
+        };
+        let atom = match self.atom(tree, &[])? {
+            Some(atom) => atom,
+            None => {
+                let infix = self.disequation(tree, &[])?;
+                positive = !positive;
+                equation(infix.left, infix.right)
+            }
+        };
+        Ok(match atom {
+            fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Infix(
+                equation,
+            )) if !positive => cnf::Literal::Infix(fof::InfixUnary {
+                left: equation.left,
+                op: InfixInequality,
+                right: equation.right,
+            }),
+            atom if positive => cnf::Literal::Atomic(atom),
+            atom => cnf::Literal::NegatedAtomic(atom),
+        })
+    }
+
+    fn clause(
+        &mut self,
+        tree: &Tree<'t>,
+        literals: &mut Vec<cnf::Literal<'a>>,
+    ) -> Result<(), ImportError> {
+        match tree {
+            Tree::Node("|", arguments) => {
+                for argument in arguments {
+                    self.clause(argument, literals)?;
+                }
+            }
+            tree => literals.push(self.literal(tree)?),
+        }
+        Ok(())
+    }
+
+    fn formula(
+        &mut self,
+        tree: &Tree<'t>,
+        bound: &mut Vec<&'t str>,
+    ) -> Result<fof::LogicFormula<'a>, ImportError> {
+        use fof::{LogicFormula, UnaryFormula, UnitaryFormula};
+        let arguments = match tree {
+            Tree::Node(_, arguments) => arguments.as_slice(),
+            _ => &[],
+        };
+        let nonassoc = |op, left, right| {
+            let left = Box::new(unit(left));
+            let right = Box::new(unit(right));
+            LogicFormula::Binary(fof::BinaryFormula::Nonassoc(
+                fof::BinaryNonassoc { left, op, right },
+            ))
+        };
+        Ok(match (tree, arguments) {
+            (Tree::Node(op @ ("<->" | "->" | "<-"), _), [left, right]) => {
+                let op = match *op {
+                    "<->" => NonassocConnective::Equivalent,
+                    "->" => NonassocConnective::LRImplies,
+                    _ => NonassocConnective::RLImplies,
+                };
+                let left = self.formula(left, bound)?;
+                let right = self.formula(right, bound)?;
+                nonassoc(op, left, right)
+            }
+            (Tree::Node(op @ ("|" | "&"), _), [left, right]) => {
+                let op = if *op == "|" {
+                    AssocConnective::Or
+                } else {
+                    AssocConnective::And
+                };
+                let left = self.formula(left, bound)?;
+                let right = self.formula(right, bound)?;
+                assoc(op, vec![left, right])
+            }
+            (Tree::Node("-", _), [negated]) => {
+                if let Tree::Node("=", equation) = negated {
+                    if equation.len() == 2 {
+                        let infix = self.disequation(negated, bound)?;
+                        return Ok(LogicFormula::Unary(
+                            UnaryFormula::InfixUnary(infix),
+                        ));
+                    }
+                }
+                let negated = Box::new(unit(self.formula(negated, bound)?));
+                LogicFormula::Unary(UnaryFormula::Unary(
+                    UnaryConnective,
+                    negated,
+                ))
+            }
+            (
+                Tree::Node(quantifier @ ("all" | "exists"), _),
+                [Tree::Node(variable, _), body],
+            ) => {
+                let quantifier = if *quantifier == "all" {
+                    fof::Quantifier::Forall
+                } else {
+                    fof::Quantifier::Exists
+                };
+                bound.push(variable);
+                let mut variables = vec![self.variable(variable)];
+                let body = self.formula(body, bound)?;
+                bound.pop();
+                let formula = match body {
+                    LogicFormula::Unitary(UnitaryFormula::Quantified(
+                        inner,
+                    )) if inner.quantifier == quantifier => {
+                        variables.extend(inner.bound.0);
+                        inner.formula
+                    }
+                    body => Box::new(unit(body)),
+                };
+                let bound = fof::VariableList(variables);
+                LogicFormula::Unitary(UnitaryFormula::Quantified(
+                    fof::QuantifiedFormula {
+                        quantifier,
+                        bound,
+                        formula,
+                    },
+                ))
+            }
+            (tree, _) => match self.atom(tree, bound)? {
+                Some(atom) => LogicFormula::Unitary(UnitaryFormula::Atomic(
+                    Box::new(atom),
+                )),
+                None => LogicFormula::Unary(UnaryFormula::InfixUnary(
+                    self.disequation(tree, bound)?,
+                )),
+            },
+        })
+    }
+
+    /// a formula universally closed over its free variables
+    fn closed(
+        &mut self,
+        tree: &Tree<'t>,
+    ) -> Result<fof::Formula<'a>, ImportError> {
+        let formula = fof::Formula(self.formula(tree, &mut vec![])?);
+        let free = formula.free_variables();
+        if free.is_empty() {
+            return Ok(formula);
+        }
+        let quantified = fof::QuantifiedFormula {
+            quantifier: fof::Quantifier::Forall,
+            bound: fof::VariableList(free),
+            formula: Box::new(unit(formula.0)),
+        };
+        Ok(fof::Formula(fof::LogicFormula::Unitary(
+            fof::UnitaryFormula::Quantified(quantified),
+        )))
+    }
+}
+
+fn annotate<'a, T>(
+    name: Name<'a>,
+    role: &'a str,
+    formula: T,
+) -> Annotated<'a, T> {
+    Annotated {
+        name,
+        role: FormulaRole(LowerWord(role)),
+        formula: Box::new(formula),
+        annotations: Annotations(None),
+    }
+}
+
+/// import the `formulas` and `clauses` lists of a Prover9 or Mace4 input file, storing new symbols in `arena`
+///
+/// Quantifier-free disjunctions of literals become CNF axioms, and other formulas FOF axioms universally closed over their free variables.
+/// Goals become FOF conjectures in the same way, although Prover9 need only prove one of several goals.
+/// Formulas are named by their `label` attribute, or `axiomN` or `goalN` if they have none.
+/// Other statements such as `set` and `assign` are ignored, as are `hints` and other kinds of list.
+/// Symbols are single-quoted if need be, so that `x * y` becomes `'*'(X,Y)`.
+pub fn import<'a>(
+    input: &str,
+    arena: &'a Arena,
+) -> Result<Vec<AnnotatedFormula<'a>>, ImportError> {
+    let mut parser = Parser::new(input, 0);
+    let mut statements = vec![];
+    let mut list = None;
+    while let Some(statement) = parser.statement()? {
+        match (list, &statement) {
+            (Some(_), Tree::Node("end_of_list", arguments))
+                if arguments.is_empty() =>
+            {
+                list = None
+            }
+            (Some(kind), _) => statements.push((kind, statement)),
+            (None, Tree::Node(kind, arguments)) if arguments.len() == 1 => {
+                list = match (*kind, &arguments[0]) {
+                    ("formulas" | "clauses", Tree::Node("hints", _)) => {
+                        Some("")
+                    }
+                    ("formulas" | "clauses", Tree::Node("goals", _)) => {
+                        Some("goal")
+                    }
+                    ("formulas" | "clauses", _) => Some("axiom"),
+                    (
+                        "list" | "terms" | "weights" | "actions"
+                        | "interpretations",
+                        _,
+                    ) => Some(""),
+                    _ => None,
+                }
+            }
+            (None, _) => {}
+        }
+    }
+
+    let mut importer = Importer::new(arena);
+    let mut fresh = Fresh::new(arena);
+    let mut labelled = vec![];
+    for (kind, statement) in statements {
+        if kind.is_empty() {
+            continue;
+        }
+        let (formula, attributes) = split_attributes(&statement);
+        let mut name = None;
+        for attribute in attributes {
+            if let Tree::Node("label", arguments) = attribute {
+                let text = match arguments.as_slice() {
+                    [Tree::Node(text, _)] | [Tree::Quoted(text)] => *text,
+                    _ => {
+                        return Err(ImportError::Unsupported(
+                            attribute.to_string(),
+                        ))
+                    }
+                };
+                fresh.avoid(arena.alloc(text));
+                name = Some(if text.bytes().all(|b| b.is_ascii_digit()) {
+                    Name::Integer(Integer(arena.alloc(text)))
+                } else {
+                    Name::AtomicWord(quote(arena, text)?)
+                });
+            }
+        }
+        labelled.push((kind, name, formula.clone()));
+    }
+
+    let mut formulas = vec![];
+    for (kind, name, tree) in labelled {
+        importer.variables.clear();
+        importer.used.clear();
+        let name = match name {
+            Some(name) => name,
+            None => Name::AtomicWord(AtomicWord::Lower(LowerWord(
+                fresh.symbol(kind),
+            ))),
+        };
+        formulas.push(if kind == "axiom" && is_clause(&tree) {
+            let mut literals = vec![];
+            importer.clause(&tree, &mut literals)?;
+            let formula =
+                cnf::Formula::Disjunction(cnf::Disjunction(literals));
+            let annotated = annotate(name, "axiom", formula);
+            AnnotatedFormula::Cnf(Box::new(CnfAnnotated(annotated)))
+        } else {
+            let role = if kind == "goal" {
+                "conjecture"
+            } else {
+                "axiom"
+            };
+            let formula = importer.closed(&tree)?;
+            let annotated = annotate(name, role, formula);
+            AnnotatedFormula::Fof(Box::new(FofAnnotated(annotated)))
+        });
+    }
+    Ok(formulas)
+}
+
+fn distinct(element: &str) -> fof::Term<'_> {
+    let distinct = DefinedTerm::Distinct(DistinctObject(element));
+    fof::Term::Function(Box::new(fof::FunctionTerm::Defined(
+        fof::DefinedTerm::Defined(distinct),
+    )))
+}
+
+fn atomic(atom: fof::AtomicFormula) -> fof::LogicFormula {
+    fof::LogicFormula::Unitary(fof::UnitaryFormula::Atomic(Box::new(atom)))
+}
+
+/// the formulas of a Mace4 `interpretation` term
+fn interpretation<'a>(
+    tree: &Tree,
+    arena: &'a Arena,
+) -> Result<Vec<AnnotatedFormula<'a>>, ImportError> {
+    let malformed = || ImportError::Interpretation(tree.to_string());
+    let (size, items) = match tree {
+        Tree::Node("interpretation", arguments) => {
+            match arguments.as_slice() {
+                [Tree::Node(size, _), _, Tree::List(items)] => (size, items),
+                _ => return Err(malformed()),
+            }
+        }
+        _ => return Err(malformed()),
+    };
+    let size: usize = size.parse().map_err(|_| malformed())?;
+    if size == 0 {
+        return Err(malformed());
+    }
+    let elements: Vec<_> = (0..size)
+        .map(|element| distinct(arena.alloc(&element.to_string())))
+        .collect();
+
+    let mut formulas = vec![];
+    let mut push = |name: String, role, formula| -> Result<(), ImportError> {
+        let name = Name::AtomicWord(quote(arena, &name)?);
+        let annotated = annotate(name, role, fof::Formula(formula));
+        formulas
+            .push(AnnotatedFormula::Fof(Box::new(FofAnnotated(annotated))));
+        Ok(())
+    };
+    let variable = Variable(UpperWord("X"));
+    let mut cases = vec![];
+    for element in &elements {
+        let left = Box::new(fof::Term::Variable(variable.clone()));
+        cases.push(atomic(equation(left, Box::new(element.clone()))));
+    }
+    let domain = fof::QuantifiedFormula {
+        quantifier: fof::Quantifier::Forall,
+        bound: fof::VariableList(vec![variable]),
+        formula: Box::new(unit(assoc(AssocConnective::Or, cases))),
+    };
+    let domain =
+        fof::LogicFormula::Unitary(fof::UnitaryFormula::Quantified(domain));
+    push("domain".to_string(), "fi_domain", domain)?;
+
+    for item in items {
+        let (kind, symbol, arity, values) = match item {
+            Tree::Node(kind @ ("function" | "relation"), arguments) => {
+                match arguments.as_slice() {
+                    [Tree::Node(symbol, placeholders), Tree::List(values)] => {
+                        (*kind, *symbol, placeholders.len(), values)
+                    }
+                    _ => return Err(malformed()),
+                }
+            }
+            _ => return Err(malformed()),
+        };
+        let function = kind == "function";
+        let count = u32::try_from(arity)
+            .ok()
+            .and_then(|arity| size.checked_pow(arity));
+        if count != Some(values.len()) {
+            return Err(malformed());
+        }
+        let functor = Functor(quote(arena, symbol)?);
+        let mut conjuncts = vec![];
+        for (index, value) in values.iter().enumerate() {
+            let range = if function { size } else { 2 };
+            let value = match value {
+                Tree::Node(value, arguments) if arguments.is_empty() => {
+                    value.parse::<usize>().ok().filter(|value| *value < range)
+                }
+                _ => None,
+            };
+            let value = value.ok_or_else(malformed)?;
+            let mut arguments = vec![];
+            let mut rest = index;
+            for _ in 0..arity {
+                arguments.push(elements[rest % size].clone());
+                rest /= size;
+            }
+            arguments.reverse();
+            let plain = if arguments.is_empty() {
+                fof::PlainTerm::Constant(Constant(functor.clone()))
+            } else {
+                let arguments = Box::new(fof::Arguments(arguments));
+                fof::PlainTerm::Function(functor.clone(), arguments)
+            };
+            conjuncts.push(if function {
+                let left = Box::new(fof::Term::Function(Box::new(
+                    fof::FunctionTerm::Plain(plain),
+                )));
+                atomic(equation(left, Box::new(elements[value].clone())))
+            } else {
+                let atom = atomic(fof::AtomicFormula::Plain(
+                    fof::PlainAtomicFormula(plain),
+                ));
+                if value == 1 {
+                    atom
+                } else {
+                    fof::LogicFormula::Unary(fof::UnaryFormula::Unary(
+                        UnaryConnective,
+                        Box::new(unit(atom)),
+                    ))
+                }
+            });
+        }
+        let formula = assoc(AssocConnective::And, conjuncts);
+        if function {
+            let name = format!("function_{}", symbol);
+            push(name, "fi_functors", formula)?;
+        } else {
+            let name = format!("predicate_{}", symbol);
+            push(name, "fi_predicates", formula)?;
+        }
+    }
+    Ok(formulas)
+}
+
+/// import the models in Mace4 output as TPTP finite interpretations, storing new symbols in `arena`
+///
+/// Each `interpretation` becomes a list of formulas: an `fi_domain` formula for the domain, whose elements are the distinct objects `"0"`, `"1"` and so on, then an `fi_functors` formula for each function and an `fi_predicates` formula for each relation, tabulating it.
+/// Any other output, such as Mace4's statistics, is ignored.
+pub fn import_models<'a>(
+    input: &str,
+    arena: &'a Arena,
+) -> Result<Vec<Vec<AnnotatedFormula<'a>>>, ImportError> {
+    let mut models = vec![];
+    let mut position = 0;
+    while let Some(offset) = input[position..].find("interpretation(") {
+        let start = position + offset;
+        position = start + 1;
+        if input[..start].ends_with(is_ordinary_char) {
+            continue;
+        }
+        let mut parser = Parser::new(input, start);
+        let model = parser.term(TERM)?;
+        parser.expect(Token::End)?;
+        position = parser.position;
+        models.push(interpretation(&model, arena)?);
+    }
+    Ok(models)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    fn printed(formulas: &[AnnotatedFormula]) -> Vec<String> {
+        formulas
+            .iter()
+            .map(|annotated| annotated.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_export() {
+        let formulas = problem(
+            b"
+            fof(ax, axiom, ![X, Y]: (p(X) => q(X, Y))).
+            cnf(cl, axiom, ~p(Z) | Z != a | 'odd name'(Z)).
+            fof(neg, axiom, ~(~p(a)) & ~(a != b) & (p(a) <~> $true)).
+            fof(goal, conjecture, ?[A, VA]: (q(A, xylo) & A = VA)).
+            cnf('x y', negated_conjecture, ~a = b).
+        ",
+        );
+        assert_eq!(
+            export(&formulas).expect("export error"),
+            "\
+formulas(assumptions).
+all x all y (p(x) -> q(x,y)) # label(ax).
+-p(z) | z != a | c_odd_name(z) # label(cl).
+-(-p(a)) & -(a != b) & -(p(a) <-> $T) # label(neg).
+a != b # label(\"x y\").
+end_of_list.
+
+formulas(goals).
+exists vA exists vA1 (q(vA,c_xylo) & vA = vA1) # label(goal).
+end_of_list.
+"
+        );
+
+        let error = |input| export(&problem(input)).unwrap_err().to_string();
+        assert_eq!(
+            error(b"tff(a, axiom, p)."),
+            "a: typed formulas are not supported"
+        );
+        assert_eq!(error(b"fof(a, axiom, p(1))."), "a: unsupported term 1");
+        assert_eq!(
+            error(b"cnf(a, axiom, $distinct(a, b))."),
+            "a: unsupported term $distinct(a,b)"
+        );
+    }
+
+    #[test]
+    fn test_renaming() {
+        let formulas = problem(
+            b"
+            cnf(c, axiom, p(A, VA, VA1, X, X1, Xy) | ~q(U, V, W)).
+            fof(s, axiom, u(x) & v = w & p(zed, c_u, y(y))).
+        ",
+        );
+        let exported = export(&formulas).expect("export error");
+        assert_eq!(
+            exported,
+            "\
+formulas(assumptions).
+p(vA,vA1,vA11,x,x1,xy) | -q(u,v,w) # label(c).
+c_u1(c_x) & c_v = c_w & p(c_zed,c_u,c_y(c_y)) # label(s).
+end_of_list.
+"
+        );
+        let arena = Arena::new();
+        let reimported = import(&exported, &arena).expect("import error");
+        assert_eq!(
+            printed(&reimported),
+            [
+                "cnf(c,axiom,p(VA,VA1,VA11,X,X1,Xy)|~q(U,V,W)).",
+                "fof(s,axiom,c_u1(c_x)&c_v=c_w&p(c_zed,c_u,c_y(c_y))).",
+            ]
+        );
+
+        let input = "
+            formulas(sos).
+            -p(x$,x_) | x = X | u(v,X_).
+            u(x) & v(y,z) & p(zed).
+            end_of_list.
+        ";
+        let formulas = import(input, &arena).expect("import error");
+        assert_eq!(
+            printed(&formulas),
+            [
+                "cnf(axiom0,axiom,~p(X_,X_1)|X='X'|u(V,'X_')).",
+                "fof(axiom1,axiom,![X,Y,Z,Zed]:(u(X)&v(Y,Z)&p(Zed))).",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_connectives() {
+        let formulas = problem(
+            b"fof(a, axiom, (p <= q) & (p ~| q) & ~(p ~& q) & (p <~> q)).",
+        );
+        let exported = export(&formulas).expect("export error");
+        assert_eq!(
+            exported,
+            "\
+formulas(assumptions).
+(p <- q) & -(p | q) & -(-(p & q)) & -(p <-> q) # label(a).
+end_of_list.
+"
+        );
+        let arena = Arena::new();
+        let reimported = import(&exported, &arena).expect("import error");
+        assert_eq!(
+            printed(&reimported),
+            ["fof(a,axiom,(p<=q)&~(p|q)&~~(p&q)&~(p<=>q))."]
+        );
+    }
+
+    #[test]
+    fn test_import() {
+        let arena = Arena::new();
+        let input = "
+            % a comment
+            set(auto).
+            assign(max_seconds, 10).
+            formulas(hints).
+            p(a).
+            end_of_list.
+            formulas(sos).
+            all x all y (x * y = y * x) # label(comm).
+            -p(x) | q(x, \"b c\") | x = f(x,y).
+            p(x) -> (exists y r(x,y)).
+            -(a != b) # answer(x) # label(2).
+            -(all x p(x)) | $F.
+            end_of_list.
+            formulas(goals).
+            r(c,x) # label(goal).
+            end_of_list.
+        ";
+        let formulas = import(input, &arena).expect("import error");
+        assert_eq!(
+            printed(&formulas),
+            [
+                "fof(comm,axiom,![X,Y]:'*'(X,Y)='*'(Y,X)).",
+                "cnf(axiom0,axiom,~p(X)|q(X,'b c')|X=f(X,Y)).",
+                "fof(axiom1,axiom,![X]:(p(X)=>?[Y]:r(X,Y))).",
+                "cnf(2,axiom,a=b).",
+                "fof(axiom2,axiom,~![X]:p(X)|$false).",
+                "fof(goal,conjecture,![X]:r(c,X)).",
+            ]
+        );
+        let exported = export(&formulas[2..]).expect("export error");
+        let reimported = import(&exported, &arena).expect("import error");
+        assert_eq!(printed(&reimported), printed(&formulas[2..]));
+
+        let error = |input| import(input, &arena).unwrap_err().to_string();
+        assert_eq!(
+            error("formulas(sos).\np(a) q.\nend_of_list."),
+            "syntax error at byte 20"
+        );
+        assert_eq!(error("formulas(sos).\n[a]."), "unsupported [a]");
+    }
+
+    #[test]
+    fn test_import_models() {
+        let arena = Arena::new();
+        let output = "
+============================== MODEL =================================
+
+interpretation( 2, [number=1, seconds=0], [
+
+        function(e, [ 0 ]),
+
+        function(*(_,_), [
+			   0, 1,
+			   1, 0 ]),
+
+        relation(p(_), [ 0, 1 ]),
+
+        relation(q, [ 1 ])
+]).
+
+============================== end of model ==========================
+";
+        let models = import_models(output, &arena).expect("import error");
+        assert_eq!(models.len(), 1);
+        assert_eq!(
+            printed(&models[0]),
+            [
+                "fof(domain,fi_domain,![X]:(X=\"0\"|X=\"1\")).",
+                "fof(function_e,fi_functors,e=\"0\").",
+                "fof('function_*',fi_functors,'*'(\"0\",\"0\")=\"0\"&\
+                 '*'(\"0\",\"1\")=\"1\"&'*'(\"1\",\"0\")=\"1\"&\
+                 '*'(\"1\",\"1\")=\"0\").",
+                "fof(predicate_p,fi_predicates,~p(\"0\")&p(\"1\")).",
+                "fof(predicate_q,fi_predicates,q).",
+            ]
+        );
+
+        let ignored = "reinterpretation(2). interpretation(1, [], []).";
+        let models = import_models(ignored, &arena).expect("import error");
+        assert_eq!(models.len(), 1);
+
+        let error = |input: &str| {
+            import_models(input, &arena).unwrap_err().to_string()
+        };
+        let malformed = |interpretation: &str| {
+            format!("malformed interpretation {}", interpretation)
+        };
+        for interpretation in [
+            "interpretation(2,[],[relation(p(_),[0,2])])",
+            "interpretation(2,[],[relation(p,[a])])",
+            "interpretation(2,[],[function(c,[2])])",
+            "interpretation(2,[],[function(f(_),[0])])",
+            "interpretation(2,[],[constant(c,[0])])",
+            "interpretation(2,[],relation(p,[0]))",
+            "interpretation(0,[],[])",
+            "interpretation(x,[],[])",
+            "interpretation(1)",
+        ] {
+            let input = format!("{}.", interpretation);
+            assert_eq!(error(&input), malformed(interpretation));
+        }
+        assert_eq!(
+            error("interpretation(2, [], [relation(p, [0])]"),
+            "syntax error at byte 40"
+        );
+    }
+}
@@ -54,6 +54,9 @@
 //! ## `#![no_std]`
 //! The crate is `#![no_std]`, but recursive syntax trees must allocate so the `alloc` crate is required.
 //!
+//! ## Includes
+//! Transformations and exporters work on the formulas they are given and do not follow `include` directives: resolve them first.
+//!
 //! ## Serialisation
 //! Support for [`serde`](https://serde.rs/) can be switched on with a feature flag as usual.
 //! Structures can then be serialised, but not deseralised due to ownership issues.
@@ -66,16 +69,69 @@ extern crate alloc;
 
 #[macro_use]
 mod utils;
+/// alpha-equivalence and canonical variable names
+pub mod alpha;
+/// clausification of FOF and TFX problems
+pub mod clausify;
 /// the CNF dialect
 pub mod cnf;
+/// comment-preserving iteration
+pub mod comment;
 /// common syntax across all dialects
 pub mod common;
+/// derivation graphs of TSTP proofs
+pub mod derivation;
+/// DFG export for SPASS
+pub mod dfg;
+/// DIMACS CNF import and export for ground propositional problems
+pub mod dimacs;
+/// structural hashing and duplicate detection
+///
+/// Rather than hashing the `alpha::Canonical` form, bound variables are hashed by their distance to their binder and free variables by first occurrence.
+/// This needs no renamed copy of each formula, and unlike canonical names it does not depend on the order of subformulas, which commutative hashing sorts.
+pub mod fingerprint;
 /// the FOF dialect
 pub mod fof;
+/// fresh symbols for transformations
+pub mod fresh;
+/// Graphviz DOT and GraphML export of derivations
+pub mod graph;
+/// discrimination trees for term indexing
+pub mod index;
+/// LADR syntax for Prover9 and Mace4
+pub mod ladr;
+/// negation normal form
+pub mod nnf;
+/// Knuth-Bendix and lexicographic path orderings
+pub mod ordering;
+/// prenex normal form
+pub mod prenex;
+/// pretty-printing over several lines
+pub mod pretty;
+/// LaTeX and Unicode rendering of formulas
+pub mod render;
+/// free variables and variable scoping
+pub mod scope;
+/// SInE premise selection
+pub mod sine;
+/// Skolemisation of FOF formulas
+pub mod skolem;
+/// SMT-LIB v2 import and export
+pub mod smtlib;
+/// structured views of formula sources
+pub mod source;
+/// substitutions, unification and matching
+pub mod subst;
+/// clause subsumption and tautology checking
+pub mod subsumption;
+/// the SZS ontologies and solver output
+pub mod szs;
 /// the TFX dialect
 pub mod tfx;
 /// top-level inputs, formula annotations, etc.
 pub mod top;
+/// type checking for TFX problems
+pub mod typecheck;
 
 #[cfg(test)]
 mod tests;
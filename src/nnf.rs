@@ -0,0 +1,601 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::common::*;
+use crate::fof;
+use crate::tfx;
+
+/// formulas which can be put into negation normal form
+///
+/// The result contains no `=>`, `<=`, `<=>`, `<~>`, `~|` or `~&`.
+/// Negations apply only to atoms, and negated equalities become `!=`.
+/// Nested `&` and `|` are flattened and redundant parentheses are removed.
+pub trait NegationNormalForm: Sized {
+    /// this formula in negation normal form
+    fn nnf(&self) -> Self;
+
+    /// the negation of this formula in negation normal form
+    fn negated_nnf(&self) -> Self;
+}
+
+/// `$true` or `$false` as a word, if `constant` is one of them
+pub(crate) fn truth_value<'a>(
+    constant: &DefinedConstant<'a>,
+) -> Option<&'a str> {
+    let word = ((((constant.0).0).0).0).0;
+    match word {
+        "true" | "false" => Some(word),
+        _ => None,
+    }
+}
+
+fn negated_truth_value<'a>(word: &str) -> DefinedConstant<'a> {
+    let word = if word == "true" { "false" } else { "true" };
+    DefinedConstant(DefinedFunctor(AtomicDefinedWord(DollarWord(LowerWord(
+        word,
+    )))))
+}
+
+fn flip(quantifier: fof::Quantifier, positive: bool) -> fof::Quantifier {
+    match (quantifier, positive) {
+        (quantifier, true) => quantifier,
+        (fof::Quantifier::Forall, false) => fof::Quantifier::Exists,
+        (fof::Quantifier::Exists, false) => fof::Quantifier::Forall,
+    }
+}
+
+/// polarities of the left and right operands of a non-associative connective, and the associative connective joining them
+///
+/// Equivalences produce two such conjuncts or disjuncts.
+fn expand(
+    op: NonassocConnective,
+    positive: bool,
+) -> (AssocConnective, Vec<(bool, bool)>, AssocConnective) {
+    use AssocConnective::*;
+    use NonassocConnective::*;
+    let iff = [(false, true), (true, false)];
+    let xor = [(true, true), (false, false)];
+    match (op, positive) {
+        (LRImplies, true) => (Or, vec![(false, true)], Or),
+        (LRImplies, false) => (And, vec![(true, false)], And),
+        (RLImplies, true) => (Or, vec![(true, false)], Or),
+        (RLImplies, false) => (And, vec![(false, true)], And),
+        (Equivalent, true) | (NotEquivalent, false) => (Or, iff.to_vec(), And),
+        (Equivalent, false) | (NotEquivalent, true) => (Or, xor.to_vec(), And),
+        (NotOr, true) => (And, vec![(false, false)], And),
+        (NotOr, false) => (Or, vec![(true, true)], Or),
+        (NotAnd, true) => (Or, vec![(false, false)], Or),
+        (NotAnd, false) => (And, vec![(true, true)], And),
+    }
+}
+
+pub(crate) mod fof_nnf {
+    use super::*;
+    use fof::*;
+
+    pub(crate) fn unit(formula: LogicFormula) -> UnitFormula {
+        match formula {
+            LogicFormula::Unitary(unitary) => UnitFormula::Unitary(unitary),
+            LogicFormula::Unary(unary) => UnitFormula::Unary(unary),
+            binary => UnitFormula::Unitary(UnitaryFormula::Parenthesised(
+                Box::new(binary),
+            )),
+        }
+    }
+
+    fn logic(unit: UnitFormula) -> LogicFormula {
+        match unit {
+            UnitFormula::Unitary(unitary) => LogicFormula::Unitary(unitary),
+            UnitFormula::Unary(unary) => LogicFormula::Unary(unary),
+        }
+    }
+
+    pub(crate) fn assoc<'a>(
+        op: AssocConnective,
+        formulas: Vec<LogicFormula<'a>>,
+    ) -> LogicFormula<'a> {
+        let mut units = vec![];
+        for formula in formulas {
+            match (op, formula) {
+                (
+                    AssocConnective::And,
+                    LogicFormula::Binary(BinaryFormula::Assoc(
+                        BinaryAssoc::And(and),
+                    )),
+                ) => units.extend(and.0),
+                (
+                    AssocConnective::Or,
+                    LogicFormula::Binary(BinaryFormula::Assoc(
+                        BinaryAssoc::Or(or),
+                    )),
+                ) => units.extend(or.0),
+                (_, formula) => units.push(unit(formula)),
+            }
+        }
+        if units.len() == 1 {
+            return logic(units.pop().unwrap());
+        }
+        let assoc = match op {
+            AssocConnective::And => BinaryAssoc::And(AndFormula(units)),
+            AssocConnective::Or => BinaryAssoc::Or(OrFormula(units)),
+        };
+        LogicFormula::Binary(BinaryFormula::Assoc(assoc))
+    }
+
+    fn literal<'a>(
+        atom: &AtomicFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        if positive {
+            let atom = Box::new(atom.clone());
+            return LogicFormula::Unitary(UnitaryFormula::Atomic(atom));
+        }
+        match atom {
+            AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix)) => {
+                LogicFormula::Unary(UnaryFormula::InfixUnary(InfixUnary {
+                    left: infix.left.clone(),
+                    op: InfixInequality,
+                    right: infix.right.clone(),
+                }))
+            }
+            AtomicFormula::Defined(DefinedAtomicFormula::Plain(
+                DefinedPlainFormula(DefinedPlainTerm::Constant(constant)),
+            )) if truth_value(constant).is_some() => {
+                let word = truth_value(constant).unwrap();
+                let constant = negated_truth_value(word);
+                let atom = AtomicFormula::Defined(
+                    DefinedAtomicFormula::Plain(DefinedPlainFormula(
+                        DefinedPlainTerm::Constant(constant),
+                    )),
+                );
+                literal(&atom, true)
+            }
+            _ => {
+                let atom = Box::new(atom.clone());
+                let atom = UnitFormula::Unitary(UnitaryFormula::Atomic(atom));
+                LogicFormula::Unary(UnaryFormula::Unary(
+                    UnaryConnective,
+                    Box::new(atom),
+                ))
+            }
+        }
+    }
+
+    fn unitary<'a>(
+        formula: &UnitaryFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnitaryFormula::Quantified(quantified) => {
+                let body = unit_formula(&quantified.formula, positive);
+                LogicFormula::Unitary(UnitaryFormula::Quantified(
+                    QuantifiedFormula {
+                        quantifier: flip(quantified.quantifier, positive),
+                        bound: quantified.bound.clone(),
+                        formula: Box::new(unit(body)),
+                    },
+                ))
+            }
+            UnitaryFormula::Atomic(atom) => literal(atom, positive),
+            UnitaryFormula::Parenthesised(formula) => {
+                logic_formula(formula, positive)
+            }
+        }
+    }
+
+    fn unary<'a>(
+        formula: &UnaryFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnaryFormula::Unary(_, formula) => {
+                unit_formula(formula, !positive)
+            }
+            UnaryFormula::InfixUnary(infix) if positive => {
+                LogicFormula::Unary(UnaryFormula::InfixUnary(infix.clone()))
+            }
+            UnaryFormula::InfixUnary(infix) => {
+                let infix = DefinedInfixFormula {
+                    left: infix.left.clone(),
+                    op: DefinedInfixPred(InfixEquality),
+                    right: infix.right.clone(),
+                };
+                let atom =
+                    AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix));
+                literal(&atom, true)
+            }
+        }
+    }
+
+    pub(super) fn unit_formula<'a>(
+        formula: &UnitFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnitFormula::Unitary(formula) => unitary(formula, positive),
+            UnitFormula::Unary(formula) => unary(formula, positive),
+        }
+    }
+
+    pub(super) fn logic_formula<'a>(
+        formula: &LogicFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc_formula)) => {
+                let (op, units) = match (assoc_formula, positive) {
+                    (BinaryAssoc::And(and), true) => {
+                        (AssocConnective::And, &and.0)
+                    }
+                    (BinaryAssoc::And(and), false) => {
+                        (AssocConnective::Or, &and.0)
+                    }
+                    (BinaryAssoc::Or(or), true) => {
+                        (AssocConnective::Or, &or.0)
+                    }
+                    (BinaryAssoc::Or(or), false) => {
+                        (AssocConnective::And, &or.0)
+                    }
+                };
+                let formulas = units
+                    .iter()
+                    .map(|unit| unit_formula(unit, positive))
+                    .collect();
+                assoc(op, formulas)
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(nonassoc)) => {
+                let (inner, polarities, outer) = expand(nonassoc.op, positive);
+                let formulas = polarities
+                    .into_iter()
+                    .map(|(left, right)| {
+                        let left = unit_formula(&nonassoc.left, left);
+                        let right = unit_formula(&nonassoc.right, right);
+                        assoc(inner, vec![left, right])
+                    })
+                    .collect();
+                assoc(outer, formulas)
+            }
+            LogicFormula::Unary(formula) => unary(formula, positive),
+            LogicFormula::Unitary(formula) => unitary(formula, positive),
+        }
+    }
+}
+
+mod tfx_nnf {
+    use super::*;
+    use tfx::*;
+
+    fn unit(formula: LogicFormula) -> UnitFormula {
+        match formula {
+            LogicFormula::Unitary(unitary) => UnitFormula::Unitary(unitary),
+            LogicFormula::Unary(unary) => UnitFormula::Unary(unary),
+            LogicFormula::DefinedInfix(infix) => {
+                UnitFormula::DefinedInfix(infix)
+            }
+            binary => {
+                UnitFormula::Unitary(UnitaryFormula::Logic(Box::new(binary)))
+            }
+        }
+    }
+
+    fn logic(unit: UnitFormula) -> LogicFormula {
+        match unit {
+            UnitFormula::Unitary(unitary) => LogicFormula::Unitary(unitary),
+            UnitFormula::Unary(unary) => LogicFormula::Unary(unary),
+            UnitFormula::DefinedInfix(infix) => {
+                LogicFormula::DefinedInfix(infix)
+            }
+        }
+    }
+
+    fn assoc<'a>(
+        op: AssocConnective,
+        formulas: Vec<LogicFormula<'a>>,
+    ) -> LogicFormula<'a> {
+        let mut units = vec![];
+        for formula in formulas {
+            match (op, formula) {
+                (
+                    AssocConnective::And,
+                    LogicFormula::Binary(BinaryFormula::Assoc(
+                        BinaryAssoc::And(and),
+                    )),
+                ) => units.extend(and.0),
+                (
+                    AssocConnective::Or,
+                    LogicFormula::Binary(BinaryFormula::Assoc(
+                        BinaryAssoc::Or(or),
+                    )),
+                ) => units.extend(or.0),
+                (_, formula) => units.push(unit(formula)),
+            }
+        }
+        if units.len() == 1 {
+            return logic(units.pop().unwrap());
+        }
+        let assoc = match op {
+            AssocConnective::And => BinaryAssoc::And(AndFormula(units)),
+            AssocConnective::Or => BinaryAssoc::Or(OrFormula(units)),
+        };
+        LogicFormula::Binary(BinaryFormula::Assoc(assoc))
+    }
+
+    fn negated(formula: UnitaryFormula) -> LogicFormula {
+        LogicFormula::Unary(UnaryFormula::Prefix(PrefixUnary {
+            op: UnaryConnective,
+            formula: Box::new(PreunitFormula::Unitary(formula)),
+        }))
+    }
+
+    fn atomic<'a>(
+        atom: &AtomicFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        let formula = UnitaryFormula::Atomic(atom.clone());
+        if positive {
+            return LogicFormula::Unitary(formula);
+        }
+        match atom {
+            AtomicFormula::Defined(DefinedAtomic(DefinedPlain::Constant(
+                constant,
+            ))) if truth_value(constant).is_some() => {
+                let word = truth_value(constant).unwrap();
+                let constant = negated_truth_value(word);
+                LogicFormula::Unitary(UnitaryFormula::Atomic(
+                    AtomicFormula::Defined(DefinedAtomic(
+                        DefinedPlain::Constant(constant),
+                    )),
+                ))
+            }
+            _ => negated(formula),
+        }
+    }
+
+    fn equality<'a>(
+        infix: &DefinedInfix<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        if positive {
+            LogicFormula::DefinedInfix(infix.clone())
+        } else {
+            LogicFormula::Unary(UnaryFormula::Infix(InfixUnary {
+                left: infix.left.clone(),
+                op: InfixInequality,
+                right: infix.right.clone(),
+            }))
+        }
+    }
+
+    fn unitary<'a>(
+        formula: &UnitaryFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnitaryFormula::Quantified(quantified) => {
+                let body = unit_formula(&quantified.formula, positive);
+                LogicFormula::Unitary(UnitaryFormula::Quantified(
+                    QuantifiedFormula {
+                        quantifier: flip(quantified.quantifier, positive),
+                        bound: quantified.bound.clone(),
+                        formula: Box::new(unit(body)),
+                    },
+                ))
+            }
+            UnitaryFormula::Atomic(atom) => atomic(atom, positive),
+            UnitaryFormula::Variable(_) if positive => {
+                LogicFormula::Unitary(formula.clone())
+            }
+            UnitaryFormula::Variable(_) => negated(formula.clone()),
+            UnitaryFormula::Logic(formula) => logic_formula(formula, positive),
+        }
+    }
+
+    fn preunit<'a>(
+        formula: &PreunitFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            PreunitFormula::Unitary(formula) => unitary(formula, positive),
+            PreunitFormula::Prefix(prefix) => {
+                preunit(&prefix.formula, !positive)
+            }
+        }
+    }
+
+    fn unary<'a>(
+        formula: &UnaryFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnaryFormula::Prefix(prefix) => {
+                preunit(&prefix.formula, !positive)
+            }
+            UnaryFormula::Infix(infix) => {
+                let infix = DefinedInfix {
+                    left: infix.left.clone(),
+                    op: DefinedInfixPred(InfixEquality),
+                    right: infix.right.clone(),
+                };
+                equality(&infix, !positive)
+            }
+        }
+    }
+
+    fn unit_formula<'a>(
+        formula: &UnitFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            UnitFormula::Unitary(formula) => unitary(formula, positive),
+            UnitFormula::Unary(formula) => unary(formula, positive),
+            UnitFormula::DefinedInfix(infix) => equality(infix, positive),
+        }
+    }
+
+    pub(super) fn logic_formula<'a>(
+        formula: &LogicFormula<'a>,
+        positive: bool,
+    ) -> LogicFormula<'a> {
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc_formula)) => {
+                let (op, units) = match (assoc_formula, positive) {
+                    (BinaryAssoc::And(and), true) => {
+                        (AssocConnective::And, &and.0)
+                    }
+                    (BinaryAssoc::And(and), false) => {
+                        (AssocConnective::Or, &and.0)
+                    }
+                    (BinaryAssoc::Or(or), true) => {
+                        (AssocConnective::Or, &or.0)
+                    }
+                    (BinaryAssoc::Or(or), false) => {
+                        (AssocConnective::And, &or.0)
+                    }
+                };
+                let formulas = units
+                    .iter()
+                    .map(|unit| unit_formula(unit, positive))
+                    .collect();
+                assoc(op, formulas)
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(nonassoc)) => {
+                let (inner, polarities, outer) = expand(nonassoc.op, positive);
+                let formulas = polarities
+                    .into_iter()
+                    .map(|(left, right)| {
+                        let left = unit_formula(&nonassoc.left, left);
+                        let right = unit_formula(&nonassoc.right, right);
+                        assoc(inner, vec![left, right])
+                    })
+                    .collect();
+                assoc(outer, formulas)
+            }
+            LogicFormula::Unary(formula) => unary(formula, positive),
+            LogicFormula::Unitary(formula) => unitary(formula, positive),
+            LogicFormula::DefinedInfix(infix) => equality(infix, positive),
+        }
+    }
+}
+
+pub(crate) use fof_nnf::{assoc as fof_assoc, unit as fof_unit};
+
+impl<'a> NegationNormalForm for fof::LogicFormula<'a> {
+    fn nnf(&self) -> Self {
+        fof_nnf::logic_formula(self, true)
+    }
+
+    fn negated_nnf(&self) -> Self {
+        fof_nnf::logic_formula(self, false)
+    }
+}
+
+impl<'a> NegationNormalForm for fof::Formula<'a> {
+    fn nnf(&self) -> Self {
+        fof::Formula(self.0.nnf())
+    }
+
+    fn negated_nnf(&self) -> Self {
+        fof::Formula(self.0.negated_nnf())
+    }
+}
+
+/// Only the first-order structure is transformed: formulas nested inside terms are left alone.
+impl<'a> NegationNormalForm for tfx::LogicFormula<'a> {
+    fn nnf(&self) -> Self {
+        tfx_nnf::logic_formula(self, true)
+    }
+
+    fn negated_nnf(&self) -> Self {
+        tfx_nnf::logic_formula(self, false)
+    }
+}
+
+/// Type declarations are returned unchanged by both methods.
+impl<'a> NegationNormalForm for tfx::Formula<'a> {
+    fn nnf(&self) -> Self {
+        match self {
+            tfx::Formula::Logic(logic) => {
+                tfx::Formula::Logic(Box::new(logic.nnf()))
+            }
+            typing => typing.clone(),
+        }
+    }
+
+    fn negated_nnf(&self) -> Self {
+        match self {
+            tfx::Formula::Logic(logic) => {
+                tfx::Formula::Logic(Box::new(logic.negated_nnf()))
+            }
+            typing => typing.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    fn fof(input: &str, expected: &str) {
+        let mut bytes: Vec<u8> = input.bytes().collect();
+        bytes.push(0);
+        let nnf = check_parse(fof::Formula::parse, &bytes).nnf();
+        let printed = nnf.to_string();
+        assert_eq!(printed, expected);
+        let mut bytes: Vec<u8> = printed.bytes().collect();
+        bytes.push(0);
+        assert_eq!(check_parse(fof::Formula::parse, &bytes), nnf);
+    }
+
+    fn tfx(input: &str, expected: &str) {
+        let mut bytes: Vec<u8> = input.bytes().collect();
+        bytes.push(0);
+        let nnf = check_parse(tfx::Formula::parse, &bytes).nnf();
+        let printed = nnf.to_string();
+        assert_eq!(printed, expected);
+        let mut bytes: Vec<u8> = printed.bytes().collect();
+        bytes.push(0);
+        assert_eq!(check_parse(tfx::Formula::parse, &bytes), nnf);
+    }
+
+    #[test]
+    fn test_fof_connectives() {
+        fof("p => q", "~p|q");
+        fof("p <= q", "p|~q");
+        fof("p <=> q", "(~p|q)&(p|~q)");
+        fof("p <~> q", "(p|q)&(~p|~q)");
+        fof("p ~| q", "~p&~q");
+        fof("p ~& q", "~p|~q");
+        fof("~(p => q)", "p&~q");
+        fof("~(p <=> q)", "(p|q)&(~p|~q)");
+        fof("~~p", "p");
+    }
+
+    #[test]
+    fn test_fof_nnf() {
+        fof("~(p & (q | r))", "~p|(~q&~r)");
+        fof("p & (q & r) & s", "p&q&r&s");
+        fof("~![X]: ?[Y]: p(X, Y)", "?[X]:![Y]:~p(X,Y)");
+        fof("~![X]: (p(X) => q(X))", "?[X]:(p(X)&~q(X))");
+        fof("~(a = b)", "a!=b");
+        fof("~(a != b) | ~$true", "a=b|$false");
+        fof("![X]: ~~(X = a)", "![X]:X=a");
+    }
+
+    #[test]
+    fn test_tfx_nnf() {
+        tfx("~(p => q)", "p&~q");
+        tfx("~![X: $i]: (p(X) | X = a)", "?[X:$i]:(~p(X)&X!=a)");
+        tfx("~(a != b)", "a=b");
+        tfx(
+            "![B: $o]: ~(B <=> $false)",
+            "![B:$o]:((B|$false)&(~B|$true))",
+        );
+        tfx("~~~p(f(~q))", "~p(f(~q))");
+        tfx("p: $o", "p:$o");
+    }
+}
@@ -0,0 +1,379 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::fof;
+use crate::index::{Indexable, Key};
+use crate::subst::function_view;
+use crate::top::AnnotatedFormula;
+use crate::visitor::Visitor;
+
+/// the result of comparing two terms in a term ordering
+#[derive(
+    Clone, Copy, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Comparison {
+    #[display(fmt = ">")]
+    Greater,
+    #[display(fmt = "<")]
+    Less,
+    #[display(fmt = "=")]
+    Equal,
+    #[display(fmt = "?")]
+    Incomparable,
+}
+
+/// a simplification ordering on terms
+pub trait TermOrdering<'a> {
+    /// whether `s` is strictly greater than `t`
+    fn greater(&self, s: &fof::Term<'a>, t: &fof::Term<'a>) -> bool;
+
+    /// compare two terms
+    fn compare(&self, s: &fof::Term<'a>, t: &fof::Term<'a>) -> Comparison {
+        if s == t {
+            Comparison::Equal
+        } else if self.greater(s, t) {
+            Comparison::Greater
+        } else if self.greater(t, s) {
+            Comparison::Less
+        } else {
+            Comparison::Incomparable
+        }
+    }
+}
+
+struct Symbols<'a> {
+    first: BTreeMap<Key<'a>, usize>,
+}
+
+impl<'a> Visitor<'a> for Symbols<'a> {
+    fn visit_fof_term(&mut self, fof_term: &fof::Term<'a>) {
+        for key in fof_term.keys() {
+            if key != Key::Variable {
+                let next = self.first.len();
+                self.first.entry(key).or_insert(next);
+            }
+        }
+    }
+}
+
+/// a total precedence on function symbols
+///
+/// Symbols are identified by their `Key`, so symbols with the same name and different arities are different.
+/// Ranked symbols are greater than unranked ones, which are ordered by arity and then arbitrarily.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Precedence<'a> {
+    ranks: BTreeMap<Key<'a>, usize>,
+}
+
+impl<'a> Precedence<'a> {
+    /// a precedence with `symbols` listed from least to greatest
+    pub fn new(symbols: impl IntoIterator<Item = Key<'a>>) -> Self {
+        let ranks = symbols
+            .into_iter()
+            .enumerate()
+            .map(|(rank, symbol)| (symbol, rank))
+            .collect();
+        Self { ranks }
+    }
+
+    /// a default precedence for the function symbols of a problem
+    ///
+    /// Symbols of higher arity are greater, with ties broken by first occurrence: earlier symbols are smaller.
+    /// Only terms in FOF and CNF formulas are considered.
+    pub fn from_formulas<'f>(
+        formulas: impl IntoIterator<Item = &'f AnnotatedFormula<'a>>,
+    ) -> Self
+    where
+        'a: 'f,
+    {
+        let mut symbols = Symbols {
+            first: BTreeMap::new(),
+        };
+        for formula in formulas {
+            symbols.visit_annotated_formula(formula);
+        }
+        let mut ordered: Vec<_> = symbols.first.into_iter().collect();
+        ordered.sort_by_key(|(symbol, first)| (symbol.arity(), *first));
+        Self::new(ordered.into_iter().map(|(symbol, _)| symbol))
+    }
+
+    /// the rank of `symbol`, if it has one: greater symbols have greater ranks
+    pub fn rank(&self, symbol: &Key<'a>) -> Option<usize> {
+        self.ranks.get(symbol).copied()
+    }
+
+    /// compare two symbols
+    pub fn compare(&self, left: &Key<'a>, right: &Key<'a>) -> Ordering {
+        let key = |symbol: &Key<'a>| {
+            let rank = self.rank(symbol);
+            (rank.is_some(), rank, symbol.arity())
+        };
+        key(left).cmp(&key(right)).then_with(|| left.cmp(right))
+    }
+}
+
+/// symbol weights for the Knuth-Bendix ordering
+///
+/// For the ordering to be well-founded, constants must weigh at least as much as variables, and at most one unary symbol, which must be greatest in the precedence, may weigh 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Weights<'a> {
+    symbols: BTreeMap<Key<'a>, usize>,
+    /// the weight of symbols not given one explicitly, 1 by default
+    pub default: usize,
+    /// the weight of variables, 1 by default
+    pub variable: usize,
+}
+
+impl<'a> Default for Weights<'a> {
+    fn default() -> Self {
+        Self {
+            symbols: BTreeMap::new(),
+            default: 1,
+            variable: 1,
+        }
+    }
+}
+
+impl<'a> Weights<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// set the weight of `symbol`
+    pub fn set(&mut self, symbol: Key<'a>, weight: usize) {
+        self.symbols.insert(symbol, weight);
+    }
+
+    /// the weight of `symbol`
+    pub fn get(&self, symbol: &Key<'a>) -> usize {
+        self.symbols.get(symbol).copied().unwrap_or(self.default)
+    }
+}
+
+/// the symbol and arguments of a non-variable term
+fn application<'t, 'a>(
+    function: &'t fof::FunctionTerm<'a>,
+) -> (Key<'a>, Vec<&'t fof::Term<'a>>) {
+    let (head, arguments) = function_view(function);
+    let arguments: Vec<_> = arguments.iter().collect();
+    (Key::new(head, arguments.len()), arguments)
+}
+
+fn occurs(variable: &Variable, term: &fof::Term) -> bool {
+    match term {
+        fof::Term::Variable(other) => variable == other,
+        fof::Term::Function(function) => {
+            let (_, arguments) = application(function);
+            arguments.iter().any(|argument| occurs(variable, argument))
+        }
+    }
+}
+
+/// whether the first differing argument of `left` is greater than that of `right`
+fn lexicographic<'a, O: TermOrdering<'a> + ?Sized>(
+    ordering: &O,
+    left: &[&fof::Term<'a>],
+    right: &[&fof::Term<'a>],
+) -> bool {
+    match left.iter().zip(right).find(|(s, t)| s != t) {
+        Some((s, t)) => ordering.greater(s, t),
+        None => false,
+    }
+}
+
+/// the Knuth-Bendix ordering
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Kbo<'a> {
+    pub precedence: Precedence<'a>,
+    pub weights: Weights<'a>,
+}
+
+impl<'a> Kbo<'a> {
+    pub fn new(precedence: Precedence<'a>, weights: Weights<'a>) -> Self {
+        Self {
+            precedence,
+            weights,
+        }
+    }
+
+    /// the weight of `term`, counting variable occurrences into `variables` with sign `sign`
+    fn weigh(
+        &self,
+        term: &fof::Term<'a>,
+        sign: isize,
+        variables: &mut BTreeMap<Variable<'a>, isize>,
+    ) -> usize {
+        match term {
+            fof::Term::Variable(variable) => {
+                *variables.entry(variable.clone()).or_insert(0) += sign;
+                self.weights.variable
+            }
+            fof::Term::Function(function) => {
+                let (symbol, arguments) = application(function);
+                arguments
+                    .iter()
+                    .fold(self.weights.get(&symbol), |sum, term| {
+                        sum + self.weigh(term, sign, variables)
+                    })
+            }
+        }
+    }
+}
+
+impl<'a> TermOrdering<'a> for Kbo<'a> {
+    fn greater(&self, s: &fof::Term<'a>, t: &fof::Term<'a>) -> bool {
+        let (s_function, t_function) = match (s, t) {
+            (fof::Term::Variable(_), _) => return false,
+            (_, fof::Term::Variable(variable)) => return occurs(variable, s),
+            (fof::Term::Function(s), fof::Term::Function(t)) => (s, t),
+        };
+        let mut variables = BTreeMap::new();
+        let s_weight = self.weigh(s, 1, &mut variables);
+        let t_weight = self.weigh(t, -1, &mut variables);
+        if variables.values().any(|count| *count < 0) {
+            return false;
+        }
+        if s_weight != t_weight {
+            return s_weight > t_weight;
+        }
+        let (f, s_arguments) = application(s_function);
+        let (g, t_arguments) = application(t_function);
+        match self.precedence.compare(&f, &g) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal => lexicographic(self, &s_arguments, &t_arguments),
+        }
+    }
+}
+
+/// the lexicographic path ordering
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lpo<'a> {
+    pub precedence: Precedence<'a>,
+}
+
+impl<'a> Lpo<'a> {
+    pub fn new(precedence: Precedence<'a>) -> Self {
+        Self { precedence }
+    }
+}
+
+impl<'a> TermOrdering<'a> for Lpo<'a> {
+    fn greater(&self, s: &fof::Term<'a>, t: &fof::Term<'a>) -> bool {
+        let s_function = match s {
+            fof::Term::Variable(_) => return false,
+            fof::Term::Function(function) => function,
+        };
+        let (f, s_arguments) = application(s_function);
+        if s_arguments
+            .iter()
+            .any(|argument| *argument == t || self.greater(argument, t))
+        {
+            return true;
+        }
+        let t_function = match t {
+            fof::Term::Variable(_) => return false,
+            fof::Term::Function(function) => function,
+        };
+        let (g, t_arguments) = application(t_function);
+        let dominates =
+            || t_arguments.iter().all(|argument| self.greater(s, argument));
+        match self.precedence.compare(&f, &g) {
+            Ordering::Greater => dominates(),
+            Ordering::Less => false,
+            Ordering::Equal => {
+                lexicographic(self, &s_arguments, &t_arguments) && dominates()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+
+    fn term(input: &[u8]) -> fof::Term<'_> {
+        check_parse(fof::Term::parse, input)
+    }
+
+    fn symbol(name: &str, arity: usize) -> Key<'_> {
+        Key::Functor(Functor(AtomicWord::Lower(LowerWord(name))), arity)
+    }
+
+    #[test]
+    fn test_kbo() {
+        let precedence =
+            Precedence::new([symbol("a", 0), symbol("g", 1), symbol("f", 2)]);
+        let kbo = Kbo::new(precedence, Weights::new());
+        let compare = |s, t| kbo.compare(&term(s), &term(t));
+        assert_eq!(compare(b"f(X, a)\0", b"X\0"), Comparison::Greater);
+        assert_eq!(compare(b"g(X)\0", b"f(X, a)\0"), Comparison::Less);
+        assert_eq!(
+            compare(b"f(X, Y)\0", b"f(Y, X)\0"),
+            Comparison::Incomparable
+        );
+        assert_eq!(compare(b"f(X, Y)\0", b"g(g(X))\0"), Comparison::Greater);
+        assert_eq!(compare(b"f(X, a)\0", b"g(Y)\0"), Comparison::Incomparable);
+        assert_eq!(compare(b"f(a, X)\0", b"g(g(X))\0"), Comparison::Greater);
+        assert_eq!(
+            compare(b"f(g(X), a)\0", b"f(X, g(a))\0"),
+            Comparison::Greater
+        );
+        assert_eq!(compare(b"g(X)\0", b"g(X)\0"), Comparison::Equal);
+        assert_eq!(compare(b"X\0", b"Y\0"), Comparison::Incomparable);
+
+        let mut weights = Weights::new();
+        weights.set(symbol("a", 0), 5);
+        let kbo = Kbo::new(Precedence::default(), weights);
+        let compare = |s, t| kbo.compare(&term(s), &term(t));
+        assert_eq!(compare(b"a\0", b"g(g(b))\0"), Comparison::Greater);
+    }
+
+    #[test]
+    fn test_lpo() {
+        let precedence = Precedence::new([
+            symbol("a", 0),
+            symbol("s", 1),
+            symbol("plus", 2),
+            symbol("times", 2),
+        ]);
+        let lpo = Lpo::new(precedence);
+        let compare = |s, t| lpo.compare(&term(s), &term(t));
+        assert_eq!(
+            compare(b"times(s(X), Y)\0", b"plus(times(X, Y), Y)\0"),
+            Comparison::Greater
+        );
+        assert_eq!(
+            compare(b"plus(s(X), Y)\0", b"s(plus(X, Y))\0"),
+            Comparison::Greater
+        );
+        assert_eq!(compare(b"plus(X, a)\0", b"X\0"), Comparison::Greater);
+        assert_eq!(compare(b"s(X)\0", b"s(Y)\0"), Comparison::Incomparable);
+        assert_eq!(compare(b"s(s(X))\0", b"times(X, X)\0"), Comparison::Less);
+    }
+
+    #[test]
+    fn test_default_precedence() {
+        let formula = check_parse(
+            AnnotatedFormula::parse,
+            b"cnf(c, axiom, p(f(X, b), g(a)) | h(c) = c).\0",
+        );
+        let precedence = Precedence::from_formulas([&formula]);
+        let ranked = |name, arity| precedence.rank(&symbol(name, arity));
+        assert_eq!(ranked("b", 0), Some(0));
+        assert_eq!(ranked("a", 0), Some(1));
+        assert_eq!(ranked("c", 0), Some(2));
+        assert_eq!(ranked("g", 1), Some(3));
+        assert_eq!(ranked("h", 1), Some(4));
+        assert_eq!(ranked("f", 2), Some(5));
+        assert_eq!(ranked("p", 2), None);
+    }
+}
@@ -0,0 +1,230 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::common::*;
+use crate::fof;
+use crate::fresh::Fresh;
+use crate::nnf::{fof_assoc, fof_unit, NegationNormalForm};
+use crate::scope::FreeVariables;
+use crate::subst::{apply_atom, apply_term};
+
+/// a FOF formula in prenex normal form
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct Prenex<'a> {
+    /// the quantifier prefix, outermost first
+    pub prefix: Vec<(fof::Quantifier, Variable<'a>)>,
+    /// the quantifier-free matrix, in negation normal form
+    pub matrix: fof::LogicFormula<'a>,
+}
+
+struct Prenexer<'f, 'a> {
+    fresh: &'f mut Fresh<'a>,
+    used: BTreeSet<Variable<'a>>,
+    renaming: Vec<(Variable<'a>, Variable<'a>)>,
+    prefix: Vec<(fof::Quantifier, Variable<'a>)>,
+}
+
+impl<'f, 'a> Prenexer<'f, 'a> {
+    fn lookup(&self, variable: &Variable<'a>) -> Option<fof::Term<'a>> {
+        self.renaming
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == variable)
+            .map(|(_, renamed)| fof::Term::Variable(renamed.clone()))
+    }
+
+    fn logic(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+    ) -> fof::LogicFormula<'a> {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::And(and) => (AssocConnective::And, &and.0),
+                    BinaryAssoc::Or(or) => (AssocConnective::Or, &or.0),
+                };
+                let formulas =
+                    units.iter().map(|unit| self.unit(unit)).collect();
+                fof_assoc(op, formulas)
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(_)) => {
+                unreachable!("not in negation normal form")
+            }
+            LogicFormula::Unary(unary) => {
+                LogicFormula::Unary(self.unary(unary))
+            }
+            LogicFormula::Unitary(unitary) => self.unitary(unitary),
+        }
+    }
+
+    fn unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+    ) -> fof::LogicFormula<'a> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.unitary(unitary),
+            fof::UnitFormula::Unary(unary) => {
+                fof::LogicFormula::Unary(self.unary(unary))
+            }
+        }
+    }
+
+    fn unary(&self, formula: &fof::UnaryFormula<'a>) -> fof::UnaryFormula<'a> {
+        let lookup = |variable: &Variable<'a>| self.lookup(variable);
+        match formula {
+            fof::UnaryFormula::Unary(connective, unit) => match &**unit {
+                fof::UnitFormula::Unitary(fof::UnitaryFormula::Atomic(
+                    atom,
+                )) => {
+                    let atom = Box::new(apply_atom(atom, &lookup));
+                    let unit = fof::UnitFormula::Unitary(
+                        fof::UnitaryFormula::Atomic(atom),
+                    );
+                    fof::UnaryFormula::Unary(*connective, Box::new(unit))
+                }
+                _ => unreachable!("not in negation normal form"),
+            },
+            fof::UnaryFormula::InfixUnary(infix) => {
+                fof::UnaryFormula::InfixUnary(fof::InfixUnary {
+                    left: Box::new(apply_term(&infix.left, &lookup)),
+                    op: infix.op,
+                    right: Box::new(apply_term(&infix.right, &lookup)),
+                })
+            }
+        }
+    }
+
+    fn unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+    ) -> fof::LogicFormula<'a> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.renaming.len();
+                for variable in &quantified.bound.0 {
+                    let renamed = if self.used.contains(variable) {
+                        self.fresh.variable("X")
+                    } else {
+                        variable.clone()
+                    };
+                    self.used.insert(renamed.clone());
+                    self.renaming.push((variable.clone(), renamed.clone()));
+                    self.prefix.push((quantified.quantifier, renamed));
+                }
+                let matrix = self.unit(&quantified.formula);
+                self.renaming.truncate(depth);
+                matrix
+            }
+            fof::UnitaryFormula::Atomic(atom) => {
+                let lookup = |variable: &Variable<'a>| self.lookup(variable);
+                let atom = Box::new(apply_atom(atom, &lookup));
+                fof::LogicFormula::Unitary(fof::UnitaryFormula::Atomic(atom))
+            }
+            fof::UnitaryFormula::Parenthesised(formula) => self.logic(formula),
+        }
+    }
+}
+
+impl<'a> Prenex<'a> {
+    /// put a formula into prenex normal form
+    ///
+    /// The formula is first put into negation normal form.
+    /// Quantifiers are pulled out in order of occurrence, and bound variables are renamed using `fresh` where they would clash.
+    pub fn new(formula: &fof::Formula<'a>, fresh: &mut Fresh<'a>) -> Self {
+        fresh.avoid_fof_formula(formula);
+        let nnf = formula.0.nnf();
+        let mut prenexer = Prenexer {
+            fresh,
+            used: formula.free_variables().into_iter().collect(),
+            renaming: vec![],
+            prefix: vec![],
+        };
+        let matrix = prenexer.logic(&nnf);
+        let prefix = prenexer.prefix;
+        Self { prefix, matrix }
+    }
+
+    /// the prenex formula, grouping adjacent quantifiers of the same kind
+    pub fn to_formula(&self) -> fof::Formula<'a> {
+        let mut groups: Vec<(fof::Quantifier, Vec<Variable<'a>>)> = vec![];
+        for (quantifier, variable) in &self.prefix {
+            match groups.last_mut() {
+                Some((last, bound)) if last == quantifier => {
+                    bound.push(variable.clone())
+                }
+                _ => groups.push((*quantifier, vec![variable.clone()])),
+            }
+        }
+        let formula = groups.into_iter().rev().fold(
+            self.matrix.clone(),
+            |formula, (quantifier, bound)| {
+                fof::LogicFormula::Unitary(fof::UnitaryFormula::Quantified(
+                    fof::QuantifiedFormula {
+                        quantifier,
+                        bound: fof::VariableList(bound),
+                        formula: Box::new(fof_unit(formula)),
+                    },
+                ))
+            },
+        );
+        fof::Formula(formula)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fresh::Arena;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn prenex(input: &[u8]) -> String {
+        let arena = Arena::new();
+        let mut fresh = Fresh::new(&arena);
+        let formula = check_parse(fof::Formula::parse, input);
+        Prenex::new(&formula, &mut fresh).to_formula().to_string()
+    }
+
+    #[test]
+    fn test_prenex() {
+        assert_eq!(prenex(b"p\0"), "p");
+        assert_eq!(
+            prenex(b"![X]: p(X) => ?[Y]: q(Y)\0"),
+            "?[X,Y]:(~p(X)|q(Y))"
+        );
+        assert_eq!(
+            prenex(b"![X]: (p(X) & ![Y]: ?[Z]: r(X, Y, Z))\0"),
+            "![X,Y]:?[Z]:(p(X)&r(X,Y,Z))"
+        );
+        assert_eq!(
+            prenex(b"(![X]: p(X)) & (?[X]: q(X)) & r(X)\0"),
+            "![X0]:?[X1]:(p(X0)&q(X1)&r(X))"
+        );
+    }
+
+    #[test]
+    fn test_prefix() {
+        let arena = Arena::new();
+        let mut fresh = Fresh::new(&arena);
+        let formula =
+            check_parse(fof::Formula::parse, b"~?[X]: ![Y]: p(X, Y)\0");
+        let prenex = Prenex::new(&formula, &mut fresh);
+        let prefix: Vec<_> = prenex
+            .prefix
+            .iter()
+            .map(|(quantifier, variable)| (*quantifier, variable.to_string()))
+            .collect();
+        assert_eq!(
+            prefix,
+            [
+                (fof::Quantifier::Forall, String::from("X")),
+                (fof::Quantifier::Exists, String::from("Y"))
+            ]
+        );
+        assert_eq!(prenex.matrix.to_string(), "~p(X,Y)");
+    }
+}
@@ -0,0 +1,601 @@
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::fof;
+use crate::tfx;
+use crate::top::*;
+
+/// a document: text with optional line breaks
+#[derive(Clone, Debug)]
+enum Doc {
+    Text(String),
+    /// a line break, or `flat` if the enclosing group fits on one line
+    Line(&'static str),
+    /// `broken` if the enclosing group is broken, `flat` otherwise
+    IfBroken(&'static str, &'static str),
+    Concat(Vec<Doc>),
+    /// indent line breaks further
+    Nest(usize, Box<Doc>),
+    /// indent line breaks to the current column
+    Align(Box<Doc>),
+    /// lay out on one line if it fits, and break its lines otherwise
+    Group(Box<Doc>),
+}
+
+fn text(text: impl fmt::Display) -> Doc {
+    Doc::Text(text.to_string())
+}
+
+fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+fn nest(indent: usize, doc: Doc) -> Doc {
+    Doc::Nest(indent, Box::new(doc))
+}
+
+fn align(doc: Doc) -> Doc {
+    Doc::Align(Box::new(doc))
+}
+
+fn group(doc: Doc) -> Doc {
+    Doc::Group(Box::new(doc))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Broken,
+}
+
+/// documents still to be laid out, with their indentation and mode
+type Stack<'d> = Vec<(usize, Mode, &'d Doc)>;
+
+/// whether `doc` fits in `width` columns when flat, followed by `rest` up to its next line break
+fn fits(mut width: usize, doc: &Doc, rest: &Stack) -> bool {
+    let mut stack = vec![(0, Mode::Flat, doc)];
+    let mut rest = rest.iter().rev();
+    loop {
+        let (indent, mode, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some(next) => *next,
+                None => return true,
+            },
+        };
+        let length = match (doc, mode) {
+            (Doc::Text(text), _) => text.chars().count(),
+            (Doc::Line(_), Mode::Broken) => return true,
+            (Doc::Line(flat), Mode::Flat) => flat.len(),
+            (Doc::IfBroken(broken, _), Mode::Broken) => broken.len(),
+            (Doc::IfBroken(_, flat), Mode::Flat) => flat.len(),
+            (Doc::Concat(docs), _) => {
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+                0
+            }
+            (Doc::Nest(_, doc) | Doc::Align(doc) | Doc::Group(doc), _) => {
+                stack.push((indent, mode, doc));
+                0
+            }
+        };
+        width = match width.checked_sub(length) {
+            Some(width) => width,
+            None => return false,
+        };
+    }
+}
+
+/// lay out `doc` in `width` columns where possible
+fn render(doc: &Doc, width: usize) -> String {
+    let mut output = String::new();
+    let mut column = 0;
+    let mut stack = vec![(0, Mode::Broken, doc)];
+    while let Some((indent, mode, doc)) = stack.pop() {
+        match (doc, mode) {
+            (Doc::Text(text), _) => {
+                output.push_str(text);
+                column += text.chars().count();
+            }
+            (Doc::Line(_), Mode::Broken) => {
+                output.push('\n');
+                (0..indent).for_each(|_| output.push(' '));
+                column = indent;
+            }
+            (Doc::Line(text), Mode::Flat)
+            | (Doc::IfBroken(text, _), Mode::Broken)
+            | (Doc::IfBroken(_, text), Mode::Flat) => {
+                output.push_str(text);
+                column += text.len();
+            }
+            (Doc::Concat(docs), _) => {
+                stack.extend(docs.iter().rev().map(|doc| (indent, mode, doc)));
+            }
+            (Doc::Nest(extra, doc), _) => {
+                stack.push((indent + extra, mode, doc))
+            }
+            (Doc::Align(doc), _) => stack.push((column, mode, doc)),
+            (Doc::Group(doc), _) => {
+                let remaining = width.saturating_sub(column);
+                let mode =
+                    if mode == Mode::Flat || fits(remaining, doc, &stack) {
+                        Mode::Flat
+                    } else {
+                        Mode::Broken
+                    };
+                stack.push((indent, mode, doc));
+            }
+        }
+    }
+    output
+}
+
+/// a pretty-printer, laying out syntax over several lines in the style of the TPTP library
+///
+/// Line breaks and spaces are only inserted where the parser ignores whitespace, so parsing the output gives back the same syntax tree.
+/// Binary formulas break before each connective, aligned with their first operand or opening parenthesis.
+/// Annotated formulas which do not fit on one line have their formula and annotations on separate lines, aligned with each other.
+/// Atoms, terms and types are not broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Pretty {
+    /// the preferred maximum line width
+    pub width: usize,
+    /// the indentation of formulas and quantified subformulas
+    pub indent: usize,
+    /// whether to put spaces around connectives and inside parentheses
+    pub spaces: bool,
+    /// whether quantified formulas may break after the quantifier
+    pub break_quantifiers: bool,
+}
+
+impl Default for Pretty {
+    fn default() -> Self {
+        Self {
+            width: 80,
+            indent: 4,
+            spaces: true,
+            break_quantifiers: true,
+        }
+    }
+}
+
+impl Pretty {
+    pub fn new(
+        width: usize,
+        indent: usize,
+        spaces: bool,
+        break_quantifiers: bool,
+    ) -> Self {
+        Self {
+            width,
+            indent,
+            spaces,
+            break_quantifiers,
+        }
+    }
+
+    fn space(&self) -> &'static str {
+        if self.spaces {
+            " "
+        } else {
+            ""
+        }
+    }
+
+    /// `operands` separated by a binary connective, breaking before each connective
+    fn binary(&self, op: impl fmt::Display, operands: Vec<Doc>) -> Doc {
+        let space = self.space();
+        let mut docs = vec![];
+        for (index, operand) in operands.into_iter().enumerate() {
+            if index > 0 {
+                docs.push(Doc::Line(space));
+                docs.push(text(format!("{}{}", op, space)));
+            }
+            docs.push(align(operand));
+        }
+        group(concat(docs))
+    }
+
+    fn parenthesised(&self, inner: Doc) -> Doc {
+        let space = self.space();
+        let open = text(format!("({}", space));
+        let close = text(format!("{})", space));
+        group(align(concat(vec![open, inner, close])))
+    }
+
+    fn prefix(&self, op: impl fmt::Display, operand: Doc) -> Doc {
+        concat(vec![
+            text(format!("{}{}", op, self.space())),
+            align(operand),
+        ])
+    }
+
+    fn infix(
+        &self,
+        left: impl fmt::Display,
+        op: impl fmt::Display,
+        right: impl fmt::Display,
+    ) -> Doc {
+        let space = self.space();
+        text(format!("{}{}{}{}{}", left, space, op, space, right))
+    }
+
+    fn quantified(
+        &self,
+        quantifier: fof::Quantifier,
+        bound: String,
+        body: Doc,
+    ) -> Doc {
+        let space = self.space();
+        let head =
+            text(format!("{}{}[{}]{}:", quantifier, space, bound, space));
+        if self.break_quantifiers {
+            let body = nest(self.indent, concat(vec![Doc::Line(space), body]));
+            group(concat(vec![head, body]))
+        } else {
+            concat(vec![head, text(space), body])
+        }
+    }
+
+    fn fof_atomic(&self, atom: &fof::AtomicFormula) -> Doc {
+        match atom {
+            fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Infix(
+                infix,
+            )) => self.infix(&infix.left, infix.op, &infix.right),
+            atom => text(atom),
+        }
+    }
+
+    fn fof_unary(&self, formula: &fof::UnaryFormula) -> Doc {
+        match formula {
+            fof::UnaryFormula::Unary(op, unit) => {
+                self.prefix(op, self.fof_unit(unit))
+            }
+            fof::UnaryFormula::InfixUnary(infix) => {
+                self.infix(&infix.left, infix.op, &infix.right)
+            }
+        }
+    }
+
+    fn fof_unitary(&self, formula: &fof::UnitaryFormula) -> Doc {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => self.quantified(
+                quantified.quantifier,
+                quantified.bound.to_string(),
+                self.fof_unit(&quantified.formula),
+            ),
+            fof::UnitaryFormula::Atomic(atom) => self.fof_atomic(atom),
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.parenthesised(self.fof_logic(formula))
+            }
+        }
+    }
+
+    fn fof_unit(&self, formula: &fof::UnitFormula) -> Doc {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.fof_unitary(unitary),
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary),
+        }
+    }
+
+    fn fof_logic(&self, formula: &fof::LogicFormula) -> Doc {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::Or(or) => ('|', &or.0),
+                    BinaryAssoc::And(and) => ('&', &and.0),
+                };
+                let units = units.iter().map(|unit| self.fof_unit(unit));
+                self.binary(op, units.collect())
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.fof_unit(&binary.left);
+                let right = self.fof_unit(&binary.right);
+                self.binary(binary.op, vec![left, right])
+            }
+            LogicFormula::Unary(unary) => self.fof_unary(unary),
+            LogicFormula::Unitary(unitary) => self.fof_unitary(unitary),
+        }
+    }
+
+    fn literal(&self, literal: &cnf::Literal) -> Doc {
+        match literal {
+            cnf::Literal::Atomic(atom) => self.fof_atomic(atom),
+            cnf::Literal::NegatedAtomic(atom) => {
+                self.prefix('~', self.fof_atomic(atom))
+            }
+            cnf::Literal::Infix(infix) => {
+                self.infix(&infix.left, infix.op, &infix.right)
+            }
+        }
+    }
+
+    fn disjunction(&self, disjunction: &cnf::Disjunction) -> Doc {
+        let literals =
+            disjunction.0.iter().map(|literal| self.literal(literal));
+        self.binary('|', literals.collect())
+    }
+
+    fn cnf(&self, formula: &cnf::Formula) -> Doc {
+        match formula {
+            cnf::Formula::Disjunction(disjunction) => {
+                self.disjunction(disjunction)
+            }
+            cnf::Formula::Parenthesised(disjunction) => {
+                self.parenthesised(self.disjunction(disjunction))
+            }
+        }
+    }
+
+    fn tfx_prefix(&self, prefix: &tfx::PrefixUnary) -> Doc {
+        let operand = match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            tfx::PreunitFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+        };
+        self.prefix(prefix.op, operand)
+    }
+
+    fn tfx_unary(&self, formula: &tfx::UnaryFormula) -> Doc {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                self.infix(&infix.left, infix.op, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_variable(&self, variable: &tfx::Variable) -> String {
+        match variable {
+            tfx::Variable::Typed(typed) => {
+                format!("{}:{}{}", typed.variable, self.space(), typed.typ)
+            }
+            tfx::Variable::Untyped(variable) => variable.to_string(),
+        }
+    }
+
+    fn tfx_unitary(&self, formula: &tfx::UnitaryFormula) -> Doc {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let bound: Vec<_> = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| self.tfx_variable(variable))
+                    .collect();
+                self.quantified(
+                    quantified.quantifier,
+                    bound.join(","),
+                    self.tfx_unit(&quantified.formula),
+                )
+            }
+            tfx::UnitaryFormula::Atomic(atom) => text(atom),
+            tfx::UnitaryFormula::Variable(variable) => text(variable),
+            tfx::UnitaryFormula::Logic(formula) => {
+                self.parenthesised(self.tfx_logic(formula))
+            }
+        }
+    }
+
+    fn tfx_unit(&self, formula: &tfx::UnitFormula) -> Doc {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.infix(&infix.left, infix.op, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_logic(&self, formula: &tfx::LogicFormula) -> Doc {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::Or(or) => ('|', &or.0),
+                    BinaryAssoc::And(and) => ('&', &and.0),
+                };
+                let units = units.iter().map(|unit| self.tfx_unit(unit));
+                self.binary(op, units.collect())
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.tfx_unit(&binary.left);
+                let right = self.tfx_unit(&binary.right);
+                self.binary(binary.op, vec![left, right])
+            }
+            LogicFormula::Unary(unary) => self.tfx_unary(unary),
+            LogicFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            LogicFormula::DefinedInfix(infix) => {
+                self.infix(&infix.left, infix.op, &infix.right)
+            }
+        }
+    }
+
+    fn atom_typing(&self, typing: &tfx::AtomTyping) -> Doc {
+        match typing {
+            tfx::AtomTyping::Typing(atom, typ) => {
+                text(format!("{}:{}{}", atom, self.space(), typ))
+            }
+            tfx::AtomTyping::Parenthesised(typing) => {
+                self.parenthesised(self.atom_typing(typing))
+            }
+        }
+    }
+
+    fn tfx(&self, formula: &tfx::Formula) -> Doc {
+        match formula {
+            tfx::Formula::Logic(logic) => self.tfx_logic(logic),
+            tfx::Formula::AtomTyping(typing) => self.atom_typing(typing),
+        }
+    }
+
+    /// `dialect(name,role,` then the formula and annotations, one per line if they do not fit
+    fn annotated<T>(
+        &self,
+        dialect: &str,
+        annotated: &Annotated<T>,
+        formula: Doc,
+    ) -> Doc {
+        let mut body = vec![Doc::Line(""), formula];
+        if let Some(annotations) = &annotated.annotations.0 {
+            body.extend(vec![text(","), Doc::Line(""), text(&annotations.0)]);
+            if let Some(info) = &(annotations.1).0 {
+                body.extend(vec![text(","), Doc::Line(""), text(info)]);
+            }
+        }
+        let head =
+            format!("{}({},{},", dialect, annotated.name, annotated.role);
+        group(concat(vec![
+            text(head),
+            nest(self.indent, concat(body)),
+            Doc::IfBroken(" ).", ")."),
+        ]))
+    }
+
+    fn annotated_formula_doc(&self, annotated: &AnnotatedFormula) -> Doc {
+        match annotated {
+            AnnotatedFormula::Tfx(tfx) => {
+                self.annotated("tff", &tfx.0, self.tfx(&tfx.0.formula))
+            }
+            AnnotatedFormula::Fof(fof) => {
+                let formula = self.fof_logic(&fof.0.formula.0);
+                self.annotated("fof", &fof.0, formula)
+            }
+            AnnotatedFormula::Cnf(cnf) => {
+                self.annotated("cnf", &cnf.0, self.cnf(&cnf.0.formula))
+            }
+        }
+    }
+
+    /// lay out a FOF formula
+    pub fn fof_formula(&self, formula: &fof::Formula) -> String {
+        render(&self.fof_logic(&formula.0), self.width)
+    }
+
+    /// lay out a CNF formula
+    pub fn cnf_formula(&self, formula: &cnf::Formula) -> String {
+        render(&self.cnf(formula), self.width)
+    }
+
+    /// lay out a TFX formula
+    pub fn tfx_formula(&self, formula: &tfx::Formula) -> String {
+        render(&self.tfx(formula), self.width)
+    }
+
+    /// lay out an annotated formula, without a final newline
+    pub fn annotated_formula(&self, annotated: &AnnotatedFormula) -> String {
+        render(&self.annotated_formula_doc(annotated), self.width)
+    }
+
+    /// lay out a `TPTP_input`, without a final newline
+    pub fn input(&self, input: &TPTPInput) -> String {
+        match input {
+            TPTPInput::Annotated(annotated) => {
+                self.annotated_formula(annotated)
+            }
+            TPTPInput::Include(include) => include.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs(input: &[u8]) -> Vec<TPTPInput<'_>> {
+        crate::TPTPIterator::<()>::new(input)
+            .map(|input| input.expect("parse error"))
+            .collect()
+    }
+
+    const PROBLEM: &[u8] = b"
+        include('Axioms/SET001-0.ax', [a, b]).
+        fof(short, axiom, p).
+        fof(ax, axiom, ![X, Y]: ((p(X) & q(X, Y)) => (r(Y) | ~s(X, Y) | X = Y))).
+        fof(nested, conjecture, ?[X]: ~(~(p(X) <~> (q(X) ~& ![Y]: X != Y)))).
+        cnf(c_0_5, plain, (p(X) | ~q(X) | X != a | f(X) = X), inference(resolution, [status(thm)], [c_0_1, c_0_2])).
+        cnf(c, axiom, ~a = b, file('f.p', c), [useful, info]).
+        tff(f_type, type, f: ($i * $i) > $o).
+        tff(t, type, (t: $tType)).
+        tff(typed, axiom, ![X: $int, Y: $i]: (~~f(Y, Y) | ((~ (X = 1) => $less(X, 2)) & Y != Y))).
+    ";
+
+    #[test]
+    fn test_layout() {
+        let inputs = inputs(PROBLEM);
+        let pretty = Pretty::default();
+        assert_eq!(
+            pretty.input(&inputs[2]),
+            "fof(ax,axiom,! [X,Y] : ( ( p(X) & q(X,Y) ) => ( r(Y) | ~ s(X,Y) | X = Y ) ))."
+        );
+        assert_eq!(
+            pretty.input(&inputs[4]),
+            "\
+cnf(c_0_5,plain,
+    ( p(X) | ~ q(X) | X != a | f(X) = X ),
+    inference(resolution,[status(thm)],[c_0_1,c_0_2]) )."
+        );
+        assert_eq!(
+            pretty.input(&inputs[8]),
+            "\
+tff(typed,axiom,
+    ! [X: $int,Y: $i] :
+        ( ~ ~ f(Y,Y) | ( ( ~ ( X = 1 ) => $less(X,2) ) & Y != Y ) ) )."
+        );
+
+        let narrow = Pretty::new(30, 2, true, true);
+        assert_eq!(
+            narrow.input(&inputs[2]),
+            "\
+fof(ax,axiom,
+  ! [X,Y] :
+    ( ( p(X) & q(X,Y) )
+    => ( r(Y)
+       | ~ s(X,Y)
+       | X = Y ) ) )."
+        );
+        let compact = Pretty::new(30, 2, false, false);
+        assert_eq!(
+            compact.input(&inputs[2]),
+            "\
+fof(ax,axiom,
+  ![X,Y]:((p(X)&q(X,Y))
+         =>(r(Y)
+           |~s(X,Y)
+           |X=Y)) )."
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = inputs(PROBLEM);
+        let compact = Pretty::new(usize::MAX, 0, false, false);
+        for input in &inputs {
+            assert_eq!(compact.input(input), input.to_string());
+        }
+        for width in [0, 20, 40, 80] {
+            for spaces in [false, true] {
+                for break_quantifiers in [false, true] {
+                    let pretty =
+                        Pretty::new(width, 3, spaces, break_quantifiers);
+                    let printed: Vec<_> = inputs
+                        .iter()
+                        .map(|input| pretty.input(input))
+                        .collect();
+                    let printed = printed.join("\n");
+                    assert_eq!(
+                        super::tests::inputs(printed.as_bytes()),
+                        inputs
+                    );
+                }
+            }
+        }
+    }
+}
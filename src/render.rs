@@ -0,0 +1,712 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::clausify::tfx_terms;
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::subst::{atom_view, function_view, Arguments, Head};
+use crate::tfx;
+
+/// the output notation of a `Renderer`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Notation {
+    /// LaTeX math mode, e.g. `\forall X.\, p(X) \Rightarrow q(X)`
+    Latex,
+    /// plain Unicode, e.g. `∀X. p(X) → q(X)`
+    Unicode,
+}
+
+/// how to render a functor, predicate or type
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Symbol {
+    /// applied to arguments as usual, but under another name
+    Name(String),
+    /// a unary prefix operator, binding tighter than any infix operator
+    Prefix(String),
+    /// a binary infix operator with a precedence: higher binds tighter
+    Infix(String, u8),
+}
+
+/// the precedence of formulas: lower binds less tightly
+const QUANTIFIED: u8 = 0;
+const NONASSOC: u8 = 1;
+const OR: u8 = 2;
+const AND: u8 = 3;
+const EQUATION: u8 = 4;
+const UNARY: u8 = 5;
+const ATOMIC: u8 = 6;
+
+/// the precedence of prefix and non-operator terms
+const PREFIX_TERM: u8 = u8::MAX - 1;
+const ATOMIC_TERM: u8 = u8::MAX;
+
+/// a rendered formula
+struct Rendered {
+    text: String,
+    precedence: u8,
+    /// whether the text ends with a quantifier whose scope could extend further right
+    open: bool,
+}
+
+fn closed(text: String, precedence: u8) -> Rendered {
+    let open = false;
+    Rendered {
+        text,
+        precedence,
+        open,
+    }
+}
+
+/// a renderer of formulas as mathematics, in LaTeX or Unicode
+///
+/// Unlike `Display`, parentheses are inserted only where precedence requires them.
+/// `~` binds tightest, then equations, `&`, `|`, the non-associative connectives, and finally quantifiers, whose scope extends as far right as possible.
+/// Functors, predicates and types are rendered according to a table of `symbols`, keyed by name: `$sum` is an infix `+` by default.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Renderer {
+    pub notation: Notation,
+    pub symbols: BTreeMap<String, Symbol>,
+}
+
+impl Renderer {
+    /// a renderer with the default symbols for `notation`
+    pub fn new(notation: Notation) -> Self {
+        let table: &[(&str, &str, &str, Option<u8>)] = &[
+            ("$uminus", "-", "-", None),
+            ("$sum", "+", "+", Some(1)),
+            ("$difference", "-", "-", Some(1)),
+            ("$product", "×", "\\times", Some(2)),
+            ("$quotient", "/", "/", Some(2)),
+            ("$less", "<", "<", Some(0)),
+            ("$lesseq", "≤", "\\leq", Some(0)),
+            ("$greater", ">", ">", Some(0)),
+            ("$greatereq", "≥", "\\geq", Some(0)),
+        ];
+        let names: &[(&str, &str, &str)] = &[
+            ("$true", "⊤", "\\top"),
+            ("$false", "⊥", "\\bot"),
+            ("$i", "ι", "\\iota"),
+            ("$o", "o", "o"),
+            ("$tType", "Type", "\\mathsf{Type}"),
+            ("$int", "ℤ", "\\mathbb{Z}"),
+            ("$rat", "ℚ", "\\mathbb{Q}"),
+            ("$real", "ℝ", "\\mathbb{R}"),
+        ];
+        let pick = |unicode: &str, latex: &str| match notation {
+            Notation::Latex => latex.to_string(),
+            Notation::Unicode => unicode.to_string(),
+        };
+        let mut symbols = BTreeMap::new();
+        for (name, unicode, latex, precedence) in table {
+            let operator = pick(unicode, latex);
+            let symbol = match precedence {
+                Some(precedence) => Symbol::Infix(operator, *precedence),
+                None => Symbol::Prefix(operator),
+            };
+            symbols.insert(name.to_string(), symbol);
+        }
+        for (name, unicode, latex) in names {
+            symbols
+                .insert(name.to_string(), Symbol::Name(pick(unicode, latex)));
+        }
+        Self { notation, symbols }
+    }
+
+    fn pick(
+        &self,
+        unicode: &'static str,
+        latex: &'static str,
+    ) -> &'static str {
+        match self.notation {
+            Notation::Latex => latex,
+            Notation::Unicode => unicode,
+        }
+    }
+
+    /// TPTP syntax as it is, escaped and set as text for LaTeX
+    fn text(&self, text: &str) -> String {
+        if self.notation == Notation::Unicode {
+            return text.to_string();
+        }
+        let mut escaped = String::new();
+        for c in text.chars() {
+            match c {
+                '\\' => escaped.push_str("\\textbackslash{}"),
+                '~' => escaped.push_str("\\textasciitilde{}"),
+                '^' => escaped.push_str("\\textasciicircum{}"),
+                '_' | '$' | '&' | '%' | '#' | '{' | '}' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                c => escaped.push(c),
+            }
+        }
+        format!("\\text{{{}}}", escaped)
+    }
+
+    /// a name, escaped and set in italics for LaTeX if it has several letters
+    fn name(&self, name: &str) -> String {
+        if self.notation == Notation::Unicode {
+            return name.to_string();
+        }
+        if name.starts_with('\'') || name.starts_with('"') {
+            return self.text(name);
+        }
+        let escaped = name.replace('_', "\\_").replace('$', "\\$");
+        if name.chars().count() > 1 {
+            format!("\\mathit{{{}}}", escaped)
+        } else {
+            escaped
+        }
+    }
+
+    fn parenthesise(&self, text: &str) -> String {
+        format!("({})", text)
+    }
+
+    /// `head` applied to `arguments`, with its precedence as a term
+    fn application(&self, head: Head, arguments: Arguments) -> (String, u8) {
+        let name = match head {
+            Head::Plain(functor) => functor.to_string(),
+            Head::Defined(functor) => functor.to_string(),
+            Head::System(functor) => functor.to_string(),
+            Head::Value(DefinedTerm::Number(number)) => {
+                return (number.to_string(), ATOMIC_TERM)
+            }
+            Head::Value(DefinedTerm::Distinct(distinct)) => {
+                return (self.name(&distinct.to_string()), ATOMIC_TERM)
+            }
+            Head::Equality => "=".to_string(),
+        };
+        let arguments: Vec<_> = arguments
+            .iter()
+            .map(|term| self.term_precedence(term))
+            .collect();
+        match (self.symbols.get(&name), arguments.as_slice()) {
+            (Some(Symbol::Infix(op, precedence)), [left, right]) => {
+                let precedence = *precedence;
+                let left = if left.1 < precedence {
+                    self.parenthesise(&left.0)
+                } else {
+                    left.0.clone()
+                };
+                let right = if right.1 <= precedence {
+                    self.parenthesise(&right.0)
+                } else {
+                    right.0.clone()
+                };
+                (format!("{} {} {}", left, op, right), precedence)
+            }
+            (Some(Symbol::Prefix(op)), [operand]) => {
+                let operand = if operand.1 < PREFIX_TERM {
+                    self.parenthesise(&operand.0)
+                } else {
+                    operand.0.clone()
+                };
+                (format!("{}{}", op, operand), PREFIX_TERM)
+            }
+            (symbol, arguments) => {
+                let name = match symbol {
+                    Some(Symbol::Name(name)) => name.clone(),
+                    _ => self.name(&name),
+                };
+                if arguments.is_empty() {
+                    return (name, ATOMIC_TERM);
+                }
+                let arguments: Vec<_> =
+                    arguments.iter().map(|(text, _)| text.as_str()).collect();
+                (format!("{}({})", name, arguments.join(", ")), ATOMIC_TERM)
+            }
+        }
+    }
+
+    fn term_precedence(&self, term: &fof::Term) -> (String, u8) {
+        match term {
+            fof::Term::Variable(variable) => {
+                (self.name(&variable.to_string()), ATOMIC_TERM)
+            }
+            fof::Term::Function(function) => {
+                let (head, arguments) = function_view(function);
+                self.application(head, arguments)
+            }
+        }
+    }
+
+    fn equation(
+        &self,
+        left: &fof::Term,
+        op: &'static str,
+        right: &fof::Term,
+    ) -> Rendered {
+        let left = self.term_precedence(left).0;
+        let right = self.term_precedence(right).0;
+        closed(format!("{} {} {}", left, op, right), EQUATION)
+    }
+
+    fn atom(&self, atom: &fof::AtomicFormula) -> Rendered {
+        let (head, arguments) = atom_view(atom);
+        if let (Head::Equality, Arguments::Pair([left, right])) =
+            (&head, &arguments)
+        {
+            return self.equation(left, "=", right);
+        }
+        let (text, precedence) = self.application(head, arguments);
+        let precedence = if precedence == ATOMIC_TERM {
+            ATOMIC
+        } else {
+            EQUATION
+        };
+        closed(text, precedence)
+    }
+
+    /// `rendered` as an operand needing at least `minimum` precedence, and whether the result is open
+    fn operand(
+        &self,
+        rendered: Rendered,
+        minimum: u8,
+        last: bool,
+    ) -> (String, bool) {
+        let quantified = last && rendered.precedence == QUANTIFIED;
+        let loose = rendered.precedence < minimum && !quantified;
+        if loose || (rendered.open && !last) {
+            (self.parenthesise(&rendered.text), false)
+        } else {
+            (rendered.text, rendered.open)
+        }
+    }
+
+    fn binary(
+        &self,
+        op: &'static str,
+        operands: Vec<Rendered>,
+        precedence: u8,
+        minimum: u8,
+    ) -> Rendered {
+        let count = operands.len();
+        let mut open = false;
+        let mut texts = Vec::with_capacity(count);
+        for (index, operand) in operands.into_iter().enumerate() {
+            let last = index + 1 == count;
+            let (text, operand_open) = self.operand(operand, minimum, last);
+            open = operand_open;
+            texts.push(text);
+        }
+        let text = texts.join(&format!(" {} ", op));
+        Rendered {
+            text,
+            precedence,
+            open,
+        }
+    }
+
+    fn assoc(
+        &self,
+        connective: AssocConnective,
+        operands: Vec<Rendered>,
+    ) -> Rendered {
+        match connective {
+            AssocConnective::And => {
+                self.binary(self.pick("∧", "\\land"), operands, AND, AND)
+            }
+            AssocConnective::Or => {
+                self.binary(self.pick("∨", "\\lor"), operands, OR, OR)
+            }
+        }
+    }
+
+    fn nonassoc(
+        &self,
+        connective: NonassocConnective,
+        left: Rendered,
+        right: Rendered,
+    ) -> Rendered {
+        use NonassocConnective::*;
+        let op = match connective {
+            LRImplies => self.pick("→", "\\Rightarrow"),
+            RLImplies => self.pick("←", "\\Leftarrow"),
+            Equivalent => self.pick("↔", "\\Leftrightarrow"),
+            NotEquivalent => self.pick("⊕", "\\oplus"),
+            NotOr => self.pick("↓", "\\downarrow"),
+            NotAnd => self.pick("↑", "\\uparrow"),
+        };
+        self.binary(op, alloc::vec![left, right], NONASSOC, OR)
+    }
+
+    fn negation(&self, operand: Rendered) -> Rendered {
+        let (text, open) = self.operand(operand, UNARY, true);
+        let text = format!("{}{}", self.pick("¬", "\\neg "), text);
+        let precedence = UNARY;
+        Rendered {
+            text,
+            precedence,
+            open,
+        }
+    }
+
+    fn quantified(
+        &self,
+        quantifier: fof::Quantifier,
+        bound: Vec<String>,
+        body: Rendered,
+    ) -> Rendered {
+        let bound = bound.join(", ");
+        let text = match (self.notation, quantifier) {
+            (Notation::Latex, fof::Quantifier::Forall) => {
+                format!("\\forall {}.\\, {}", bound, body.text)
+            }
+            (Notation::Latex, fof::Quantifier::Exists) => {
+                format!("\\exists {}.\\, {}", bound, body.text)
+            }
+            (Notation::Unicode, fof::Quantifier::Forall) => {
+                format!("∀{}. {}", bound, body.text)
+            }
+            (Notation::Unicode, fof::Quantifier::Exists) => {
+                format!("∃{}. {}", bound, body.text)
+            }
+        };
+        Rendered {
+            text,
+            precedence: QUANTIFIED,
+            open: true,
+        }
+    }
+
+    fn fof_unitary(&self, formula: &fof::UnitaryFormula) -> Rendered {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let bound = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| self.name(&variable.to_string()))
+                    .collect();
+                let body = self.fof_unit(&quantified.formula);
+                self.quantified(quantified.quantifier, bound, body)
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.atom(atom),
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.fof_logic(formula)
+            }
+        }
+    }
+
+    fn fof_unary(&self, formula: &fof::UnaryFormula) -> Rendered {
+        match formula {
+            fof::UnaryFormula::Unary(_, unit) => {
+                self.negation(self.fof_unit(unit))
+            }
+            fof::UnaryFormula::InfixUnary(infix) => {
+                let op = self.pick("≠", "\\neq");
+                self.equation(&infix.left, op, &infix.right)
+            }
+        }
+    }
+
+    fn fof_unit(&self, formula: &fof::UnitFormula) -> Rendered {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.fof_unitary(unitary),
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary),
+        }
+    }
+
+    fn fof_logic(&self, formula: &fof::LogicFormula) -> Rendered {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (connective, units) = match assoc {
+                    BinaryAssoc::Or(or) => (AssocConnective::Or, &or.0),
+                    BinaryAssoc::And(and) => (AssocConnective::And, &and.0),
+                };
+                let units = units.iter().map(|unit| self.fof_unit(unit));
+                self.assoc(connective, units.collect())
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.fof_unit(&binary.left);
+                let right = self.fof_unit(&binary.right);
+                self.nonassoc(binary.op, left, right)
+            }
+            LogicFormula::Unary(unary) => self.fof_unary(unary),
+            LogicFormula::Unitary(unitary) => self.fof_unitary(unitary),
+        }
+    }
+
+    fn literal(&self, literal: &cnf::Literal) -> Rendered {
+        match literal {
+            cnf::Literal::Atomic(atom) => self.atom(atom),
+            cnf::Literal::NegatedAtomic(atom) => {
+                self.negation(self.atom(atom))
+            }
+            cnf::Literal::Infix(infix) => {
+                let op = self.pick("≠", "\\neq");
+                self.equation(&infix.left, op, &infix.right)
+            }
+        }
+    }
+
+    fn disjunction(&self, disjunction: &cnf::Disjunction) -> Rendered {
+        let literals =
+            disjunction.0.iter().map(|literal| self.literal(literal));
+        self.assoc(AssocConnective::Or, literals.collect())
+    }
+
+    /// a TFX term which is not first-order is rendered as TPTP text
+    fn tfx_term(&self, term: &tfx::UnitaryTerm) -> String {
+        match tfx_terms::unitary(term) {
+            Some(term) => self.term_precedence(&term).0,
+            None => self.text(&term.to_string()),
+        }
+    }
+
+    fn tfx_equation(
+        &self,
+        left: &tfx::UnitaryTerm,
+        op: &'static str,
+        right: &tfx::UnitaryTerm,
+    ) -> Rendered {
+        let left = self.tfx_term(left);
+        let right = self.tfx_term(right);
+        closed(format!("{} {} {}", left, op, right), EQUATION)
+    }
+
+    /// a type, with symbols replaced and type connectives rendered
+    fn typ(&self, typ: &str) -> String {
+        let mut rendered = String::new();
+        let mut rest = typ;
+        while let Some(c) = rest.chars().next() {
+            let word = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            if word > 0 {
+                let word = &rest[..word];
+                match self.symbols.get(word) {
+                    Some(Symbol::Name(name)) => rendered.push_str(name),
+                    _ => rendered.push_str(&self.name(word)),
+                }
+                rest = &rest[word.len()..];
+                continue;
+            }
+            let (text, length) = match c {
+                '!' if rest.starts_with("!>") => (self.pick("Π", "\\Pi "), 2),
+                '>' => (self.pick(" → ", " \\to "), 1),
+                '*' => (self.pick(" × ", " \\times "), 1),
+                ':' => (": ", 1),
+                ',' => (", ", 1),
+                _ => ("", 0),
+            };
+            if length > 0 {
+                rendered.push_str(text);
+                rest = &rest[length..];
+            } else {
+                rendered.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        rendered
+    }
+
+    fn tfx_variable(&self, variable: &tfx::Variable) -> String {
+        match variable {
+            tfx::Variable::Typed(typed) => {
+                let name = self.name(&typed.variable.to_string());
+                format!("{}: {}", name, self.typ(&typed.typ.to_string()))
+            }
+            tfx::Variable::Untyped(variable) => {
+                self.name(&variable.to_string())
+            }
+        }
+    }
+
+    fn tfx_prefix(&self, prefix: &tfx::PrefixUnary) -> Rendered {
+        let operand = match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            tfx::PreunitFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+        };
+        self.negation(operand)
+    }
+
+    fn tfx_unary(&self, formula: &tfx::UnaryFormula) -> Rendered {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                let op = self.pick("≠", "\\neq");
+                self.tfx_equation(&infix.left, op, &infix.right)
+            }
+        }
+    }
+
+    fn tfx_unitary(&self, formula: &tfx::UnitaryFormula) -> Rendered {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let bound = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| self.tfx_variable(variable))
+                    .collect();
+                let body = self.tfx_unit(&quantified.formula);
+                self.quantified(quantified.quantifier, bound, body)
+            }
+            tfx::UnitaryFormula::Atomic(atom) => match tfx_terms::atom(atom) {
+                Some(atom) => self.atom(&atom),
+                None => closed(self.text(&atom.to_string()), ATOMIC),
+            },
+            tfx::UnitaryFormula::Variable(variable) => {
+                closed(self.name(&variable.to_string()), ATOMIC)
+            }
+            tfx::UnitaryFormula::Logic(formula) => self.tfx_logic(formula),
+        }
+    }
+
+    fn tfx_unit(&self, formula: &tfx::UnitFormula) -> Rendered {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_equation(&infix.left, "=", &infix.right)
+            }
+        }
+    }
+
+    fn tfx_logic(&self, formula: &tfx::LogicFormula) -> Rendered {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (connective, units) = match assoc {
+                    BinaryAssoc::Or(or) => (AssocConnective::Or, &or.0),
+                    BinaryAssoc::And(and) => (AssocConnective::And, &and.0),
+                };
+                let units = units.iter().map(|unit| self.tfx_unit(unit));
+                self.assoc(connective, units.collect())
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.tfx_unit(&binary.left);
+                let right = self.tfx_unit(&binary.right);
+                self.nonassoc(binary.op, left, right)
+            }
+            LogicFormula::Unary(unary) => self.tfx_unary(unary),
+            LogicFormula::Unitary(unitary) => self.tfx_unitary(unitary),
+            LogicFormula::DefinedInfix(infix) => {
+                self.tfx_equation(&infix.left, "=", &infix.right)
+            }
+        }
+    }
+
+    fn atom_typing(&self, typing: &tfx::AtomTyping) -> String {
+        match typing {
+            tfx::AtomTyping::Typing(atom, typ) => {
+                let atom = self.name(&atom.to_string());
+                format!("{}: {}", atom, self.typ(&typ.to_string()))
+            }
+            tfx::AtomTyping::Parenthesised(typing) => self.atom_typing(typing),
+        }
+    }
+
+    /// render a term
+    pub fn term(&self, term: &fof::Term) -> String {
+        self.term_precedence(term).0
+    }
+
+    /// render a FOF formula
+    pub fn fof_formula(&self, formula: &fof::Formula) -> String {
+        self.fof_logic(&formula.0).text
+    }
+
+    /// render a CNF formula
+    pub fn cnf_formula(&self, formula: &cnf::Formula) -> String {
+        match formula {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => {
+                self.disjunction(disjunction).text
+            }
+        }
+    }
+
+    /// render a TFX formula, or a type declaration
+    pub fn tfx_formula(&self, formula: &tfx::Formula) -> String {
+        match formula {
+            tfx::Formula::Logic(logic) => self.tfx_logic(logic).text,
+            tfx::Formula::AtomTyping(typing) => self.atom_typing(typing),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::check_parse;
+    use crate::Parse;
+
+    #[test]
+    fn test_fof() {
+        let latex = Renderer::new(Notation::Latex);
+        let unicode = Renderer::new(Notation::Unicode);
+        for (formula, expected_latex, expected_unicode) in [
+            (
+                &b"![X]:(p(X)=>q(X))\0"[..],
+                "\\forall X.\\, p(X) \\Rightarrow q(X)",
+                "∀X. p(X) → q(X)",
+            ),
+            (
+                b"((p|(q&r))|~(s=t))\0",
+                "p \\lor q \\land r \\lor \\neg (s = t)",
+                "p ∨ q ∧ r ∨ ¬(s = t)",
+            ),
+            (
+                b"((![X]:p(X))&q)\0",
+                "(\\forall X.\\, p(X)) \\land q",
+                "(∀X. p(X)) ∧ q",
+            ),
+            (
+                b"(q&~?[Y]:r(Y,f_x))\0",
+                "q \\land \\neg \\exists Y.\\, r(Y, \\mathit{f\\_x})",
+                "q ∧ ¬∃Y. r(Y, f_x)",
+            ),
+            (
+                b"((p=>q)=>(p<=>~(a!=b)))\0",
+                "(p \\Rightarrow q) \\Rightarrow (p \\Leftrightarrow \\neg (a \\neq b))",
+                "(p → q) → (p ↔ ¬(a ≠ b))",
+            ),
+        ] {
+            let formula = check_parse(fof::Formula::parse, formula);
+            assert_eq!(latex.fof_formula(&formula), expected_latex);
+            assert_eq!(unicode.fof_formula(&formula), expected_unicode);
+        }
+    }
+
+    #[test]
+    fn test_tfx() {
+        let mut unicode = Renderer::new(Notation::Unicode);
+        let formula = check_parse(
+            tfx::Formula::parse,
+            b"![X:$int]:$less($product($sum(X,1),$uminus(X)),$difference(X,$difference(2,X)))\0",
+        );
+        assert_eq!(
+            unicode.tfx_formula(&formula),
+            "∀X: ℤ. (X + 1) × -X < X - (2 - X)"
+        );
+        unicode
+            .symbols
+            .insert("$less".into(), Symbol::Name("lt".into()));
+        assert_eq!(
+            unicode.tfx_formula(&formula),
+            "∀X: ℤ. lt((X + 1) × -X, X - (2 - X))"
+        );
+        let typing = check_parse(tfx::Formula::parse, b"f:($i*$int)>$o\0");
+        assert_eq!(unicode.tfx_formula(&typing), "f: (ι × ℤ) → o");
+
+        let latex = Renderer::new(Notation::Latex);
+        let formula = check_parse(tfx::Formula::parse, b"e=$ite(p&q_r,a,b)\0");
+        assert_eq!(
+            latex.tfx_formula(&formula),
+            "e = \\text{\\$ite(p\\&q\\_r,a,b)}"
+        );
+    }
+}
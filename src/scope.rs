@@ -0,0 +1,219 @@
+use alloc::vec::Vec;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::tfx;
+use crate::top::*;
+use crate::visitor::Visitor;
+
+/// a scoping problem found by `check_scope`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ScopeError<'a> {
+    /// a variable occurs free in a formula that must be closed
+    #[display(fmt = "free variable {}", _0)]
+    Free(Variable<'a>),
+    /// a variable is bound twice in one quantifier list, as in `![X,X]:p(X)`
+    #[display(fmt = "variable {} bound twice", _0)]
+    Duplicate(Variable<'a>),
+    /// a binding hides an enclosing one, as in `![X]:?[X]:p(X)`
+    #[display(fmt = "variable {} shadows an outer binding", _0)]
+    Shadowed(Variable<'a>),
+}
+
+/// syntax in which variables may occur free
+pub trait FreeVariables<'a> {
+    /// the free variables, without repeats, in order of first occurrence
+    fn free_variables(&self) -> Vec<Variable<'a>>;
+}
+
+#[derive(Default)]
+struct Scope<'a> {
+    bound: Vec<Variable<'a>>,
+    free: Vec<Variable<'a>>,
+    errors: Vec<ScopeError<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn bind(&mut self, variable: &Variable<'a>, list_start: usize) {
+        if self.bound[list_start..].contains(variable) {
+            self.errors.push(ScopeError::Duplicate(variable.clone()));
+        } else if self.bound[..list_start].contains(variable) {
+            self.errors.push(ScopeError::Shadowed(variable.clone()));
+        }
+        self.bound.push(variable.clone());
+    }
+
+    fn bind_tfx_variables(&mut self, list: &tfx::VariableList<'a>) {
+        let list_start = self.bound.len();
+        for variable in &list.0 {
+            match variable {
+                tfx::Variable::Typed(typed) => {
+                    self.visit_tfx_atomic_type(&typed.typ);
+                    self.bind(&typed.variable, list_start);
+                }
+                tfx::Variable::Untyped(variable) => {
+                    self.bind(variable, list_start);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for Scope<'a> {
+    fn visit_variable(&mut self, variable: &Variable<'a>) {
+        if !self.bound.contains(variable) && !self.free.contains(variable) {
+            self.free.push(variable.clone());
+        }
+    }
+
+    fn visit_fof_quantified_formula(
+        &mut self,
+        fof_quantified_formula: &fof::QuantifiedFormula<'a>,
+    ) {
+        let list_start = self.bound.len();
+        for variable in &fof_quantified_formula.bound.0 {
+            self.bind(variable, list_start);
+        }
+        self.visit_fof_unit_formula(&fof_quantified_formula.formula);
+        self.bound.truncate(list_start);
+    }
+
+    fn visit_tfx_quantified_formula(
+        &mut self,
+        tfx_quantified_formula: &tfx::QuantifiedFormula<'a>,
+    ) {
+        let list_start = self.bound.len();
+        self.bind_tfx_variables(&tfx_quantified_formula.bound);
+        self.visit_tfx_unit_formula(&tfx_quantified_formula.formula);
+        self.bound.truncate(list_start);
+    }
+
+    fn visit_tfx_quantified_type(
+        &mut self,
+        tfx_quantified_type: &tfx::QuantifiedType<'a>,
+    ) {
+        let list_start = self.bound.len();
+        self.bind_tfx_variables(&tfx_quantified_type.bound);
+        self.visit_tfx_monotype(&tfx_quantified_type.typ);
+        self.bound.truncate(list_start);
+    }
+}
+
+macro_rules! free_variables {
+    ($dialect: ident :: $T: ident, $visit: ident) => {
+        impl<'a> FreeVariables<'a> for $dialect::$T<'a> {
+            fn free_variables(&self) -> Vec<Variable<'a>> {
+                let mut scope = Scope::default();
+                scope.$visit(self);
+                scope.free
+            }
+        }
+    };
+}
+
+free_variables!(fof::Term, visit_fof_term);
+free_variables!(fof::UnitFormula, visit_fof_unit_formula);
+free_variables!(fof::LogicFormula, visit_fof_logic_formula);
+free_variables!(fof::Formula, visit_fof_formula);
+free_variables!(cnf::Literal, visit_literal);
+free_variables!(cnf::Disjunction, visit_disjunction);
+free_variables!(cnf::Formula, visit_cnf_formula);
+free_variables!(tfx::Term, visit_tfx_term);
+free_variables!(tfx::UnitFormula, visit_tfx_unit_formula);
+free_variables!(tfx::LogicFormula, visit_tfx_logic_formula);
+free_variables!(tfx::Formula, visit_tfx_formula);
+
+/// check variable scoping in an annotated formula
+///
+/// FOF and TFX formulas must be closed, so free variables are reported.
+/// CNF formulas are implicitly universally quantified and contain no binders,
+/// so they never have scoping problems.
+/// Annotations are not checked.
+pub fn check_scope<'a>(
+    annotated: &AnnotatedFormula<'a>,
+) -> Vec<ScopeError<'a>> {
+    let mut scope = Scope::default();
+    match annotated {
+        AnnotatedFormula::Fof(fof) => scope.visit_fof_formula(&fof.0.formula),
+        AnnotatedFormula::Tfx(tfx) => scope.visit_tfx_formula(&tfx.0.formula),
+        AnnotatedFormula::Cnf(_) => return Vec::new(),
+    }
+    let mut errors = scope.errors;
+    errors.extend(scope.free.into_iter().map(ScopeError::Free));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn names(variables: Vec<Variable>) -> Vec<String> {
+        variables.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn errors(input: &[u8]) -> Vec<String> {
+        let annotated = check_parse(AnnotatedFormula::parse, input);
+        check_scope(&annotated)
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_fof_free_variables() {
+        let term = check_parse(fof::Term::parse, b"f(X, g(Y, X))\0");
+        assert_eq!(names(term.free_variables()), ["X", "Y"]);
+        let formula =
+            check_parse(fof::Formula::parse, b"![X]: p(X, Y) & q(X)\0");
+        assert_eq!(names(formula.free_variables()), ["Y", "X"]);
+        let formula =
+            check_parse(fof::Formula::parse, b"![X]: ?[Y]: p(X, Y)\0");
+        assert!(formula.free_variables().is_empty());
+    }
+
+    #[test]
+    fn test_cnf_free_variables() {
+        let formula = check_parse(cnf::Formula::parse, b"p(X) | ~q(Y, X)\0");
+        assert_eq!(names(formula.free_variables()), ["X", "Y"]);
+    }
+
+    #[test]
+    fn test_tfx_free_variables() {
+        let formula = check_parse(
+            tfx::Formula::parse,
+            b"![A: $tType, X: A]: p(X, Y) = Z\0",
+        );
+        assert_eq!(names(formula.free_variables()), ["Y", "Z"]);
+        let formula = check_parse(tfx::Formula::parse, b"![X: B]: p(X)\0");
+        assert_eq!(names(formula.free_variables()), ["B"]);
+        let formula =
+            check_parse(tfx::Formula::parse, b"f: !>[A: $tType]: (A > B)\0");
+        assert_eq!(names(formula.free_variables()), ["B"]);
+    }
+
+    #[test]
+    fn test_check_scope() {
+        assert!(errors(b"fof(a, axiom, ![X]: p(X)).\0").is_empty());
+        assert!(errors(b"cnf(a, axiom, p(X)).\0").is_empty());
+        assert_eq!(errors(b"fof(a, axiom, p(X)).\0"), ["free variable X"]);
+        assert_eq!(
+            errors(b"fof(a, axiom, ![X, X]: p(X)).\0"),
+            ["variable X bound twice"]
+        );
+        assert_eq!(
+            errors(b"tff(a, axiom, ![X: $i]: ?[X: $i]: p(X)).\0"),
+            ["variable X shadows an outer binding"]
+        );
+        assert!(
+            errors(b"fof(a, axiom, (![X]: p(X)) & ![X]: q(X)).\0").is_empty()
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::tfx;
+use crate::top::*;
+use crate::visitor::Visitor;
+
+struct Symbols<'s, 'a>(&'s mut BTreeSet<AtomicWord<'a>>);
+
+impl<'s, 'a> Visitor<'a> for Symbols<'s, 'a> {
+    fn visit_functor(&mut self, functor: &Functor<'a>) {
+        self.0.insert(functor.0.clone());
+    }
+
+    fn visit_type_functor(&mut self, type_functor: &TypeFunctor<'a>) {
+        self.0.insert(type_functor.0.clone());
+    }
+}
+
+/// the signature of `annotated`: its function, predicate and type symbols
+///
+/// Defined and system symbols are not included, nor are symbols in annotations.
+pub fn signature<'a>(
+    annotated: &AnnotatedFormula<'a>,
+) -> BTreeSet<AtomicWord<'a>> {
+    let mut signature = BTreeSet::new();
+    let mut symbols = Symbols(&mut signature);
+    match annotated {
+        AnnotatedFormula::Tfx(tfx) => {
+            symbols.visit_tfx_formula(&tfx.0.formula)
+        }
+        AnnotatedFormula::Fof(fof) => {
+            symbols.visit_fof_formula(&fof.0.formula)
+        }
+        AnnotatedFormula::Cnf(cnf) => {
+            symbols.visit_cnf_formula(&cnf.0.formula)
+        }
+    }
+    signature
+}
+
+/// the symbol declared by a `type`-role formula
+fn declaration<'t, 'a>(
+    annotated: &'t AnnotatedFormula<'a>,
+) -> Option<&'t AtomicWord<'a>> {
+    let tfx = match annotated {
+        AnnotatedFormula::Tfx(tfx) if (tfx.0.role.0).0 == "type" => tfx,
+        _ => return None,
+    };
+    let mut typing: &tfx::AtomTyping = match &*tfx.0.formula {
+        tfx::Formula::AtomTyping(typing) => typing,
+        tfx::Formula::Logic(_) => return None,
+    };
+    loop {
+        match typing {
+            tfx::AtomTyping::Parenthesised(inner) => typing = &**inner,
+            tfx::AtomTyping::Typing(UntypedAtom::Constant(constant), _) => {
+                return Some(&(constant.0).0)
+            }
+            tfx::AtomTyping::Typing(UntypedAtom::System(_), _) => return None,
+        }
+    }
+}
+
+fn is_seed(annotated: &AnnotatedFormula) -> bool {
+    matches!(
+        (annotated.role().0).0,
+        "conjecture" | "negated_conjecture" | "hypothesis"
+    )
+}
+
+/// SInE premise selection
+///
+/// A symbol triggers a formula if it occurs in the formula, and occurs in at most `tolerance` times as many formulas as the rarest symbol of that formula.
+/// Conjectures, negated conjectures and hypotheses are always selected, at depth 0.
+/// Formulas triggered by a symbol of a formula selected at depth `n` are selected at depth `n + 1`.
+/// Type declarations are not triggered, but are selected with the first formula which uses their symbol.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Sine {
+    /// how much more common than the rarest symbol of a formula a symbol may be and still trigger it, at least 1
+    pub tolerance: f64,
+    /// the greatest depth at which formulas are selected, or no limit
+    pub depth: Option<usize>,
+}
+
+impl Default for Sine {
+    fn default() -> Self {
+        Self {
+            tolerance: 1.0,
+            depth: None,
+        }
+    }
+}
+
+impl Sine {
+    pub fn new(tolerance: f64, depth: Option<usize>) -> Self {
+        Self { tolerance, depth }
+    }
+
+    /// the depth at which each formula is selected, if it is
+    pub fn relevance<'a>(
+        &self,
+        formulas: &[AnnotatedFormula<'a>],
+    ) -> Vec<Option<usize>> {
+        let signatures: Vec<_> = formulas.iter().map(signature).collect();
+        let declarations: Vec<_> = formulas.iter().map(declaration).collect();
+        let untyped = |index: &usize| declarations[*index].is_none();
+        let indices = 0..formulas.len();
+
+        let mut occurrences: BTreeMap<&AtomicWord<'a>, usize> =
+            BTreeMap::new();
+        for index in indices.clone().filter(untyped) {
+            for symbol in &signatures[index] {
+                *occurrences.entry(symbol).or_insert(0) += 1;
+            }
+        }
+
+        let mut triggers: BTreeMap<&AtomicWord<'a>, Vec<usize>> =
+            BTreeMap::new();
+        for index in indices.clone().filter(untyped) {
+            if is_seed(&formulas[index]) {
+                continue;
+            }
+            let signature = &signatures[index];
+            let rarest = match signature.iter().map(|s| occurrences[s]).min() {
+                Some(rarest) => rarest as f64,
+                None => continue,
+            };
+            for symbol in signature {
+                if occurrences[symbol] as f64 <= self.tolerance * rarest {
+                    triggers.entry(symbol).or_default().push(index);
+                }
+            }
+        }
+
+        let mut depths = vec![None; formulas.len()];
+        let mut reached: BTreeMap<&AtomicWord<'a>, usize> = BTreeMap::new();
+        let mut frontier = vec![];
+        for index in indices.clone().filter(untyped) {
+            if !is_seed(&formulas[index]) {
+                continue;
+            }
+            depths[index] = Some(0);
+            for symbol in &signatures[index] {
+                if reached.insert(symbol, 0).is_none() {
+                    frontier.push(symbol);
+                }
+            }
+        }
+        let mut depth = 0;
+        while !frontier.is_empty()
+            && !matches!(self.depth, Some(limit) if depth >= limit)
+        {
+            depth += 1;
+            let mut next = vec![];
+            for symbol in frontier {
+                for index in triggers.get(symbol).into_iter().flatten() {
+                    if depths[*index].is_some() {
+                        continue;
+                    }
+                    depths[*index] = Some(depth);
+                    for symbol in &signatures[*index] {
+                        if !reached.contains_key(symbol) {
+                            reached.insert(symbol, depth);
+                            next.push(symbol);
+                        }
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in indices.clone() {
+                let declared = match declarations[index] {
+                    Some(declared) if depths[index].is_none() => declared,
+                    _ => continue,
+                };
+                if let Some(depth) = reached.get(declared).copied() {
+                    depths[index] = Some(depth);
+                    for symbol in &signatures[index] {
+                        reached.entry(symbol).or_insert(depth);
+                    }
+                    changed = true;
+                }
+            }
+        }
+        depths
+    }
+
+    /// the selected formulas, in their original order
+    pub fn select<'f, 'a>(
+        &self,
+        formulas: &'f [AnnotatedFormula<'a>],
+    ) -> Vec<&'f AnnotatedFormula<'a>> {
+        formulas
+            .iter()
+            .zip(self.relevance(formulas))
+            .filter(|(_, depth)| depth.is_some())
+            .map(|(formula, _)| formula)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+    use alloc::string::{String, ToString};
+
+    fn names(formulas: Vec<&AnnotatedFormula>) -> Vec<String> {
+        formulas
+            .iter()
+            .map(|annotated| annotated.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_select() {
+        let formulas = problem(
+            b"
+            fof(a1, axiom, ![X]: (p(X) => q(X))).
+            fof(a2, axiom, ![X]: (q(X) => r(X))).
+            fof(a3, axiom, ![X]: (s(X) => t(X))).
+            fof(a4, axiom, p(c) | s(c)).
+            fof(goal, conjecture, r(c)).
+        ",
+        );
+        let sine = Sine::default();
+        assert_eq!(
+            sine.relevance(&formulas),
+            [Some(2), Some(1), None, Some(1), Some(0)]
+        );
+        assert_eq!(names(sine.select(&formulas)), ["a1", "a2", "a4", "goal"]);
+        let shallow = Sine::new(1.0, Some(1));
+        assert_eq!(names(shallow.select(&formulas)), ["a2", "a4", "goal"]);
+        let tolerant = Sine::new(2.0, None);
+        assert_eq!(tolerant.relevance(&formulas)[2], Some(2));
+    }
+
+    #[test]
+    fn test_types() {
+        let formulas = problem(
+            b"
+            tff(t_type, type, t: $tType).
+            tff(f_type, type, f: t > $o).
+            tff(h_type, type, h: t > $o).
+            tff(c_type, type, c: t).
+            tff(ax, axiom, ![X: t]: f(X)).
+            tff(other, axiom, ![X: t]: h(X)).
+            tff(goal, conjecture, f(c)).
+        ",
+        );
+        assert_eq!(
+            names(Sine::default().select(&formulas)),
+            ["t_type", "f_type", "c_type", "ax", "goal"]
+        );
+    }
+}
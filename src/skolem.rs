@@ -0,0 +1,319 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::fof;
+use crate::fresh::{Arena, Fresh};
+use crate::nnf::{fof_assoc, fof_unit, NegationNormalForm};
+use crate::scope::FreeVariables;
+use crate::subst::{application, apply_atom, apply_term};
+
+/// which universal variables a Skolem function takes as arguments
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Skolemisation {
+    /// every enclosing universal variable
+    Outer,
+    /// only those enclosing universal variables which the existential depends on
+    Inner,
+}
+
+impl Default for Skolemisation {
+    fn default() -> Self {
+        Skolemisation::Inner
+    }
+}
+
+/// variables in scope, mapped to their replacements
+#[derive(Default)]
+struct Scope<'a> {
+    bindings: Vec<(Variable<'a>, fof::Term<'a>)>,
+    universals: Vec<Variable<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn lookup(&self, variable: &Variable<'a>) -> Option<fof::Term<'a>> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == variable)
+            .map(|(_, term)| term.clone())
+    }
+}
+
+/// a Skolemiser for FOF formulas
+///
+/// Formulas are put into negation normal form, then existentially-quantified variables are replaced by terms `sK0(X, ...)` over universal variables.
+/// Universal quantifiers are left in place, renamed if they would capture a variable of a Skolem term.
+/// Fresh symbols are generated deterministically, so the same formulas in the same order produce the same Skolem functions.
+pub struct Skolemiser<'a> {
+    fresh: Fresh<'a>,
+    introduced: Vec<(Functor<'a>, usize)>,
+    /// the arguments of Skolem functions
+    pub mode: Skolemisation,
+    /// the prefix of Skolem functors, `sK` by default
+    pub prefix: &'a str,
+}
+
+impl<'a> Skolemiser<'a> {
+    /// a Skolemiser storing fresh symbols in `arena`
+    pub fn new(arena: &'a Arena) -> Self {
+        Self {
+            fresh: Fresh::new(arena),
+            introduced: vec![],
+            mode: Skolemisation::default(),
+            prefix: "sK",
+        }
+    }
+
+    /// the fresh symbol generator, for reserving symbols in the problem signature
+    pub fn fresh(&mut self) -> &mut Fresh<'a> {
+        &mut self.fresh
+    }
+
+    /// Skolem functors introduced so far, with their arities
+    pub fn introduced(&self) -> &[(Functor<'a>, usize)] {
+        &self.introduced
+    }
+
+    /// Skolemise a formula
+    ///
+    /// Symbols in `formula` are avoided, but symbols elsewhere in the problem must be reserved with `fresh`.
+    pub fn skolemise(
+        &mut self,
+        formula: &fof::Formula<'a>,
+    ) -> fof::Formula<'a> {
+        self.fresh.avoid_fof_formula(formula);
+        let nnf = formula.0.nnf();
+        fof::Formula(self.logic(&nnf, &mut Scope::default()))
+    }
+
+    fn logic(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+        scope: &mut Scope<'a>,
+    ) -> fof::LogicFormula<'a> {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, units) = match assoc {
+                    BinaryAssoc::And(and) => (AssocConnective::And, &and.0),
+                    BinaryAssoc::Or(or) => (AssocConnective::Or, &or.0),
+                };
+                let formulas =
+                    units.iter().map(|unit| self.unit(unit, scope)).collect();
+                fof_assoc(op, formulas)
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(_)) => {
+                unreachable!("not in negation normal form")
+            }
+            LogicFormula::Unary(unary) => {
+                LogicFormula::Unary(Self::unary(unary, scope))
+            }
+            LogicFormula::Unitary(unitary) => self.unitary(unitary, scope),
+        }
+    }
+
+    fn unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+        scope: &mut Scope<'a>,
+    ) -> fof::LogicFormula<'a> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.unitary(unitary, scope),
+            fof::UnitFormula::Unary(unary) => {
+                fof::LogicFormula::Unary(Self::unary(unary, scope))
+            }
+        }
+    }
+
+    fn unary(
+        formula: &fof::UnaryFormula<'a>,
+        scope: &Scope<'a>,
+    ) -> fof::UnaryFormula<'a> {
+        let lookup = |variable: &Variable<'a>| scope.lookup(variable);
+        match formula {
+            fof::UnaryFormula::Unary(connective, unit) => match &**unit {
+                fof::UnitFormula::Unitary(fof::UnitaryFormula::Atomic(
+                    atom,
+                )) => {
+                    let atom = Box::new(apply_atom(atom, &lookup));
+                    let unit = fof::UnitFormula::Unitary(
+                        fof::UnitaryFormula::Atomic(atom),
+                    );
+                    fof::UnaryFormula::Unary(*connective, Box::new(unit))
+                }
+                _ => unreachable!("not in negation normal form"),
+            },
+            fof::UnaryFormula::InfixUnary(infix) => {
+                fof::UnaryFormula::InfixUnary(fof::InfixUnary {
+                    left: Box::new(apply_term(&infix.left, &lookup)),
+                    op: infix.op,
+                    right: Box::new(apply_term(&infix.right, &lookup)),
+                })
+            }
+        }
+    }
+
+    fn unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+        scope: &mut Scope<'a>,
+    ) -> fof::LogicFormula<'a> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                self.quantified(quantified, scope)
+            }
+            fof::UnitaryFormula::Atomic(atom) => {
+                let lookup = |variable: &Variable<'a>| scope.lookup(variable);
+                let atom = Box::new(apply_atom(atom, &lookup));
+                fof::LogicFormula::Unitary(fof::UnitaryFormula::Atomic(atom))
+            }
+            fof::UnitaryFormula::Parenthesised(formula) => {
+                self.logic(formula, scope)
+            }
+        }
+    }
+
+    /// the universal variables in scope on which `quantified` depends
+    fn dependencies(
+        &self,
+        quantified: &fof::QuantifiedFormula<'a>,
+        scope: &Scope<'a>,
+    ) -> Vec<Variable<'a>> {
+        if self.mode == Skolemisation::Outer {
+            return scope.universals.clone();
+        }
+        let mut occurring = vec![];
+        for free in quantified.formula.free_variables() {
+            if quantified.bound.0.contains(&free) {
+                continue;
+            }
+            if let Some(term) = scope.lookup(&free) {
+                occurring.extend(term.free_variables());
+            }
+        }
+        scope
+            .universals
+            .iter()
+            .filter(|universal| occurring.contains(universal))
+            .cloned()
+            .collect()
+    }
+
+    fn quantified(
+        &mut self,
+        quantified: &fof::QuantifiedFormula<'a>,
+        scope: &mut Scope<'a>,
+    ) -> fof::LogicFormula<'a> {
+        let depth = scope.bindings.len();
+        let universals = scope.universals.len();
+        let result = match quantified.quantifier {
+            fof::Quantifier::Forall => {
+                let mut bound = vec![];
+                for variable in &quantified.bound.0 {
+                    let renamed = if scope.universals.contains(variable) {
+                        self.fresh.variable("X")
+                    } else {
+                        variable.clone()
+                    };
+                    let term = fof::Term::Variable(renamed.clone());
+                    scope.bindings.push((variable.clone(), term));
+                    scope.universals.push(renamed.clone());
+                    bound.push(renamed);
+                }
+                let formula = self.unit(&quantified.formula, scope);
+                fof::LogicFormula::Unitary(fof::UnitaryFormula::Quantified(
+                    fof::QuantifiedFormula {
+                        quantifier: fof::Quantifier::Forall,
+                        bound: fof::VariableList(bound),
+                        formula: Box::new(fof_unit(formula)),
+                    },
+                ))
+            }
+            fof::Quantifier::Exists => {
+                let arguments = self.dependencies(quantified, scope);
+                for variable in &quantified.bound.0 {
+                    let functor = self.fresh.functor(self.prefix);
+                    self.introduced.push((functor.clone(), arguments.len()));
+                    let term = application(functor, &arguments);
+                    let term = fof::Term::Function(Box::new(
+                        fof::FunctionTerm::Plain(term),
+                    ));
+                    scope.bindings.push((variable.clone(), term));
+                }
+                self.unit(&quantified.formula, scope)
+            }
+        };
+        scope.bindings.truncate(depth);
+        scope.universals.truncate(universals);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn skolemise(input: &[u8], mode: Skolemisation) -> String {
+        let arena = Arena::new();
+        let formula = check_parse(fof::Formula::parse, input);
+        let mut skolemiser = Skolemiser::new(&arena);
+        skolemiser.mode = mode;
+        skolemiser.skolemise(&formula).to_string()
+    }
+
+    #[test]
+    fn test_skolemise() {
+        let input = b"![X, Y]: (p(X) & ?[Z]: q(Z, Y))\0";
+        assert_eq!(
+            skolemise(input, Skolemisation::Outer),
+            "![X,Y]:(p(X)&q(sK0(X,Y),Y))"
+        );
+        assert_eq!(
+            skolemise(input, Skolemisation::Inner),
+            "![X,Y]:(p(X)&q(sK0(Y),Y))"
+        );
+        assert_eq!(
+            skolemise(b"![X]: ?[Y]: ?[Z]: r(X, Y, Z)\0", Skolemisation::Inner),
+            "![X]:r(X,sK0(X),sK1(X))"
+        );
+        assert_eq!(
+            skolemise(b"~![X]: p(X)\0", Skolemisation::Inner),
+            "~p(sK0)"
+        );
+    }
+
+    #[test]
+    fn test_capture() {
+        assert_eq!(
+            skolemise(
+                b"![Y]: ?[X]: (q(Y) & ![Y]: p(X, Y))\0",
+                Skolemisation::Inner
+            ),
+            "![Y]:(q(Y)&![X0]:p(sK0(Y),X0))"
+        );
+    }
+
+    #[test]
+    fn test_fresh_names() {
+        let arena = Arena::new();
+        let mut skolemiser = Skolemiser::new(&arena);
+        skolemiser.prefix = "esk";
+        skolemiser.fresh().avoid("esk0");
+        let formula = check_parse(fof::Formula::parse, b"?[X]: p(X, esk1)\0");
+        assert_eq!(skolemiser.skolemise(&formula).to_string(), "p(esk2,esk1)");
+        let introduced: Vec<_> = skolemiser
+            .introduced()
+            .iter()
+            .map(|(functor, arity)| (functor.to_string(), *arity))
+            .collect();
+        assert_eq!(introduced, [(String::from("esk2"), 0)]);
+    }
+}
@@ -0,0 +1,2748 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::fmt::Write;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::once;
+use core::mem;
+use core::str::FromStr;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+use crate::fresh::{Arena, Fresh};
+use crate::subst::{atom_view, function_view, Arguments, Head};
+use crate::tfx;
+use crate::top::*;
+use crate::utils::{unescape, word_text, Separated};
+
+/// an SMT-LIB s-expression
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Sexp {
+    Atom(String),
+    #[display(fmt = "({})", "Separated(' ', _0)")]
+    List(Vec<Sexp>),
+}
+
+fn atom(text: impl Into<String>) -> Sexp {
+    Sexp::Atom(text.into())
+}
+
+/// `head` applied to `arguments`, or just `head` if there are none
+fn app(head: &str, arguments: Vec<Sexp>) -> Sexp {
+    if arguments.is_empty() {
+        return atom(head);
+    }
+    Sexp::List(once(atom(head)).chain(arguments).collect())
+}
+
+/// an SMT-LIB script, printed one command per line and read by `str::parse`
+#[derive(Clone, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Script(pub Vec<Sexp>);
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for command in &self.0 {
+            writeln!(f, "{}", command)?;
+        }
+        Ok(())
+    }
+}
+
+/// why a formula cannot be written in SMT-LIB syntax
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ExportErrorKind {
+    #[display(fmt = "polymorphic type {}", _0)]
+    Polymorphic(String),
+    #[display(fmt = "conflicting types for {}", _0)]
+    Conflict(String),
+    #[display(fmt = "ill-typed arguments of {}", _0)]
+    IllTyped(String),
+    #[display(fmt = "unsupported symbol {}", _0)]
+    Unsupported(String),
+}
+
+type Translation<T> = core::result::Result<T, ExportErrorKind>;
+
+/// `prefix` followed by `text`, with characters other than ASCII letters, digits and `_` written as `%XX`
+///
+/// Each kind of name has its own prefix, so mangled names never collide with each other or with SMT-LIB symbols.
+fn mangle(prefix: &str, text: &str) -> String {
+    let mut mangled = String::from(prefix);
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            mangled.push(c);
+        } else {
+            write!(mangled, "%{:02X}", c as u32).expect("write to string");
+        }
+    }
+    mangled
+}
+
+fn defined_name<'t>(functor: &'t DefinedFunctor) -> &'t str {
+    ((functor.0).0).0 .0
+}
+
+/// a sign and the rest of a number
+fn signed(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+fn negated(negative: bool, sexp: Sexp) -> Sexp {
+    if negative {
+        app("-", vec![sexp])
+    } else {
+        sexp
+    }
+}
+
+/// an SMT-LIB decimal for an unsigned TPTP real, which may have an exponent
+fn decimal(text: &str) -> String {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(at) => (&text[..at], text[at + 1..].parse().unwrap_or(0)),
+        None => (text, 0),
+    };
+    let (integral, fractional) = match mantissa.find('.') {
+        Some(at) => (&mantissa[..at], &mantissa[at + 1..]),
+        None => (mantissa, ""),
+    };
+    let digits: String = once(integral).chain(once(fractional)).collect();
+    let point = integral.len() as i64 + exponent;
+    let (integral, fractional) = if point <= 0 {
+        let zeros = "0".repeat(-point as usize);
+        (String::new(), zeros + &digits)
+    } else if point as usize >= digits.len() {
+        let zeros = "0".repeat(point as usize - digits.len());
+        (digits + &zeros, String::new())
+    } else {
+        let (integral, fractional) = digits.split_at(point as usize);
+        (integral.to_string(), fractional.to_string())
+    };
+    let integral = integral.trim_start_matches('0');
+    let integral = if integral.is_empty() { "0" } else { integral };
+    let fractional = if fractional.is_empty() {
+        "0"
+    } else {
+        &fractional
+    };
+    format!("{}.{}", integral, fractional)
+}
+
+/// whether `sexp` is a numeric literal
+fn is_constant(sexp: &Sexp) -> bool {
+    match sexp {
+        Sexp::Atom(text) => text.starts_with(|c: char| c.is_ascii_digit()),
+        Sexp::List(list) => match list.split_first() {
+            Some((Sexp::Atom(op), arguments)) if op == "-" || op == "/" => {
+                arguments.iter().all(is_constant)
+            }
+            _ => false,
+        },
+    }
+}
+
+/// bind non-atomic `arguments` with `let`, so `body` can use them more than once
+fn shared(arguments: Vec<Sexp>, body: impl FnOnce(&[Sexp]) -> Sexp) -> Sexp {
+    let mut bindings = vec![];
+    let mut names = vec![];
+    for argument in arguments {
+        if let Sexp::Atom(_) = argument {
+            names.push(argument);
+            continue;
+        }
+        let name = atom(format!("$arg{}", bindings.len()));
+        bindings.push(Sexp::List(vec![name.clone(), argument]));
+        names.push(name);
+    }
+    let body = body(&names);
+    if bindings.is_empty() {
+        return body;
+    }
+    Sexp::List(vec![atom("let"), Sexp::List(bindings), body])
+}
+
+fn nonassoc(op: NonassocConnective, left: Sexp, right: Sexp) -> Sexp {
+    match op {
+        NonassocConnective::LRImplies => app("=>", vec![left, right]),
+        NonassocConnective::RLImplies => app("=>", vec![right, left]),
+        NonassocConnective::Equivalent => app("=", vec![left, right]),
+        NonassocConnective::NotEquivalent => app("xor", vec![left, right]),
+        NonassocConnective::NotOr => {
+            app("not", vec![app("or", vec![left, right])])
+        }
+        NonassocConnective::NotAnd => {
+            app("not", vec![app("and", vec![left, right])])
+        }
+    }
+}
+
+/// `op` applied to `operands`, which may have fewer than two elements
+fn associative(op: &str, unit: &str, mut operands: Vec<Sexp>) -> Sexp {
+    match operands.len() {
+        0 => atom(unit),
+        1 => operands.remove(0),
+        _ => app(op, operands),
+    }
+}
+
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+enum Sort {
+    Individual,
+    Bool,
+    Int,
+    Real,
+    Declared(String, Vec<Sort>),
+}
+
+impl Sort {
+    fn sexp(&self) -> Sexp {
+        match self {
+            Sort::Individual => atom("$i"),
+            Sort::Bool => atom("Bool"),
+            Sort::Int => atom("Int"),
+            Sort::Real => atom("Real"),
+            Sort::Declared(name, arguments) => {
+                app(name, arguments.iter().map(Sort::sexp).collect())
+            }
+        }
+    }
+
+    fn is_numeric(&self) -> bool {
+        matches!(self, Sort::Int | Sort::Real)
+    }
+}
+
+type Typed = (Sexp, Sort);
+
+fn is_kind(typ: &tfx::AtomicType) -> bool {
+    matches!(
+        typ,
+        tfx::AtomicType::Defined(defined) if ((defined.0).0).0 .0 == "tType"
+    )
+}
+
+fn flatten<'t, 'a>(
+    typ: &'t tfx::UnitaryType<'a>,
+    types: &mut Vec<&'t tfx::AtomicType<'a>>,
+) {
+    match typ {
+        tfx::UnitaryType::Atomic(atomic) => types.push(atomic),
+        tfx::UnitaryType::Product(product) => {
+            for typ in &product.0 {
+                flatten(typ, types);
+            }
+        }
+    }
+}
+
+/// the argument and result types of a declaration
+fn non_atomic<'t, 'a>(
+    typ: &'t tfx::NonAtomicType<'a>,
+) -> Translation<(Vec<&'t tfx::AtomicType<'a>>, &'t tfx::AtomicType<'a>)> {
+    match typ {
+        tfx::NonAtomicType::Mapping(mapping) => {
+            let mut domain = vec![];
+            flatten(&mapping.domain, &mut domain);
+            Ok((domain, &mapping.range))
+        }
+        tfx::NonAtomicType::Quantified(_) => {
+            Err(ExportErrorKind::Polymorphic(typ.to_string()))
+        }
+        tfx::NonAtomicType::Parenthesised(typ) => non_atomic(typ),
+    }
+}
+
+#[derive(Default)]
+struct Translator<'a> {
+    sorts: Vec<(String, usize)>,
+    sort_arities: BTreeMap<String, usize>,
+    functions: Vec<(String, Vec<Sort>, Sort)>,
+    function_indices: BTreeMap<String, usize>,
+    distinct: Vec<String>,
+    bound: Vec<(&'a str, Sort)>,
+    free: Vec<&'a str>,
+    individuals: bool,
+    quantifiers: bool,
+    integers: bool,
+    reals: bool,
+    nonlinear: bool,
+}
+
+impl<'a> Translator<'a> {
+    fn individual(&mut self) -> Sort {
+        self.individuals = true;
+        Sort::Individual
+    }
+
+    fn declare_sort(
+        &mut self,
+        text: String,
+        name: String,
+        arity: usize,
+    ) -> Translation<()> {
+        match self.sort_arities.get(&name) {
+            Some(declared) if *declared == arity => Ok(()),
+            Some(_) => Err(ExportErrorKind::Conflict(text)),
+            None => {
+                self.sort_arities.insert(name.clone(), arity);
+                self.sorts.push((name, arity));
+                Ok(())
+            }
+        }
+    }
+
+    fn declare_function(
+        &mut self,
+        text: String,
+        name: String,
+        domain: Vec<Sort>,
+        range: Sort,
+    ) -> Translation<usize> {
+        if let Some(index) = self.function_indices.get(&name) {
+            let (_, declared_domain, declared_range) = &self.functions[*index];
+            if *declared_domain != domain || *declared_range != range {
+                return Err(ExportErrorKind::Conflict(text));
+            }
+            return Ok(*index);
+        }
+        let index = self.functions.len();
+        self.function_indices.insert(name.clone(), index);
+        self.functions.push((name, domain, range));
+        Ok(index)
+    }
+
+    fn sort(&mut self, typ: &tfx::AtomicType<'a>) -> Translation<Sort> {
+        let (functor, arguments) = match typ {
+            tfx::AtomicType::Constant(constant) => (&constant.0, vec![]),
+            tfx::AtomicType::Function(functor, arguments) => {
+                let arguments = arguments
+                    .0
+                    .iter()
+                    .map(|typ| self.sort(typ))
+                    .collect::<Translation<_>>()?;
+                (functor, arguments)
+            }
+            tfx::AtomicType::Defined(defined) => {
+                return match ((defined.0).0).0 .0 {
+                    "i" => Ok(self.individual()),
+                    "o" => Ok(Sort::Bool),
+                    "int" => {
+                        self.integers = true;
+                        Ok(Sort::Int)
+                    }
+                    "rat" | "real" => {
+                        self.reals = true;
+                        Ok(Sort::Real)
+                    }
+                    _ => {
+                        Err(ExportErrorKind::Unsupported(defined.to_string()))
+                    }
+                };
+            }
+            tfx::AtomicType::Variable(variable) => {
+                return Err(ExportErrorKind::Polymorphic(variable.to_string()))
+            }
+        };
+        let name = mangle("s_", &word_text(&functor.0));
+        self.declare_sort(functor.to_string(), name.clone(), arguments.len())?;
+        Ok(Sort::Declared(name, arguments))
+    }
+
+    /// add the declaration in an atom typing
+    fn declare(&mut self, typing: &tfx::AtomTyping<'a>) -> Translation<()> {
+        let (atom, typ) = match typing {
+            tfx::AtomTyping::Typing(atom, typ) => (atom, typ),
+            tfx::AtomTyping::Parenthesised(typing) => {
+                return self.declare(typing)
+            }
+        };
+        let (text, symbol) = match atom {
+            UntypedAtom::Constant(constant) => {
+                (constant.to_string(), word_text(&(constant.0).0))
+            }
+            UntypedAtom::System(system) => {
+                (system.to_string(), system.to_string())
+            }
+        };
+        let (domain, range) = match typ {
+            tfx::TopLevelType::Atomic(atomic) => (vec![], &**atomic),
+            tfx::TopLevelType::NonAtomic(non_atomic_type) => {
+                non_atomic(non_atomic_type)?
+            }
+        };
+        if is_kind(range) {
+            if !domain.iter().all(|typ| is_kind(typ)) {
+                return Err(ExportErrorKind::Polymorphic(typ.to_string()));
+            }
+            let name = mangle("s_", &symbol);
+            return self.declare_sort(text, name, domain.len());
+        }
+        let domain = domain
+            .into_iter()
+            .map(|typ| self.sort(typ))
+            .collect::<Translation<_>>()?;
+        let range = self.sort(range)?;
+        let name = mangle("f_", &symbol);
+        self.declare_function(text, name, domain, range)?;
+        Ok(())
+    }
+
+    /// apply an uninterpreted symbol, declaring it with TPTP's default types if necessary
+    fn apply(
+        &mut self,
+        text: String,
+        name: String,
+        arguments: Vec<Typed>,
+        predicate: bool,
+    ) -> Translation<Typed> {
+        let index = match self.function_indices.get(&name) {
+            Some(index) => *index,
+            None => {
+                let domain = arguments.iter().map(|_| self.individual());
+                let domain = domain.collect();
+                let range = if predicate {
+                    Sort::Bool
+                } else {
+                    self.individual()
+                };
+                self.declare_function(
+                    text.clone(),
+                    name.clone(),
+                    domain,
+                    range,
+                )?
+            }
+        };
+        let (_, domain, range) = &self.functions[index];
+        if domain.len() != arguments.len()
+            || domain
+                .iter()
+                .zip(&arguments)
+                .any(|(sort, (_, given))| sort != given)
+        {
+            return Err(ExportErrorKind::IllTyped(text));
+        }
+        let arguments = arguments.into_iter().map(|(sexp, _)| sexp).collect();
+        Ok((app(&name, arguments), range.clone()))
+    }
+
+    fn equality(&mut self, arguments: Vec<Typed>) -> Translation<Sexp> {
+        let sorts_agree =
+            arguments.windows(2).all(|pair| pair[0].1 == pair[1].1);
+        if arguments.len() != 2 || !sorts_agree {
+            return Err(ExportErrorKind::IllTyped("=".to_string()));
+        }
+        let arguments = arguments.into_iter().map(|(sexp, _)| sexp).collect();
+        Ok(app("=", arguments))
+    }
+
+    fn boolean(
+        &self,
+        (sexp, sort): Typed,
+        text: impl FnOnce() -> String,
+    ) -> Translation<Sexp> {
+        if sort == Sort::Bool {
+            Ok(sexp)
+        } else {
+            Err(ExportErrorKind::IllTyped(text()))
+        }
+    }
+
+    fn number(&mut self, number: &Number<'a>) -> Typed {
+        match number {
+            Number::Integer(integer) => {
+                self.integers = true;
+                let (negative, digits) = signed(integer.0);
+                (negated(negative, atom(digits)), Sort::Int)
+            }
+            Number::Rational(rational) => {
+                self.reals = true;
+                let (negative, digits) = signed(rational.0);
+                let (numerator, denominator) =
+                    digits.split_at(digits.find('/').unwrap_or(0));
+                let quotient = app(
+                    "/",
+                    vec![
+                        atom(decimal(numerator)),
+                        atom(decimal(&denominator[1..])),
+                    ],
+                );
+                (negated(negative, quotient), Sort::Real)
+            }
+            Number::Real(real) => {
+                self.reals = true;
+                let (negative, digits) = signed(real.0);
+                (negated(negative, atom(decimal(digits))), Sort::Real)
+            }
+        }
+    }
+
+    fn defined_term(&mut self, term: &DefinedTerm<'a>) -> Typed {
+        match term {
+            DefinedTerm::Number(number) => self.number(number),
+            DefinedTerm::Distinct(distinct) => {
+                let name = mangle("d_", &unescape(distinct.0));
+                if !self.distinct.contains(&name) {
+                    self.distinct.push(name.clone());
+                }
+                (atom(name), self.individual())
+            }
+        }
+    }
+
+    /// integer division and remainder in the style of `$quotient_x` and `$remainder_x`
+    fn division(name: &str, integral: bool, arguments: Vec<Sexp>) -> Sexp {
+        let zero = atom(if integral { "0" } else { "0.0" });
+        shared(arguments, |arguments| {
+            let (x, y) = (arguments[0].clone(), arguments[1].clone());
+            let minus = |sexp: Sexp| app("-", vec![sexp]);
+            if integral {
+                let (op, rounding) = name.split_at(name.find('_').unwrap());
+                let op = if op == "$quotient" { "div" } else { "mod" };
+                let apply = |x, y| app(op, vec![x, y]);
+                return match rounding {
+                    "_e" => apply(x, y),
+                    "_t" => app(
+                        "ite",
+                        vec![
+                            app(">=", vec![x.clone(), zero]),
+                            apply(x.clone(), y.clone()),
+                            minus(apply(minus(x), y)),
+                        ],
+                    ),
+                    _ => {
+                        let negative =
+                            apply(minus(x.clone()), minus(y.clone()));
+                        let negative = if op == "div" {
+                            negative
+                        } else {
+                            minus(negative)
+                        };
+                        app(
+                            "ite",
+                            vec![
+                                app(">", vec![y.clone(), zero]),
+                                apply(x, y),
+                                negative,
+                            ],
+                        )
+                    }
+                };
+            }
+            let floor = |sexp| app("to_real", vec![app("to_int", vec![sexp])]);
+            let quotient = app("/", vec![x.clone(), y.clone()]);
+            let rounded = |condition| {
+                app(
+                    "ite",
+                    vec![
+                        condition,
+                        floor(quotient.clone()),
+                        minus(floor(minus(quotient.clone()))),
+                    ],
+                )
+            };
+            let rounded = match &name[name.len() - 2..] {
+                "_e" => rounded(app(">", vec![y.clone(), zero])),
+                "_t" => rounded(app(">=", vec![quotient.clone(), zero])),
+                _ => floor(quotient.clone()),
+            };
+            if name.starts_with("$quotient") {
+                rounded
+            } else {
+                app("-", vec![x, app("*", vec![y, rounded])])
+            }
+        })
+    }
+
+    /// a defined symbol, such as `$true` or `$sum`
+    fn defined(
+        &mut self,
+        name: &str,
+        arguments: Vec<Typed>,
+    ) -> Translation<Typed> {
+        let text = format!("${}", name);
+        match (name, arguments.len()) {
+            ("true", 0) => return Ok((atom("true"), Sort::Bool)),
+            ("false", 0) => return Ok((atom("false"), Sort::Bool)),
+            ("distinct", _) => {
+                if arguments.windows(2).any(|pair| pair[0].1 != pair[1].1) {
+                    return Err(ExportErrorKind::IllTyped(text));
+                }
+                if arguments.len() < 2 {
+                    return Ok((atom("true"), Sort::Bool));
+                }
+                let arguments = arguments.into_iter().map(|(sexp, _)| sexp);
+                return Ok((app("distinct", arguments.collect()), Sort::Bool));
+            }
+            ("ite", 3) => {
+                if arguments[0].1 != Sort::Bool
+                    || arguments[1].1 != arguments[2].1
+                {
+                    return Err(ExportErrorKind::IllTyped(text));
+                }
+                let sort = arguments[1].1.clone();
+                let arguments = arguments.into_iter().map(|(sexp, _)| sexp);
+                return Ok((app("ite", arguments.collect()), sort));
+            }
+            _ => {}
+        }
+        let sort = match arguments.first() {
+            Some((_, sort)) if sort.is_numeric() => sort.clone(),
+            Some(_) => return Err(ExportErrorKind::IllTyped(text)),
+            None => return Err(ExportErrorKind::Unsupported(text)),
+        };
+        if arguments.iter().any(|(_, other)| *other != sort) {
+            return Err(ExportErrorKind::IllTyped(text));
+        }
+        let integral = sort == Sort::Int;
+        let mut arguments: Vec<_> =
+            arguments.into_iter().map(|(sexp, _)| sexp).collect();
+        let divisor = arguments.len() < 2 || is_constant(&arguments[1]);
+        let to_real = |sexp| app("to_real", vec![sexp]);
+        let floor = |sexp| to_real(app("to_int", vec![sexp]));
+        let minus = |sexp| app("-", vec![sexp]);
+        let comparison = |op| Ok((app(op, arguments.clone()), Sort::Bool));
+        match (name, arguments.len()) {
+            ("less", 2) => return comparison("<"),
+            ("lesseq", 2) => return comparison("<="),
+            ("greater", 2) => return comparison(">"),
+            ("greatereq", 2) => return comparison(">="),
+            ("is_int", 1) if integral => {
+                return Ok((atom("true"), Sort::Bool))
+            }
+            ("is_int", 1) => {
+                return Ok((app("is_int", arguments), Sort::Bool))
+            }
+            ("is_rat", 1) => return Ok((atom("true"), Sort::Bool)),
+            ("to_int", 1) if integral => {
+                return Ok((arguments.remove(0), Sort::Int))
+            }
+            ("to_int", 1) => return Ok((app("to_int", arguments), Sort::Int)),
+            ("to_rat" | "to_real", 1) if integral => {
+                self.reals = true;
+                return Ok((to_real(arguments.remove(0)), Sort::Real));
+            }
+            ("to_rat" | "to_real", 1) => {
+                return Ok((arguments.remove(0), Sort::Real))
+            }
+            _ => {}
+        }
+        let sexp = match (name, arguments.len()) {
+            ("uminus", 1) => app("-", arguments),
+            ("sum", 2) => app("+", arguments),
+            ("difference", 2) => app("-", arguments),
+            ("product", 2) => {
+                if !arguments.iter().any(is_constant) {
+                    self.nonlinear = true;
+                }
+                app("*", arguments)
+            }
+            ("quotient", 2) if !integral => {
+                self.nonlinear |= !divisor;
+                app("/", arguments)
+            }
+            (
+                "quotient_e" | "quotient_t" | "quotient_f" | "remainder_e"
+                | "remainder_t" | "remainder_f",
+                2,
+            ) => {
+                self.nonlinear |= !divisor;
+                Self::division(&text, integral, arguments)
+            }
+            ("floor" | "ceiling" | "truncate" | "round", 1) if integral => {
+                arguments.remove(0)
+            }
+            ("floor", 1) => floor(arguments.remove(0)),
+            ("ceiling", 1) => minus(floor(minus(arguments.remove(0)))),
+            ("truncate" | "round", 1) => {
+                let half = |x: &Sexp, op| {
+                    if name == "round" {
+                        app(op, vec![x.clone(), atom("0.5")])
+                    } else {
+                        x.clone()
+                    }
+                };
+                shared(arguments, |arguments| {
+                    let x = &arguments[0];
+                    app(
+                        "ite",
+                        vec![
+                            app(">=", vec![x.clone(), atom("0.0")]),
+                            floor(half(x, "+")),
+                            minus(floor(half(&minus(x.clone()), "+"))),
+                        ],
+                    )
+                })
+            }
+            _ => return Err(ExportErrorKind::Unsupported(text)),
+        };
+        Ok((sexp, sort))
+    }
+
+    fn variable(&mut self, variable: &Variable<'a>) -> Typed {
+        let name = (variable.0).0;
+        let bound = self.bound.iter().rev().find(|(bound, _)| *bound == name);
+        let sort = match bound {
+            Some((_, sort)) => sort.clone(),
+            None => {
+                if !self.free.contains(&name) {
+                    self.free.push(name);
+                }
+                self.individual()
+            }
+        };
+        (atom(mangle("v_", name)), sort)
+    }
+
+    fn quantify(
+        &mut self,
+        quantifier: fof::Quantifier,
+        variables: Vec<(&'a str, Sort)>,
+        body: impl FnOnce(&mut Self) -> Translation<Sexp>,
+    ) -> Translation<Sexp> {
+        self.quantifiers = true;
+        let declarations = variables
+            .iter()
+            .map(|(name, sort)| {
+                Sexp::List(vec![atom(mangle("v_", name)), sort.sexp()])
+            })
+            .collect();
+        let depth = self.bound.len();
+        self.bound.extend(variables);
+        let body = body(self);
+        self.bound.truncate(depth);
+        let quantifier = match quantifier {
+            fof::Quantifier::Forall => "forall",
+            fof::Quantifier::Exists => "exists",
+        };
+        Ok(Sexp::List(vec![
+            atom(quantifier),
+            Sexp::List(declarations),
+            body?,
+        ]))
+    }
+
+    /// universally quantify the free variables of a formula
+    fn close(&mut self, formula: Sexp) -> Sexp {
+        let free = mem::take(&mut self.free);
+        if free.is_empty() {
+            return formula;
+        }
+        self.quantifiers = true;
+        let declarations = free
+            .iter()
+            .map(|name| Sexp::List(vec![atom(mangle("v_", name)), atom("$i")]))
+            .collect();
+        Sexp::List(vec![atom("forall"), Sexp::List(declarations), formula])
+    }
+
+    fn fof_term(&mut self, term: &fof::Term<'a>) -> Translation<Typed> {
+        match term {
+            fof::Term::Variable(variable) => Ok(self.variable(variable)),
+            fof::Term::Function(function) => {
+                let (head, arguments) = function_view(function);
+                self.fof_application(head, arguments, false)
+            }
+        }
+    }
+
+    fn fof_application(
+        &mut self,
+        head: Head<'_, 'a>,
+        arguments: Arguments<'_, 'a>,
+        predicate: bool,
+    ) -> Translation<Typed> {
+        let arguments = arguments
+            .iter()
+            .map(|term| self.fof_term(term))
+            .collect::<Translation<_>>()?;
+        match head {
+            Head::Plain(functor) => {
+                let name = mangle("f_", &word_text(&functor.0));
+                self.apply(functor.to_string(), name, arguments, predicate)
+            }
+            Head::System(functor) => {
+                let text = functor.to_string();
+                let name = mangle("f_", &text);
+                self.apply(text, name, arguments, predicate)
+            }
+            Head::Defined(functor) => {
+                self.defined(defined_name(functor), arguments)
+            }
+            Head::Value(value) => Ok(self.defined_term(value)),
+            Head::Equality => Ok((self.equality(arguments)?, Sort::Bool)),
+        }
+    }
+
+    fn fof_atomic(
+        &mut self,
+        atom: &fof::AtomicFormula<'a>,
+    ) -> Translation<Sexp> {
+        let (head, arguments) = atom_view(atom);
+        let typed = self.fof_application(head, arguments, true)?;
+        self.boolean(typed, || atom.to_string())
+    }
+
+    fn fof_equality(
+        &mut self,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+    ) -> Translation<Sexp> {
+        let arguments = vec![self.fof_term(left)?, self.fof_term(right)?];
+        self.equality(arguments)
+    }
+
+    fn fof_unit(
+        &mut self,
+        formula: &fof::UnitFormula<'a>,
+    ) -> Translation<Sexp> {
+        match formula {
+            fof::UnitFormula::Unitary(unitary) => self.fof_unitary(unitary),
+            fof::UnitFormula::Unary(unary) => self.fof_unary(unary),
+        }
+    }
+
+    fn fof_unary(
+        &mut self,
+        formula: &fof::UnaryFormula<'a>,
+    ) -> Translation<Sexp> {
+        let negated = match formula {
+            fof::UnaryFormula::Unary(_, unit) => self.fof_unit(unit)?,
+            fof::UnaryFormula::InfixUnary(infix) => {
+                self.fof_equality(&infix.left, &infix.right)?
+            }
+        };
+        Ok(app("not", vec![negated]))
+    }
+
+    fn fof_unitary(
+        &mut self,
+        formula: &fof::UnitaryFormula<'a>,
+    ) -> Translation<Sexp> {
+        match formula {
+            fof::UnitaryFormula::Quantified(quantified) => {
+                let variables = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| ((variable.0).0, self.individual()))
+                    .collect();
+                self.quantify(quantified.quantifier, variables, |this| {
+                    this.fof_unit(&quantified.formula)
+                })
+            }
+            fof::UnitaryFormula::Atomic(atom) => self.fof_atomic(atom),
+            fof::UnitaryFormula::Parenthesised(logic) => self.fof_logic(logic),
+        }
+    }
+
+    fn fof_logic(
+        &mut self,
+        formula: &fof::LogicFormula<'a>,
+    ) -> Translation<Sexp> {
+        use fof::{BinaryAssoc, BinaryFormula, LogicFormula};
+        match formula {
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, unit, units) = match assoc {
+                    BinaryAssoc::Or(or) => ("or", "false", &or.0),
+                    BinaryAssoc::And(and) => ("and", "true", &and.0),
+                };
+                let units = units
+                    .iter()
+                    .map(|unit| self.fof_unit(unit))
+                    .collect::<Translation<_>>()?;
+                Ok(associative(op, unit, units))
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.fof_unit(&binary.left)?;
+                let right = self.fof_unit(&binary.right)?;
+                Ok(nonassoc(binary.op, left, right))
+            }
+            LogicFormula::Unary(unary) => self.fof_unary(unary),
+            LogicFormula::Unitary(unitary) => self.fof_unitary(unitary),
+        }
+    }
+
+    fn literal(&mut self, literal: &cnf::Literal<'a>) -> Translation<Sexp> {
+        match literal {
+            cnf::Literal::Atomic(atom) => self.fof_atomic(atom),
+            cnf::Literal::NegatedAtomic(atom) => {
+                Ok(app("not", vec![self.fof_atomic(atom)?]))
+            }
+            cnf::Literal::Infix(infix) => {
+                let equality = self.fof_equality(&infix.left, &infix.right)?;
+                Ok(app("not", vec![equality]))
+            }
+        }
+    }
+
+    fn cnf(&mut self, formula: &cnf::Formula<'a>) -> Translation<Sexp> {
+        let disjunction = match formula {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => disjunction,
+        };
+        let literals = disjunction
+            .0
+            .iter()
+            .map(|literal| self.literal(literal))
+            .collect::<Translation<_>>()?;
+        Ok(associative("or", "false", literals))
+    }
+
+    fn tfx_arguments(
+        &mut self,
+        arguments: &tfx::Arguments<'a>,
+    ) -> Translation<Vec<Typed>> {
+        arguments.0.iter().map(|term| self.tfx_term(term)).collect()
+    }
+
+    fn tfx_atomic(
+        &mut self,
+        atom: &tfx::AtomicFormula<'a>,
+        predicate: bool,
+    ) -> Translation<Typed> {
+        use tfx::{AtomicFormula, DefinedPlain, PlainAtomic, SystemAtomic};
+        let (text, symbol, arguments) = match atom {
+            AtomicFormula::Plain(PlainAtomic::Constant(constant)) => {
+                let symbol = word_text(&(constant.0).0);
+                (constant.to_string(), symbol, vec![])
+            }
+            AtomicFormula::Plain(PlainAtomic::Function(
+                functor,
+                arguments,
+            )) => {
+                let arguments = self.tfx_arguments(arguments)?;
+                (functor.to_string(), word_text(&functor.0), arguments)
+            }
+            AtomicFormula::System(SystemAtomic::Constant(constant)) => {
+                let text = constant.to_string();
+                (text.clone(), text, vec![])
+            }
+            AtomicFormula::System(SystemAtomic::Function(
+                functor,
+                arguments,
+            )) => {
+                let text = functor.to_string();
+                (text.clone(), text, self.tfx_arguments(arguments)?)
+            }
+            AtomicFormula::Defined(defined) => {
+                return match &defined.0 {
+                    DefinedPlain::Constant(constant) => {
+                        self.defined(defined_name(&constant.0), vec![])
+                    }
+                    DefinedPlain::Function(functor, arguments) => {
+                        let arguments = self.tfx_arguments(arguments)?;
+                        self.defined(defined_name(functor), arguments)
+                    }
+                };
+            }
+        };
+        let name = mangle("f_", &symbol);
+        self.apply(text, name, arguments, predicate)
+    }
+
+    fn tfx_term(&mut self, term: &tfx::Term<'a>) -> Translation<Typed> {
+        match term {
+            tfx::Term::Logic(logic) => self.tfx_typed(logic, false),
+            tfx::Term::Defined(defined) => Ok(self.defined_term(defined)),
+        }
+    }
+
+    fn tfx_unitary_term(
+        &mut self,
+        term: &tfx::UnitaryTerm<'a>,
+    ) -> Translation<Typed> {
+        match term {
+            tfx::UnitaryTerm::Atomic(atom) => self.tfx_atomic(atom, false),
+            tfx::UnitaryTerm::Defined(defined) => {
+                Ok(self.defined_term(defined))
+            }
+            tfx::UnitaryTerm::Variable(variable) => {
+                Ok(self.variable(variable))
+            }
+            tfx::UnitaryTerm::Logic(logic) => self.tfx_typed(logic, false),
+        }
+    }
+
+    fn tfx_equality(
+        &mut self,
+        left: &tfx::UnitaryTerm<'a>,
+        right: &tfx::UnitaryTerm<'a>,
+    ) -> Translation<Sexp> {
+        let arguments =
+            vec![self.tfx_unitary_term(left)?, self.tfx_unitary_term(right)?];
+        self.equality(arguments)
+    }
+
+    fn tfx_prefix(
+        &mut self,
+        prefix: &tfx::PrefixUnary<'a>,
+    ) -> Translation<Sexp> {
+        let negated = match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => {
+                let typed = self.tfx_unitary(unitary, true)?;
+                self.boolean(typed, || unitary.to_string())?
+            }
+            tfx::PreunitFormula::Prefix(prefix) => self.tfx_prefix(prefix)?,
+        };
+        Ok(app("not", vec![negated]))
+    }
+
+    fn tfx_unary(
+        &mut self,
+        formula: &tfx::UnaryFormula<'a>,
+    ) -> Translation<Sexp> {
+        match formula {
+            tfx::UnaryFormula::Prefix(prefix) => self.tfx_prefix(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                let equality = self.tfx_equality(&infix.left, &infix.right)?;
+                Ok(app("not", vec![equality]))
+            }
+        }
+    }
+
+    fn tfx_unitary(
+        &mut self,
+        formula: &tfx::UnitaryFormula<'a>,
+        predicate: bool,
+    ) -> Translation<Typed> {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let variables = quantified
+                    .bound
+                    .0
+                    .iter()
+                    .map(|variable| match variable {
+                        tfx::Variable::Typed(typed) => {
+                            Ok(((typed.variable.0).0, self.sort(&typed.typ)?))
+                        }
+                        tfx::Variable::Untyped(variable) => {
+                            Ok(((variable.0).0, self.individual()))
+                        }
+                    })
+                    .collect::<Translation<_>>()?;
+                let sexp =
+                    self.quantify(quantified.quantifier, variables, |this| {
+                        this.tfx_unit(&quantified.formula)
+                    })?;
+                Ok((sexp, Sort::Bool))
+            }
+            tfx::UnitaryFormula::Atomic(atom) => {
+                self.tfx_atomic(atom, predicate)
+            }
+            tfx::UnitaryFormula::Variable(variable) => {
+                Ok(self.variable(variable))
+            }
+            tfx::UnitaryFormula::Logic(logic) => {
+                self.tfx_typed(logic, predicate)
+            }
+        }
+    }
+
+    fn tfx_unit(
+        &mut self,
+        formula: &tfx::UnitFormula<'a>,
+    ) -> Translation<Sexp> {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => {
+                let typed = self.tfx_unitary(unitary, true)?;
+                self.boolean(typed, || unitary.to_string())
+            }
+            tfx::UnitFormula::Unary(unary) => self.tfx_unary(unary),
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.tfx_equality(&infix.left, &infix.right)
+            }
+        }
+    }
+
+    /// a formula which may be a term, defaulting undeclared atoms to predicates if `predicate`
+    fn tfx_typed(
+        &mut self,
+        formula: &tfx::LogicFormula<'a>,
+        predicate: bool,
+    ) -> Translation<Typed> {
+        use tfx::{BinaryAssoc, BinaryFormula, LogicFormula};
+        let sexp = match formula {
+            LogicFormula::Unitary(unitary) => {
+                return self.tfx_unitary(unitary, predicate)
+            }
+            LogicFormula::Unary(unary) => self.tfx_unary(unary)?,
+            LogicFormula::Binary(BinaryFormula::Assoc(assoc)) => {
+                let (op, unit, units) = match assoc {
+                    BinaryAssoc::Or(or) => ("or", "false", &or.0),
+                    BinaryAssoc::And(and) => ("and", "true", &and.0),
+                };
+                let units = units
+                    .iter()
+                    .map(|unit| self.tfx_unit(unit))
+                    .collect::<Translation<_>>()?;
+                associative(op, unit, units)
+            }
+            LogicFormula::Binary(BinaryFormula::Nonassoc(binary)) => {
+                let left = self.tfx_unit(&binary.left)?;
+                let right = self.tfx_unit(&binary.right)?;
+                nonassoc(binary.op, left, right)
+            }
+            LogicFormula::DefinedInfix(infix) => {
+                self.tfx_equality(&infix.left, &infix.right)?
+            }
+        };
+        Ok((sexp, Sort::Bool))
+    }
+
+    fn tfx_logic(
+        &mut self,
+        formula: &tfx::LogicFormula<'a>,
+    ) -> Translation<Sexp> {
+        let typed = self.tfx_typed(formula, true)?;
+        self.boolean(typed, || formula.to_string())
+    }
+
+    fn formula(
+        &mut self,
+        annotated: &AnnotatedFormula<'a>,
+    ) -> Translation<Option<Sexp>> {
+        let formula = match annotated {
+            AnnotatedFormula::Tfx(tfx) => match &*tfx.0.formula {
+                tfx::Formula::Logic(logic) => self.tfx_logic(logic),
+                tfx::Formula::AtomTyping(_) => return Ok(None),
+            },
+            AnnotatedFormula::Fof(fof) => self.fof_logic(&fof.0.formula.0),
+            AnnotatedFormula::Cnf(cnf) => self.cnf(&cnf.0.formula),
+        };
+        let formula = formula.map(|formula| self.close(formula));
+        self.bound.clear();
+        self.free.clear();
+        formula.map(Some)
+    }
+
+    fn logic(&self) -> String {
+        let mut logic = String::new();
+        if !self.quantifiers {
+            logic.push_str("QF_");
+        }
+        logic.push_str("UF");
+        if self.integers || self.reals {
+            logic.push(if self.nonlinear { 'N' } else { 'L' });
+            if self.integers {
+                logic.push('I');
+            }
+            if self.reals {
+                logic.push('R');
+            }
+            logic.push('A');
+        }
+        logic
+    }
+
+    fn finish(self, assertions: Vec<Sexp>, conjectures: Vec<Sexp>) -> Script {
+        let command = |name: &str, arguments: Vec<Sexp>| {
+            Sexp::List(once(atom(name)).chain(arguments).collect())
+        };
+        let mut commands =
+            vec![command("set-logic", vec![atom(self.logic())])];
+        if self.individuals {
+            commands
+                .push(command("declare-sort", vec![atom("$i"), atom("0")]));
+        }
+        for (name, arity) in self.sorts {
+            let arity = atom(arity.to_string());
+            commands.push(command("declare-sort", vec![atom(name), arity]));
+        }
+        for (name, domain, range) in self.functions {
+            let domain = Sexp::List(domain.iter().map(Sort::sexp).collect());
+            let declaration = vec![atom(name), domain, range.sexp()];
+            commands.push(command("declare-fun", declaration));
+        }
+        for name in &self.distinct {
+            let declaration = vec![atom(name), Sexp::List(vec![]), atom("$i")];
+            commands.push(command("declare-fun", declaration));
+        }
+        if self.distinct.len() > 1 {
+            let distinct = self.distinct.into_iter().map(atom).collect();
+            commands.push(command("assert", vec![app("distinct", distinct)]));
+        }
+        for assertion in assertions {
+            commands.push(command("assert", vec![assertion]));
+        }
+        if !conjectures.is_empty() {
+            let conjecture = associative("and", "true", conjectures);
+            let negated = app("not", vec![conjecture]);
+            commands.push(command("assert", vec![negated]));
+        }
+        commands.push(command("check-sat", vec![]));
+        Script(commands)
+    }
+}
+
+/// export a problem to SMT-LIB v2
+///
+/// TFX type declarations become `declare-sort` and `declare-fun`, and other symbols are declared with TPTP's default types, using a sort `$i` for individuals.
+/// Conjectures are conjoined and asserted negated, and other formulas are asserted, so the problem is a theorem iff the script is unsatisfiable.
+/// Free variables are universally quantified, and distinct objects become pairwise-distinct constants of sort `$i`.
+/// Arithmetic uses the SMT-LIB `Ints` and `Reals` theories, with `$rat` as `Real`, and `$ite` becomes `ite`.
+/// Symbols are mangled with a prefix for each kind of name: `f_` for functions and predicates, `s_` for sorts, `v_` for variables and `d_` for distinct objects.
+pub fn export<'a>(
+    formulas: &[AnnotatedFormula<'a>],
+) -> Result<Script, ExportError<'a, ExportErrorKind>> {
+    let error = |annotated: &AnnotatedFormula<'a>| {
+        let name = annotated.name().clone();
+        move |kind| ExportError { name, kind }
+    };
+    let mut translator = Translator::default();
+    for annotated in formulas {
+        if let AnnotatedFormula::Tfx(tfx) = annotated {
+            if let tfx::Formula::AtomTyping(typing) = &*tfx.0.formula {
+                translator.declare(typing).map_err(error(annotated))?;
+            }
+        }
+    }
+    let mut assertions = vec![];
+    let mut conjectures = vec![];
+    for annotated in formulas {
+        let formula =
+            match translator.formula(annotated).map_err(error(annotated))? {
+                Some(formula) => formula,
+                None => continue,
+            };
+        if (annotated.role().0).0 == "conjecture" {
+            conjectures.push(formula);
+        } else {
+            assertions.push(formula);
+        }
+    }
+    Ok(translator.finish(assertions, conjectures))
+}
+
+/// what went wrong in `import`, or in reading a `Script`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ImportError {
+    #[display(fmt = "syntax error at byte {}", _0)]
+    Syntax(usize),
+    #[display(fmt = "malformed {}", _0)]
+    Malformed(String),
+    #[display(fmt = "unsupported {}", _0)]
+    Unsupported(String),
+    #[display(fmt = "undeclared symbol {}", _0)]
+    Undeclared(String),
+    #[display(fmt = "conflicting declarations of {}", _0)]
+    Conflict(String),
+    #[display(fmt = "ill-typed term {}", _0)]
+    IllTyped(String),
+}
+
+/// a reader of s-expressions
+struct Reader<'t> {
+    input: &'t str,
+    position: usize,
+}
+
+impl<'t> Reader<'t> {
+    /// skip whitespace and `;` comments
+    fn skip(&mut self) {
+        let bytes = self.input.as_bytes();
+        while let Some(byte) = bytes.get(self.position) {
+            if *byte == b';' {
+                while !matches!(bytes.get(self.position), None | Some(b'\n')) {
+                    self.position += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.position += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<Sexp, ImportError> {
+        let bytes = self.input.as_bytes();
+        let start = self.position;
+        let find = |from: usize, delimiter: u8| {
+            let length = bytes[from..].iter().position(|b| *b == delimiter);
+            length.map(|length| from + length + 1)
+        };
+        self.position = match bytes[start] {
+            b'|' => find(start + 1, b'|').ok_or(ImportError::Syntax(start))?,
+            b'"' => {
+                let mut end = start + 1;
+                loop {
+                    end = find(end, b'"').ok_or(ImportError::Syntax(start))?;
+                    if bytes.get(end) != Some(&b'"') {
+                        break end;
+                    }
+                    end += 1;
+                }
+            }
+            _ => bytes[start..]
+                .iter()
+                .position(|b| b.is_ascii_whitespace() || b"();|\"".contains(b))
+                .map_or(bytes.len(), |length| start + length),
+        };
+        Ok(atom(&self.input[start..self.position]))
+    }
+
+    /// the next s-expression, or `None` at the end of the input
+    fn sexp(&mut self) -> Result<Option<Sexp>, ImportError> {
+        self.skip();
+        let start = self.position;
+        match self.input.as_bytes().get(start) {
+            None => Ok(None),
+            Some(b')') => Err(ImportError::Syntax(start)),
+            Some(b'(') => {
+                self.position += 1;
+                let mut list = vec![];
+                loop {
+                    self.skip();
+                    if self.input.as_bytes().get(self.position) == Some(&b')')
+                    {
+                        self.position += 1;
+                        return Ok(Some(Sexp::List(list)));
+                    }
+                    list.push(self.sexp()?.ok_or(ImportError::Syntax(start))?);
+                }
+            }
+            Some(_) => self.atom().map(Some),
+        }
+    }
+}
+
+impl FromStr for Script {
+    type Err = ImportError;
+
+    fn from_str(input: &str) -> Result<Self, ImportError> {
+        let mut reader = Reader { input, position: 0 };
+        let mut commands = vec![];
+        while let Some(command) = reader.sexp()? {
+            commands.push(command);
+        }
+        Ok(Script(commands))
+    }
+}
+
+/// the text of a symbol, without any `|` quotes
+fn symbol(sexp: &Sexp) -> Option<&str> {
+    let text = match sexp {
+        Sexp::Atom(text) => text.as_str(),
+        Sexp::List(_) => return None,
+    };
+    if text.len() > 1 && text.starts_with('|') && text.ends_with('|') {
+        return Some(&text[1..text.len() - 1]);
+    }
+    match text.as_bytes().first() {
+        None | Some(b'0'..=b'9' | b'"' | b'#' | b':' | b'|') => None,
+        _ => Some(text),
+    }
+}
+
+fn is_decimal(text: &str) -> bool {
+    let digits = |text: &str| {
+        !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit())
+    };
+    match text.split_once('.') {
+        Some((integral, fractional)) => digits(integral) && digits(fractional),
+        None => false,
+    }
+}
+
+/// a TPTP word for an SMT-LIB symbol: a lower word if possible, or single-quoted
+fn quote<'a>(
+    arena: &'a Arena,
+    symbol: &str,
+) -> Result<AtomicWord<'a>, ImportError> {
+    let mut chars = symbol.chars();
+    let lower = match chars.next() {
+        Some(first) => first.is_ascii_lowercase(),
+        None => false,
+    };
+    if lower && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Ok(AtomicWord::Lower(LowerWord(arena.alloc(symbol))));
+    }
+    if symbol.is_empty() || !symbol.chars().all(|c| matches!(c, ' '..='~')) {
+        return Err(ImportError::Unsupported(format!("|{}|", symbol)));
+    }
+    let escaped = symbol.replace('\\', "\\\\").replace('\'', "\\'");
+    let quoted = SingleQuoted(arena.alloc(&escaped));
+    Ok(AtomicWord::SingleQuoted(quoted))
+}
+
+/// the name given to `sexp` by a `:named` annotation
+fn named(sexp: &Sexp) -> Option<&str> {
+    let list = match sexp {
+        Sexp::List(list) => list,
+        Sexp::Atom(_) => return None,
+    };
+    if symbol(list.first()?) != Some("!") {
+        return None;
+    }
+    let mut attributes = list.iter().skip(2);
+    let is_named = |attribute: &&Sexp| matches!(attribute, Sexp::Atom(keyword) if keyword == ":named");
+    attributes.find(is_named)?;
+    symbol(attributes.next()?)
+}
+
+/// constructors for TFX syntax, with parentheses where TPTP needs them
+///
+/// Expressions are `tfx::UnitaryTerm`s, with compound formulas as `UnitaryTerm::Logic`.
+mod tfx_syntax {
+    use super::*;
+    use tfx::{LogicFormula, UnitaryFormula, UnitaryTerm};
+
+    fn defined_word(word: &str) -> AtomicDefinedWord<'_> {
+        AtomicDefinedWord(DollarWord(LowerWord(word)))
+    }
+
+    pub(super) fn defined_type(word: &str) -> tfx::AtomicType<'_> {
+        tfx::AtomicType::Defined(DefinedType(defined_word(word)))
+    }
+
+    /// an expression as a formula, which it must be
+    pub(super) fn logic(term: UnitaryTerm) -> LogicFormula {
+        match term {
+            UnitaryTerm::Atomic(atomic) => {
+                LogicFormula::Unitary(UnitaryFormula::Atomic(atomic))
+            }
+            UnitaryTerm::Variable(variable) => {
+                LogicFormula::Unitary(UnitaryFormula::Variable(variable))
+            }
+            UnitaryTerm::Logic(formula) => *formula,
+            UnitaryTerm::Defined(_) => {
+                unreachable!("numbers are not formulas")
+            }
+        }
+    }
+
+    fn expression(formula: LogicFormula) -> UnitaryTerm {
+        match formula {
+            LogicFormula::Unitary(UnitaryFormula::Atomic(atomic)) => {
+                UnitaryTerm::Atomic(atomic)
+            }
+            LogicFormula::Unitary(UnitaryFormula::Variable(variable)) => {
+                UnitaryTerm::Variable(variable)
+            }
+            formula => UnitaryTerm::Logic(Box::new(formula)),
+        }
+    }
+
+    fn unit(term: UnitaryTerm) -> tfx::UnitFormula {
+        match logic(term) {
+            LogicFormula::Unary(unary) => tfx::UnitFormula::Unary(unary),
+            LogicFormula::Unitary(unitary) => {
+                tfx::UnitFormula::Unitary(unitary)
+            }
+            LogicFormula::DefinedInfix(infix) => {
+                tfx::UnitFormula::DefinedInfix(infix)
+            }
+            binary => tfx::UnitFormula::Unitary(UnitaryFormula::Logic(
+                Box::new(binary),
+            )),
+        }
+    }
+
+    fn argument(term: UnitaryTerm) -> tfx::Term {
+        match term {
+            UnitaryTerm::Defined(defined) => tfx::Term::Defined(defined),
+            term => tfx::Term::Logic(Box::new(logic(term))),
+        }
+    }
+
+    fn arguments(terms: Vec<UnitaryTerm>) -> Box<tfx::Arguments> {
+        Box::new(tfx::Arguments(terms.into_iter().map(argument).collect()))
+    }
+
+    /// `$word(terms)`, or `$word` if there are no terms
+    pub(super) fn defined<'a>(
+        word: &'a str,
+        terms: Vec<UnitaryTerm<'a>>,
+    ) -> UnitaryTerm<'a> {
+        let functor = DefinedFunctor(defined_word(word));
+        let plain = if terms.is_empty() {
+            tfx::DefinedPlain::Constant(DefinedConstant(functor))
+        } else {
+            tfx::DefinedPlain::Function(functor, arguments(terms))
+        };
+        UnitaryTerm::Atomic(tfx::AtomicFormula::Defined(tfx::DefinedAtomic(
+            plain,
+        )))
+    }
+
+    /// `word(terms)`, or `word` if there are no terms
+    pub(super) fn plain<'a>(
+        word: AtomicWord<'a>,
+        terms: Vec<UnitaryTerm<'a>>,
+    ) -> UnitaryTerm<'a> {
+        let functor = Functor(word);
+        let plain = if terms.is_empty() {
+            tfx::PlainAtomic::Constant(Constant(functor))
+        } else {
+            tfx::PlainAtomic::Function(functor, arguments(terms))
+        };
+        UnitaryTerm::Atomic(tfx::AtomicFormula::Plain(plain))
+    }
+
+    pub(super) fn number(number: Number) -> UnitaryTerm {
+        UnitaryTerm::Defined(DefinedTerm::Number(number))
+    }
+
+    pub(super) fn not(term: UnitaryTerm) -> UnitaryTerm {
+        let formula = match logic(term) {
+            LogicFormula::DefinedInfix(tfx::DefinedInfix {
+                left,
+                right,
+                ..
+            }) => {
+                return expression(LogicFormula::Unary(
+                    tfx::UnaryFormula::Infix(tfx::InfixUnary {
+                        left,
+                        op: InfixInequality,
+                        right,
+                    }),
+                ))
+            }
+            LogicFormula::Unitary(unitary) => {
+                tfx::PreunitFormula::Unitary(unitary)
+            }
+            LogicFormula::Unary(tfx::UnaryFormula::Prefix(prefix)) => {
+                tfx::PreunitFormula::Prefix(prefix)
+            }
+            formula => tfx::PreunitFormula::Unitary(UnitaryFormula::Logic(
+                Box::new(formula),
+            )),
+        };
+        expression(LogicFormula::Unary(tfx::UnaryFormula::Prefix(
+            tfx::PrefixUnary {
+                op: UnaryConnective,
+                formula: Box::new(formula),
+            },
+        )))
+    }
+
+    pub(super) fn equal<'a>(
+        left: UnitaryTerm<'a>,
+        right: UnitaryTerm<'a>,
+    ) -> UnitaryTerm<'a> {
+        expression(LogicFormula::DefinedInfix(tfx::DefinedInfix {
+            left: Box::new(left),
+            op: DefinedInfixPred(InfixEquality),
+            right: Box::new(right),
+        }))
+    }
+
+    pub(super) fn binary<'a>(
+        left: UnitaryTerm<'a>,
+        op: NonassocConnective,
+        right: UnitaryTerm<'a>,
+    ) -> UnitaryTerm<'a> {
+        expression(LogicFormula::Binary(tfx::BinaryFormula::Nonassoc(
+            tfx::BinaryNonassoc {
+                left: Box::new(unit(left)),
+                op,
+                right: Box::new(unit(right)),
+            },
+        )))
+    }
+
+    /// `op` applied to `terms`, which may have fewer than two elements
+    pub(super) fn assoc(
+        op: AssocConnective,
+        mut terms: Vec<UnitaryTerm>,
+    ) -> UnitaryTerm {
+        match terms.len() {
+            0 if op == AssocConnective::And => defined("true", vec![]),
+            0 => defined("false", vec![]),
+            1 => terms.remove(0),
+            _ => {
+                let units = terms.into_iter().map(unit).collect();
+                expression(LogicFormula::Binary(tfx::BinaryFormula::Assoc(
+                    match op {
+                        AssocConnective::And => {
+                            tfx::BinaryAssoc::And(tfx::AndFormula(units))
+                        }
+                        AssocConnective::Or => {
+                            tfx::BinaryAssoc::Or(tfx::OrFormula(units))
+                        }
+                    },
+                )))
+            }
+        }
+    }
+
+    /// the conjunction of `relation` between each adjacent pair of `terms`
+    pub(super) fn chain<'a>(
+        terms: &[UnitaryTerm<'a>],
+        relation: impl Fn(UnitaryTerm<'a>, UnitaryTerm<'a>) -> UnitaryTerm<'a>,
+    ) -> UnitaryTerm<'a> {
+        let pairs = terms.windows(2);
+        let relations =
+            pairs.map(|pair| relation(pair[0].clone(), pair[1].clone()));
+        assoc(AssocConnective::And, relations.collect())
+    }
+
+    pub(super) fn quantified<'a>(
+        quantifier: fof::Quantifier,
+        bound: Vec<tfx::Variable<'a>>,
+        body: UnitaryTerm<'a>,
+    ) -> UnitaryTerm<'a> {
+        expression(LogicFormula::Unitary(UnitaryFormula::Quantified(
+            tfx::QuantifiedFormula {
+                quantifier,
+                bound: tfx::VariableList(bound),
+                formula: Box::new(unit(body)),
+            },
+        )))
+    }
+
+    /// `word: range`, or `word: domain > range`
+    pub(super) fn typing<'a>(
+        word: AtomicWord<'a>,
+        mut domain: Vec<tfx::AtomicType<'a>>,
+        range: tfx::AtomicType<'a>,
+    ) -> tfx::Formula<'a> {
+        let typ = if domain.is_empty() {
+            tfx::TopLevelType::Atomic(Box::new(range))
+        } else {
+            let domain = if domain.len() == 1 {
+                tfx::UnitaryType::Atomic(domain.remove(0))
+            } else {
+                let domain = domain.into_iter().map(tfx::UnitaryType::Atomic);
+                tfx::UnitaryType::Product(tfx::XprodType(domain.collect()))
+            };
+            let mapping = tfx::MappingType {
+                domain: Box::new(domain),
+                range,
+            };
+            tfx::TopLevelType::NonAtomic(Box::new(
+                tfx::NonAtomicType::Mapping(mapping),
+            ))
+        };
+        let atom = UntypedAtom::Constant(Constant(Functor(word)));
+        tfx::Formula::AtomTyping(Box::new(tfx::AtomTyping::Typing(atom, typ)))
+    }
+}
+
+type Expression<'a> = (tfx::UnitaryTerm<'a>, Sort);
+
+struct Importer<'a> {
+    arena: &'a Arena,
+    sorts: BTreeMap<String, (AtomicWord<'a>, usize)>,
+    functions: BTreeMap<String, (AtomicWord<'a>, Vec<Sort>, Sort)>,
+    /// bound variables and `let` bindings, innermost last
+    scope: Vec<(String, Expression<'a>)>,
+    /// variable names used in the current formula
+    variables: BTreeSet<&'a str>,
+    /// whether numerals are reals, as in logics with reals but not integers
+    real_numerals: bool,
+    /// symbols for `let` bindings
+    fresh: Fresh<'a>,
+    formulas: Vec<(Option<AtomicWord<'a>>, &'static str, tfx::Formula<'a>)>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(arena: &'a Arena) -> Self {
+        Self {
+            arena,
+            sorts: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            scope: vec![],
+            variables: BTreeSet::new(),
+            real_numerals: false,
+            fresh: Fresh::new(arena),
+            formulas: vec![],
+        }
+    }
+
+    /// never use a symbol of `sexp` for a `let` binding
+    fn avoid(&mut self, sexp: &Sexp) {
+        match sexp {
+            Sexp::Atom(_) => match symbol(sexp) {
+                Some(symbol)
+                    if symbol.starts_with("let")
+                        && !self.fresh.is_used(symbol) =>
+                {
+                    self.fresh.avoid(self.arena.alloc(symbol))
+                }
+                _ => {}
+            },
+            Sexp::List(list) => list.iter().for_each(|sexp| self.avoid(sexp)),
+        }
+    }
+
+    /// a TPTP word for a new SMT-LIB symbol
+    ///
+    /// Sorts and functions have separate namespaces in SMT-LIB, but not in TPTP.
+    fn word(&self, symbol: &str) -> Result<AtomicWord<'a>, ImportError> {
+        if self.sorts.contains_key(symbol)
+            || self.functions.contains_key(symbol)
+        {
+            return Err(ImportError::Conflict(symbol.to_string()));
+        }
+        quote(self.arena, symbol)
+    }
+
+    /// a fresh TPTP variable for an SMT-LIB symbol
+    fn variable(&mut self, symbol: &str) -> Variable<'a> {
+        let mut base: String = symbol
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        if base.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            base[..1].make_ascii_uppercase();
+        } else {
+            base.insert(0, 'X');
+        }
+        let mut name = base.clone();
+        let mut suffix = 0;
+        while self.variables.contains(name.as_str()) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        let name = self.arena.alloc(&name);
+        self.variables.insert(name);
+        Variable(UpperWord(name))
+    }
+
+    fn sort(&self, sexp: &Sexp) -> Result<Sort, ImportError> {
+        let malformed = || ImportError::Malformed(sexp.to_string());
+        let (name, arguments) = match sexp {
+            Sexp::Atom(_) => (symbol(sexp), &[][..]),
+            Sexp::List(list) => match list.split_first() {
+                Some((head, arguments)) if !arguments.is_empty() => {
+                    (symbol(head), arguments)
+                }
+                _ => return Err(malformed()),
+            },
+        };
+        let name = name.ok_or_else(malformed)?;
+        match (name, arguments.len()) {
+            ("Bool", 0) => return Ok(Sort::Bool),
+            ("Int", 0) => return Ok(Sort::Int),
+            ("Real", 0) => return Ok(Sort::Real),
+            ("$i", 0) if self.sorts.contains_key(name) => {
+                return Ok(Sort::Individual)
+            }
+            _ => {}
+        }
+        match self.sorts.get(name) {
+            Some((_, arity)) if *arity == arguments.len() => {
+                let arguments = arguments
+                    .iter()
+                    .map(|sort| self.sort(sort))
+                    .collect::<Result<_, _>>()?;
+                Ok(Sort::Declared(name.to_string(), arguments))
+            }
+            Some(_) => Err(ImportError::IllTyped(sexp.to_string())),
+            None => Err(ImportError::Undeclared(name.to_string())),
+        }
+    }
+
+    fn atomic_type(&self, sort: &Sort) -> tfx::AtomicType<'a> {
+        match sort {
+            Sort::Individual => tfx_syntax::defined_type("i"),
+            Sort::Bool => tfx_syntax::defined_type("o"),
+            Sort::Int => tfx_syntax::defined_type("int"),
+            Sort::Real => tfx_syntax::defined_type("real"),
+            Sort::Declared(name, arguments) => {
+                let functor = TypeFunctor(self.sorts[name].0.clone());
+                if arguments.is_empty() {
+                    return tfx::AtomicType::Constant(TypeConstant(functor));
+                }
+                let arguments =
+                    arguments.iter().map(|sort| self.atomic_type(sort));
+                let arguments = tfx::TypeArguments(arguments.collect());
+                tfx::AtomicType::Function(functor, Box::new(arguments))
+            }
+        }
+    }
+
+    fn number(&self, text: &str, real: bool) -> tfx::UnitaryTerm<'a> {
+        let number = if real {
+            Number::Real(Real(self.arena.alloc(text)))
+        } else {
+            Number::Integer(Integer(self.arena.alloc(text)))
+        };
+        tfx_syntax::number(number)
+    }
+
+    /// `term` as a real, if it is an integer
+    fn to_real(
+        &self,
+        term: tfx::UnitaryTerm<'a>,
+        sort: &Sort,
+    ) -> tfx::UnitaryTerm<'a> {
+        if *sort != Sort::Int {
+            return term;
+        }
+        match term {
+            tfx::UnitaryTerm::Defined(DefinedTerm::Number(
+                Number::Integer(integer),
+            )) => self.number(&format!("{}.0", integer), true),
+            term => tfx_syntax::defined("to_real", vec![term]),
+        }
+    }
+
+    /// numeric `terms` with a common sort, converting integers to reals if necessary or if `real`
+    fn numeric(
+        &self,
+        terms: Vec<tfx::UnitaryTerm<'a>>,
+        sorts: &[Sort],
+        real: bool,
+    ) -> Option<(Vec<tfx::UnitaryTerm<'a>>, Sort)> {
+        if sorts.is_empty() || !sorts.iter().all(Sort::is_numeric) {
+            return None;
+        }
+        if !real && sorts.iter().all(|sort| *sort == Sort::Int) {
+            return Some((terms, Sort::Int));
+        }
+        let terms = terms.into_iter().zip(sorts);
+        let terms = terms.map(|(term, sort)| self.to_real(term, sort));
+        Some((terms.collect(), Sort::Real))
+    }
+
+    /// `terms` with a common sort
+    fn uniform(
+        &self,
+        terms: Vec<tfx::UnitaryTerm<'a>>,
+        sorts: &[Sort],
+    ) -> Option<(Vec<tfx::UnitaryTerm<'a>>, Sort)> {
+        let sort = sorts.first()?;
+        if sorts.iter().all(|other| other == sort) {
+            return Some((terms, sort.clone()));
+        }
+        self.numeric(terms, sorts, false)
+    }
+
+    fn negate(&self, term: tfx::UnitaryTerm<'a>) -> tfx::UnitaryTerm<'a> {
+        if let tfx::UnitaryTerm::Defined(DefinedTerm::Number(number)) = &term {
+            let (real, text) = match number {
+                Number::Integer(integer) => (false, integer.0),
+                Number::Real(real) => (true, real.0),
+                Number::Rational(_) => unreachable!("no rational literals"),
+            };
+            if !text.starts_with('-') {
+                return self.number(&format!("-{}", text), real);
+            }
+        }
+        tfx_syntax::defined("uminus", vec![term])
+    }
+
+    /// a theory symbol applied to `arguments`
+    fn builtin(
+        &self,
+        sexp: &Sexp,
+        op: &str,
+        arguments: Vec<Expression<'a>>,
+    ) -> Result<Expression<'a>, ImportError> {
+        use tfx_syntax::*;
+        use AssocConnective::*;
+        use NonassocConnective::*;
+        let ill_typed = || ImportError::IllTyped(sexp.to_string());
+        let arity = arguments.len();
+        let boolean = arguments.iter().all(|(_, sort)| *sort == Sort::Bool);
+        let (mut terms, sorts): (Vec<_>, Vec<_>) =
+            arguments.into_iter().unzip();
+        let term = match op {
+            "true" if arity == 0 => defined("true", vec![]),
+            "false" if arity == 0 => defined("false", vec![]),
+            "not" if boolean && arity == 1 => not(terms.remove(0)),
+            "and" if boolean => assoc(And, terms),
+            "or" if boolean => assoc(Or, terms),
+            "=>" if boolean && arity > 1 => {
+                let last = terms.pop().expect("two operands");
+                let terms = terms.into_iter().rev();
+                terms.fold(last, |right, left| binary(left, LRImplies, right))
+            }
+            "xor" if boolean && arity > 1 => {
+                let first = terms.remove(0);
+                let terms = terms.into_iter();
+                terms.fold(first, |left, right| {
+                    binary(left, NotEquivalent, right)
+                })
+            }
+            "=" | "distinct" if arity > 1 => {
+                let (terms, sort) =
+                    self.uniform(terms, &sorts).ok_or_else(ill_typed)?;
+                match (op, sort) {
+                    ("=", Sort::Bool) => chain(&terms, |left, right| {
+                        binary(left, Equivalent, right)
+                    }),
+                    ("=", _) => chain(&terms, equal),
+                    (_, Sort::Bool) => {
+                        let mut pairs = vec![];
+                        for (index, left) in terms.iter().enumerate() {
+                            for right in &terms[index + 1..] {
+                                let (left, right) =
+                                    (left.clone(), right.clone());
+                                pairs.push(binary(left, NotEquivalent, right));
+                            }
+                        }
+                        assoc(And, pairs)
+                    }
+                    _ if arity == 2 => {
+                        let right = terms[1].clone();
+                        not(equal(terms[0].clone(), right))
+                    }
+                    _ => defined("distinct", terms),
+                }
+            }
+            "ite" if arity == 3 && sorts[0] == Sort::Bool => {
+                let condition = terms.remove(0);
+                let (mut branches, sort) =
+                    self.uniform(terms, &sorts[1..]).ok_or_else(ill_typed)?;
+                branches.insert(0, condition);
+                return Ok((defined("ite", branches), sort));
+            }
+            "+" | "-" | "*" | "/" if arity > 0 => {
+                let real = op == "/";
+                let (mut terms, sort) =
+                    self.numeric(terms, &sorts, real).ok_or_else(ill_typed)?;
+                if op == "-" && arity == 1 {
+                    return Ok((self.negate(terms.remove(0)), sort));
+                }
+                if arity == 1 {
+                    return Err(ill_typed());
+                }
+                let name = match op {
+                    "+" => "sum",
+                    "-" => "difference",
+                    "*" => "product",
+                    _ => "quotient",
+                };
+                let first = terms.remove(0);
+                let terms = terms.into_iter();
+                let term = terms.fold(first, |left, right| {
+                    defined(name, vec![left, right])
+                });
+                return Ok((term, sort));
+            }
+            "div" | "mod" if arity == 2 && sorts == [Sort::Int, Sort::Int] => {
+                let name = if op == "div" {
+                    "quotient_e"
+                } else {
+                    "remainder_e"
+                };
+                return Ok((defined(name, terms), Sort::Int));
+            }
+            "abs" if arity == 1 && sorts[0].is_numeric() => {
+                let term = terms.remove(0);
+                let real = sorts[0] == Sort::Real;
+                let zero = self.number(if real { "0.0" } else { "0" }, real);
+                let negative = defined("less", vec![term.clone(), zero]);
+                let negated = defined("uminus", vec![term.clone()]);
+                let term = defined("ite", vec![negative, negated, term]);
+                return Ok((term, sorts[0].clone()));
+            }
+            "<" | "<=" | ">" | ">=" if arity > 1 => {
+                let (terms, _) = self
+                    .numeric(terms, &sorts, false)
+                    .ok_or_else(ill_typed)?;
+                let name = match op {
+                    "<" => "less",
+                    "<=" => "lesseq",
+                    ">" => "greater",
+                    _ => "greatereq",
+                };
+                chain(&terms, |left, right| defined(name, vec![left, right]))
+            }
+            "to_real" if sorts == [Sort::Int] => {
+                return Ok((defined("to_real", terms), Sort::Real));
+            }
+            "to_int" if sorts == [Sort::Real] => {
+                return Ok((defined("to_int", terms), Sort::Int));
+            }
+            "is_int" if sorts == [Sort::Real] => defined("is_int", terms),
+            "true" | "false" | "not" | "and" | "or" | "=>" | "xor" | "="
+            | "distinct" | "ite" | "+" | "-" | "*" | "/" | "div" | "mod"
+            | "abs" | "<" | "<=" | ">" | ">=" | "to_real" | "to_int"
+            | "is_int" => return Err(ill_typed()),
+            _ => return Err(ImportError::Undeclared(op.to_string())),
+        };
+        Ok((term, Sort::Bool))
+    }
+
+    /// add a binder's `(symbol sort)` pairs to the scope as fresh variables
+    fn bind(
+        &mut self,
+        bindings: &[Sexp],
+    ) -> Result<Vec<tfx::Variable<'a>>, ImportError> {
+        let mut bound = vec![];
+        for binding in bindings {
+            let malformed = || ImportError::Malformed(binding.to_string());
+            let (name, sort) = match binding {
+                Sexp::List(pair) if pair.len() == 2 => {
+                    (symbol(&pair[0]).ok_or_else(malformed)?, &pair[1])
+                }
+                _ => return Err(malformed()),
+            };
+            let sort = self.sort(sort)?;
+            let variable = self.variable(name);
+            let typ = self.atomic_type(&sort);
+            let term = tfx::UnitaryTerm::Variable(variable.clone());
+            self.scope.push((name.to_string(), (term, sort)));
+            let typed = tfx::TypedVariable { variable, typ };
+            bound.push(tfx::Variable::Typed(typed));
+        }
+        Ok(bound)
+    }
+
+    fn atom(
+        &self,
+        sexp: &Sexp,
+        text: &str,
+    ) -> Result<Expression<'a>, ImportError> {
+        if text.bytes().all(|b| b.is_ascii_digit()) {
+            return Ok(if self.real_numerals {
+                let real = format!("{}.0", text);
+                (self.number(&real, true), Sort::Real)
+            } else {
+                (self.number(text, false), Sort::Int)
+            });
+        }
+        if is_decimal(text) {
+            return Ok((self.number(text, true), Sort::Real));
+        }
+        let name = symbol(sexp)
+            .ok_or_else(|| ImportError::Unsupported(text.to_string()))?;
+        let mut scope = self.scope.iter().rev();
+        if let Some((_, expression)) = scope.find(|(bound, _)| bound == name) {
+            return Ok(expression.clone());
+        }
+        match self.functions.get(name) {
+            Some((word, domain, range)) if domain.is_empty() => {
+                Ok((tfx_syntax::plain(word.clone(), vec![]), range.clone()))
+            }
+            Some(_) => Err(ImportError::IllTyped(text.to_string())),
+            None => self.builtin(sexp, name, vec![]),
+        }
+    }
+
+    fn term(&mut self, sexp: &Sexp) -> Result<Expression<'a>, ImportError> {
+        let malformed = || ImportError::Malformed(sexp.to_string());
+        let unsupported = || ImportError::Unsupported(sexp.to_string());
+        let list = match sexp {
+            Sexp::Atom(text) => return self.atom(sexp, text),
+            Sexp::List(list) => list,
+        };
+        let (head, arguments) = list.split_first().ok_or_else(malformed)?;
+        let head = symbol(head).ok_or_else(unsupported)?;
+        match (head, arguments) {
+            ("!", [term, ..]) => {
+                let expression = self.term(term)?;
+                match named(sexp) {
+                    Some(name) => self.name(sexp, name, expression),
+                    None => Ok(expression),
+                }
+            }
+            ("let", [Sexp::List(bindings), body]) => {
+                let mut bound = vec![];
+                for binding in bindings {
+                    let (name, term) = match binding {
+                        Sexp::List(pair) if pair.len() == 2 => {
+                            (symbol(&pair[0]).ok_or_else(malformed)?, &pair[1])
+                        }
+                        _ => return Err(malformed()),
+                    };
+                    let expression = self.term(term)?;
+                    bound.push((name.to_string(), self.share(expression)?));
+                }
+                let depth = self.scope.len();
+                self.scope.extend(bound);
+                let body = self.term(body)?;
+                self.scope.truncate(depth);
+                Ok(body)
+            }
+            ("forall" | "exists", [Sexp::List(bindings), body])
+                if !bindings.is_empty() =>
+            {
+                let depth = self.scope.len();
+                let bound = self.bind(bindings)?;
+                let (body, sort) = self.term(body)?;
+                self.scope.truncate(depth);
+                if sort != Sort::Bool {
+                    return Err(ImportError::IllTyped(sexp.to_string()));
+                }
+                let quantifier = if head == "forall" {
+                    fof::Quantifier::Forall
+                } else {
+                    fof::Quantifier::Exists
+                };
+                let formula = tfx_syntax::quantified(quantifier, bound, body);
+                Ok((formula, Sort::Bool))
+            }
+            ("!" | "let" | "forall" | "exists", _) => Err(malformed()),
+            ("_" | "as" | "match", _) => Err(unsupported()),
+            _ => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.term(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (word, domain, range) = match self.functions.get(head) {
+                    Some(function) => function,
+                    None => return self.builtin(sexp, head, arguments),
+                };
+                let (terms, sorts): (Vec<_>, Vec<_>) =
+                    arguments.into_iter().unzip();
+                if *domain != sorts {
+                    return Err(ImportError::IllTyped(sexp.to_string()));
+                }
+                Ok((tfx_syntax::plain(word.clone(), terms), range.clone()))
+            }
+        }
+    }
+
+    fn sorts(&self, sorts: &[Sexp]) -> Result<Vec<Sort>, ImportError> {
+        sorts.iter().map(|sort| self.sort(sort)).collect()
+    }
+
+    /// declare `function`, adding a `type` formula
+    fn declare(
+        &mut self,
+        function: &Sexp,
+        domain: Vec<Sort>,
+        range: Sort,
+    ) -> Result<AtomicWord<'a>, ImportError> {
+        let name = symbol(function)
+            .ok_or_else(|| ImportError::Malformed(function.to_string()))?;
+        self.declare_symbol(name, domain, range)
+    }
+
+    fn declare_symbol(
+        &mut self,
+        name: &str,
+        domain: Vec<Sort>,
+        range: Sort,
+    ) -> Result<AtomicWord<'a>, ImportError> {
+        let word = self.word(name)?;
+        let types = domain.iter().map(|sort| self.atomic_type(sort));
+        let range_type = self.atomic_type(&range);
+        let typing =
+            tfx_syntax::typing(word.clone(), types.collect(), range_type);
+        self.formulas.push((None, "type", typing));
+        let function = (word.clone(), domain, range);
+        self.functions.insert(name.to_string(), function);
+        Ok(word)
+    }
+
+    /// declare `function` and add a `definition` formula for it
+    fn define(
+        &mut self,
+        function: &Sexp,
+        parameters: &[Sexp],
+        range: &Sexp,
+        body: &Sexp,
+    ) -> Result<(), ImportError> {
+        use tfx_syntax::*;
+        let bound = self.bind(parameters)?;
+        let parameters = self.scope.iter().map(|(_, parameter)| parameter);
+        let (parameters, domain): (Vec<_>, Vec<_>) =
+            parameters.cloned().unzip();
+        let range = self.sort(range)?;
+        let (definiens, sort) = self.term(body)?;
+        self.scope.clear();
+        let definiens = match (&range, &sort) {
+            (Sort::Real, Sort::Int) => self.to_real(definiens, &sort),
+            _ if range == sort => definiens,
+            _ => return Err(ImportError::IllTyped(body.to_string())),
+        };
+        let boolean = range == Sort::Bool;
+        let word = self.declare(function, domain, range)?;
+        let head = plain(word, parameters);
+        self.definition(head, definiens, boolean, bound);
+        Ok(())
+    }
+
+    /// add a `definition` formula for `head`, universally closed by `bound`
+    fn definition(
+        &mut self,
+        head: tfx::UnitaryTerm<'a>,
+        definiens: tfx::UnitaryTerm<'a>,
+        boolean: bool,
+        bound: Vec<tfx::Variable<'a>>,
+    ) {
+        use tfx_syntax::*;
+        let mut definition = if boolean {
+            binary(head, NonassocConnective::Equivalent, definiens)
+        } else {
+            equal(head, definiens)
+        };
+        if !bound.is_empty() {
+            let forall = fof::Quantifier::Forall;
+            definition = quantified(forall, bound, definition);
+        }
+        let definition = tfx::Formula::Logic(Box::new(logic(definition)));
+        self.formulas.push((None, "definition", definition));
+    }
+
+    /// declare the name of a `:named` term as a constant defined as the term, which stands for it from then on
+    ///
+    /// Named terms must be closed, so this is unsupported under a binder.
+    fn name(
+        &mut self,
+        sexp: &Sexp,
+        name: &str,
+        (term, sort): Expression<'a>,
+    ) -> Result<Expression<'a>, ImportError> {
+        let bound = self.scope.iter().any(|(_, (term, _))| {
+            matches!(term, tfx::UnitaryTerm::Variable(_))
+        });
+        if bound {
+            return Err(ImportError::Unsupported(sexp.to_string()));
+        }
+        let boolean = sort == Sort::Bool;
+        let word = self.declare_symbol(name, vec![], sort.clone())?;
+        let head = tfx_syntax::plain(word, vec![]);
+        self.definition(head.clone(), term, boolean, vec![]);
+        Ok((head, sort))
+    }
+
+    /// a `let`-bound expression: itself if it is atomic, or else a fresh symbol defined as it
+    ///
+    /// The symbol takes the variables in scope as arguments, so that a binding is translated once however often it is used.
+    fn share(
+        &mut self,
+        (term, sort): Expression<'a>,
+    ) -> Result<Expression<'a>, ImportError> {
+        let atomic = match &term {
+            tfx::UnitaryTerm::Variable(_) | tfx::UnitaryTerm::Defined(_) => {
+                true
+            }
+            tfx::UnitaryTerm::Atomic(tfx::AtomicFormula::Plain(plain)) => {
+                matches!(plain, tfx::PlainAtomic::Constant(_))
+            }
+            tfx::UnitaryTerm::Atomic(tfx::AtomicFormula::Defined(defined)) => {
+                matches!(defined.0, tfx::DefinedPlain::Constant(_))
+            }
+            _ => false,
+        };
+        if atomic {
+            return Ok((term, sort));
+        }
+        let parameters: Vec<_> = self
+            .scope
+            .iter()
+            .filter_map(|(_, (term, sort))| match term {
+                tfx::UnitaryTerm::Variable(variable) => {
+                    Some((variable.clone(), sort.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let domain = parameters.iter().map(|(_, sort)| sort.clone());
+        let name = self.fresh.symbol("let");
+        let word =
+            self.declare_symbol(name, domain.collect(), sort.clone())?;
+        let arguments = parameters
+            .iter()
+            .map(|(variable, _)| tfx::UnitaryTerm::Variable(variable.clone()));
+        let head = tfx_syntax::plain(word, arguments.collect());
+        let bound = parameters
+            .into_iter()
+            .map(|(variable, sort)| {
+                let typ = self.atomic_type(&sort);
+                tfx::Variable::Typed(tfx::TypedVariable { variable, typ })
+            })
+            .collect();
+        let boolean = sort == Sort::Bool;
+        self.definition(head.clone(), term, boolean, bound);
+        Ok((head, sort))
+    }
+
+    fn assert(&mut self, formula: &Sexp) -> Result<(), ImportError> {
+        let (term, sort) = self.term(formula)?;
+        if sort != Sort::Bool {
+            return Err(ImportError::IllTyped(formula.to_string()));
+        }
+        let name = match named(formula) {
+            Some(name) => Some(quote(self.arena, name)?),
+            None => None,
+        };
+        let formula = tfx::Formula::Logic(Box::new(tfx_syntax::logic(term)));
+        self.formulas.push((name, "axiom", formula));
+        Ok(())
+    }
+
+    fn command(&mut self, command: &Sexp) -> Result<(), ImportError> {
+        let malformed = || ImportError::Malformed(command.to_string());
+        let (name, arguments) = match command {
+            Sexp::List(list) => match list.split_first() {
+                Some((Sexp::Atom(name), arguments)) => {
+                    (name.as_str(), arguments)
+                }
+                _ => return Err(malformed()),
+            },
+            Sexp::Atom(_) => return Err(malformed()),
+        };
+        self.scope.clear();
+        self.variables.clear();
+        match (name, arguments) {
+            ("set-logic", [logic]) => {
+                let logic = symbol(logic).ok_or_else(malformed)?;
+                let reals = logic.contains("RA") || logic.contains("RDL");
+                let integers = logic.contains("IA")
+                    || logic.contains("IRA")
+                    || logic.contains("IDL");
+                self.real_numerals = reals && !integers;
+            }
+            ("declare-sort", [sort, Sexp::Atom(arity)]) => {
+                let sort = symbol(sort).ok_or_else(malformed)?;
+                let arity: usize = arity.parse().map_err(|_| malformed())?;
+                let word = self.word(sort)?;
+                self.sorts.insert(sort.to_string(), (word.clone(), arity));
+                if sort != "$i" || arity != 0 {
+                    let kind = tfx_syntax::defined_type("tType");
+                    let domain = vec![kind.clone(); arity];
+                    let typing = tfx_syntax::typing(word, domain, kind);
+                    self.formulas.push((None, "type", typing));
+                }
+            }
+            ("declare-fun", [function, Sexp::List(domain), range]) => {
+                let domain = self.sorts(domain)?;
+                let range = self.sort(range)?;
+                self.declare(function, domain, range)?;
+            }
+            ("declare-const", [function, range]) => {
+                let range = self.sort(range)?;
+                self.declare(function, vec![], range)?;
+            }
+            (
+                "define-fun",
+                [function, Sexp::List(parameters), range, body],
+            ) => self.define(function, parameters, range, body)?,
+            ("assert", [formula]) => self.assert(formula)?,
+            ("set-info" | "set-option" | "check-sat" | "echo" | "exit", _) => {
+            }
+            _ if name.starts_with("get-") => {}
+            (
+                "set-logic" | "declare-sort" | "declare-fun" | "declare-const"
+                | "define-fun" | "assert",
+                _,
+            ) => return Err(malformed()),
+            _ => return Err(ImportError::Unsupported(name.to_string())),
+        }
+        Ok(())
+    }
+
+    /// the formulas, with fresh names for those without one
+    fn finish(self) -> Vec<AnnotatedFormula<'a>> {
+        let mut fresh = Fresh::new(self.arena);
+        for (name, _, _) in &self.formulas {
+            if let Some(AtomicWord::Lower(word)) = name {
+                fresh.avoid(word.0);
+            }
+        }
+        let formulas = self.formulas.into_iter();
+        formulas
+            .map(|(name, role, formula)| {
+                let name = name.unwrap_or_else(|| {
+                    AtomicWord::Lower(LowerWord(fresh.symbol(role)))
+                });
+                let annotated = Annotated {
+                    name: Name::AtomicWord(name),
+                    role: FormulaRole(LowerWord(role)),
+                    formula: Box::new(formula),
+                    annotations: Annotations(None),
+                };
+                AnnotatedFormula::Tfx(Box::new(TfxAnnotated(annotated)))
+            })
+            .collect()
+    }
+}
+
+/// import an SMT-LIB v2 script as TFX formulas, storing new symbols in `arena`
+///
+/// Sort and function declarations become `type` formulas, `define-fun` becomes a declaration and a `definition`, and assertions become axioms, so the formulas are satisfiable iff the script is.
+/// Assertions are named by a top-level `:named` annotation if they have one, and other formulas get fresh names such as `axiom0`.
+/// As in SMT-LIB, a `:named` term also declares its name as a constant, with a `definition` equating it to the term, and the constant replaces the term.
+/// Symbols which are not TPTP lower words are single-quoted, bound variables are renamed to TPTP variables and other annotations are dropped.
+/// A `let` binding which is not atomic becomes a fresh symbol such as `let0` with a `definition`, taking the variables in scope as arguments, so that shared bindings are not copied.
+/// Core, `Ints` and `Reals` theory symbols become their TPTP equivalents, with `ite` as `$ite`, and integers are converted to reals where a real is expected.
+/// In logics with reals but not integers, numerals are reals.
+/// A sort `$i` of arity 0, as declared by `export`, is TPTP's `$i`.
+/// Commands without logical content such as `check-sat` are ignored, and other commands such as `push` are unsupported.
+pub fn import<'a>(
+    script: &Script,
+    arena: &'a Arena,
+) -> Result<Vec<AnnotatedFormula<'a>>, ImportError> {
+    let mut importer = Importer::new(arena);
+    for command in &script.0 {
+        importer.avoid(command);
+    }
+    for command in &script.0 {
+        importer.command(command)?;
+    }
+    Ok(importer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    fn script(input: &[u8]) -> String {
+        export(&problem(input)).expect("export error").to_string()
+    }
+
+    #[test]
+    fn test_fof() {
+        let script = script(
+            b"
+            fof(a, axiom, ![X]: (p(X) => q(f(X)))).
+            cnf(b, axiom, p(c) | X = 'and' | ~q(\"one\") | q(\"two\")).
+            fof(goal, conjecture, ?[Y]: q(Y)).
+        ",
+        );
+        assert_eq!(
+            script,
+            "\
+(set-logic UF)
+(declare-sort $i 0)
+(declare-fun f_p ($i) Bool)
+(declare-fun f_f ($i) $i)
+(declare-fun f_q ($i) Bool)
+(declare-fun f_c () $i)
+(declare-fun f_and () $i)
+(declare-fun d_one () $i)
+(declare-fun d_two () $i)
+(assert (distinct d_one d_two))
+(assert (forall ((v_X $i)) (=> (f_p v_X) (f_q (f_f v_X)))))
+(assert (forall ((v_X $i)) (or (f_p f_c) (= v_X f_and) (not (f_q d_one)) (f_q d_two))))
+(assert (not (exists ((v_Y $i)) (f_q v_Y))))
+(check-sat)
+"
+        );
+    }
+
+    #[test]
+    fn test_tfx() {
+        let script = script(
+            b"
+            tff(t, type, t: $tType).
+            tff(g, type, g: (t * $int) > $real).
+            tff('odd name', type, 'a b': t).
+            tff(x, axiom, ![A: t, N: $int]:
+                $less(g(A, $quotient_e(N, 2)), $to_real(N))).
+            tff(y, axiom, g('a b', -3) = $product(1.5e-1, -1/2)).
+            tff(z, axiom, ![N: $int]: $remainder_t($sum(N, 1), 3) != 0).
+            tff(goal, conjecture, $greatereq($floor(2.5), 2.0)).
+        ",
+        );
+        assert_eq!(
+            script,
+            "\
+(set-logic UFLIRA)
+(declare-sort s_t 0)
+(declare-fun f_g (s_t Int) Real)
+(declare-fun f_a%20b () s_t)
+(assert (forall ((v_A s_t) (v_N Int)) (< (f_g v_A (div v_N 2)) (to_real v_N))))
+(assert (= (f_g f_a%20b (- 3)) (* 0.15 (- (/ 1.0 2.0)))))
+(assert (forall ((v_N Int)) (not (= (let (($arg0 (+ v_N 1))) (ite (>= $arg0 0) (mod $arg0 3) (- (mod (- $arg0) 3)))) 0))))
+(assert (not (>= (to_real (to_int 2.5)) 2.0)))
+(check-sat)
+"
+        );
+    }
+
+    #[test]
+    fn test_mangle() {
+        let script = script(
+            b"
+            tff(t, type, t: $tType).
+            tff(c, type, t: t).
+            fof(a, axiom, ![X]: ('a b'(X) | a_b(X) | 'a%20b'(X))).
+            fof(b, axiom, ![X]: p(X, 'X', v_X, '\\\'')).
+        ",
+        );
+        assert_eq!(
+            script,
+            "\
+(set-logic UF)
+(declare-sort $i 0)
+(declare-sort s_t 0)
+(declare-fun f_t () s_t)
+(declare-fun f_a%20b ($i) Bool)
+(declare-fun f_a_b ($i) Bool)
+(declare-fun f_a%2520b ($i) Bool)
+(declare-fun f_X () $i)
+(declare-fun f_v_X () $i)
+(declare-fun f_%27 () $i)
+(declare-fun f_p ($i $i $i $i) Bool)
+(assert (forall ((v_X $i)) (or (f_a%20b v_X) (f_a_b v_X) (f_a%2520b v_X))))
+(assert (forall ((v_X $i)) (f_p v_X f_X f_v_X f_%27)))
+(check-sat)
+"
+        );
+    }
+
+    #[test]
+    fn test_division() {
+        let script = script(
+            b"
+            tff(i, axiom, ![X: $int, Y: $int]:
+                $quotient_f(X, Y) = $remainder_f(X, Y)).
+            tff(r, axiom, ![X: $real, Y: $real]:
+                $quotient_f(X, Y) = $remainder_f(X, Y)).
+            tff(c, axiom, $remainder_f($sum(7, 1), -2) = 0).
+        ",
+        );
+        assert_eq!(
+            script,
+            "\
+(set-logic UFNIRA)
+(assert (forall ((v_X Int) (v_Y Int)) (= (ite (> v_Y 0) (div v_X v_Y) (div (- v_X) (- v_Y))) (ite (> v_Y 0) (mod v_X v_Y) (- (mod (- v_X) (- v_Y)))))))
+(assert (forall ((v_X Real) (v_Y Real)) (= (to_real (to_int (/ v_X v_Y))) (- v_X (* v_Y (to_real (to_int (/ v_X v_Y))))))))
+(assert (= (let (($arg0 (+ 7 1)) ($arg1 (- 2))) (ite (> $arg1 0) (mod $arg0 $arg1) (- (mod (- $arg0) (- $arg1))))) 0))
+(check-sat)
+"
+        );
+    }
+
+    #[test]
+    fn test_ite() {
+        let script = script(
+            b"
+            tff(p, type, p: $int > $o).
+            tff(a, axiom, ![X: $int]:
+                $ite($less(X, 0), $uminus(X), X) != $ite(p(X), 1, 2)).
+            tff(b, axiom, ![X: $int]: $ite(p(X), $true, p(1))).
+        ",
+        );
+        assert_eq!(
+            script,
+            "\
+(set-logic UFLIA)
+(declare-fun f_p (Int) Bool)
+(assert (forall ((v_X Int)) (not (= (ite (< v_X 0) (- v_X) v_X) (ite (f_p v_X) 1 2)))))
+(assert (forall ((v_X Int)) (ite (f_p v_X) true (f_p 1))))
+(check-sat)
+"
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let formulas = problem(
+            b"
+            tff(list, type, list: $tType > $tType).
+            tff(nil, type, nil: !>[A: $tType]: list(A)).
+        ",
+        );
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.name.to_string(), "nil");
+        assert!(matches!(error.kind, ExportErrorKind::Polymorphic(_)));
+
+        let formulas = problem(b"fof(a, axiom, p(f(a)) & f(a)).");
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "a: ill-typed arguments of f(a)");
+
+        let formulas = problem(
+            b"
+            tff(f, type, f: $i > $i).
+            tff(g, type, f: $int > $i).
+        ",
+        );
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "g: conflicting types for f");
+
+        let formulas = problem(
+            b"
+            tff(t, type, t: $tType).
+            tff(u, type, t: $tType > $tType).
+        ",
+        );
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "u: conflicting types for t");
+
+        let formulas = problem(b"tff(a, axiom, $foo(1) = 2).");
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "a: unsupported symbol $foo");
+
+        let formulas = problem(b"tff(c, type, c: $bar).");
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "c: unsupported symbol $bar");
+
+        let formulas = problem(b"tff(a, axiom, $ite($true, 1, 2.0) = 1).");
+        let error = export(&formulas).unwrap_err();
+        assert_eq!(error.to_string(), "a: ill-typed arguments of $ite");
+    }
+
+    fn imported(input: &str) -> String {
+        let arena = Arena::new();
+        let script = input.parse().expect("syntax error");
+        let formulas = import(&script, &arena).expect("import error");
+        let mut printed = String::new();
+        for annotated in &formulas {
+            writeln!(printed, "{}", annotated).expect("write to string");
+        }
+        let reparsed = problem(printed.as_bytes());
+        assert_eq!(reparsed, formulas);
+        printed
+    }
+
+    #[test]
+    fn test_import() {
+        let printed = imported(
+            "
+            ; a comment
+            (set-logic UFLIA)
+            (set-info :status sat)
+            (declare-sort U 0)
+            (declare-sort |Pair x| 2)
+            (declare-fun f (U Int) Int)
+            (declare-const c U)
+            (declare-fun p (U (|Pair x| U Bool)) Bool)
+            (define-fun g ((x Int) (y Int)) Int
+                (ite (> x y) (- x y) (+ x 1 (- 2))))
+            (assert (! (forall ((x U) (|odd x| Int))
+                (=> (exists ((x (|Pair x| U Bool))) (p c x))
+                    (= (f x |odd x|) (g |odd x| 3) (abs (mod 7 2)))))
+                :named ax1))
+            (assert (let ((y (f c 0)))
+                (and (distinct y 1 2) (not (= y (div y 2)))
+                     (xor (<= 0 y 3) true (= false (< y 0))))))
+            (assert (not ax1))
+            (check-sat)
+            (get-model)
+            (exit)
+        ",
+        );
+        assert_eq!(
+            printed,
+            "\
+tff(type0,type,'U':$tType).
+tff(type1,type,'Pair x':($tType*$tType)>$tType).
+tff(type2,type,f:('U'*$int)>$int).
+tff(type3,type,c:'U').
+tff(type4,type,p:('U'*'Pair x'('U',$o))>$o).
+tff(type5,type,g:($int*$int)>$int).
+tff(definition0,definition,![X:$int,Y:$int]:g(X,Y)=$ite($greater(X,Y),$difference(X,Y),$sum($sum(X,1),-2))).
+tff(type6,type,ax1:$o).
+tff(definition1,definition,ax1<=>![X:'U',Oddx:$int]:(?[X0:'Pair x'('U',$o)]:p(c,X0)=>(f(X,Oddx)=g(Oddx,3)&g(Oddx,3)=$ite($less($remainder_e(7,2),0),$uminus($remainder_e(7,2)),$remainder_e(7,2))))).
+tff(ax1,axiom,ax1).
+tff(type7,type,let0:$int).
+tff(definition2,definition,let0=f(c,0)).
+tff(axiom0,axiom,$distinct(let0,1,2)&let0!=$quotient_e(let0,2)&((($lesseq(0,let0)&$lesseq(let0,3))<~>$true)<~>($false<=>$less(let0,0)))).
+tff(axiom1,axiom,~ax1).
+"
+        );
+
+        let reals = "
+            (set-logic QF_LRA)
+            (declare-const x Real)
+            (assert (< (abs x) 1.0))
+        ";
+        assert_eq!(
+            imported(reals),
+            "\
+tff(type0,type,x:$real).
+tff(axiom0,axiom,$less($ite($less(x,0.0),$uminus(x),x),1.0)).
+"
+        );
+    }
+
+    #[test]
+    fn test_let() {
+        let printed = imported(
+            "
+            (declare-fun f (Int Int) Int)
+            (declare-const let0 Int)
+            (assert (forall ((x Int))
+                (let ((a (f x x)) (b 1) (c let0))
+                (let ((a (f a a)))
+                (let ((a (f a a)) (p (> a b)))
+                    (and p (= a c)))))))
+        ",
+        );
+        assert_eq!(
+            printed,
+            "\
+tff(type0,type,f:($int*$int)>$int).
+tff(type1,type,let0:$int).
+tff(type2,type,let1:$int>$int).
+tff(definition0,definition,![X:$int]:let1(X)=f(X,X)).
+tff(type3,type,let2:$int>$int).
+tff(definition1,definition,![X:$int]:let2(X)=f(let1(X),let1(X))).
+tff(type4,type,let3:$int>$int).
+tff(definition2,definition,![X:$int]:let3(X)=f(let2(X),let2(X))).
+tff(type5,type,let4:$int>$o).
+tff(definition3,definition,![X:$int]:(let4(X)<=>$greater(let2(X),1))).
+tff(axiom0,axiom,![X:$int]:(let4(X)&let3(X)=let0)).
+"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let formulas = problem(
+            b"
+            tff(q, type, q: $i > $o).
+            tff(r, type, r: $real > $o).
+            tff(a, axiom, ![X]: (q(X) | X = \"one\")).
+            tff(b, axiom, ![N: $int]: $remainder_t(N, 2) = 0).
+            tff(goal, conjecture, r($quotient(1.0, 2.0))).
+        ",
+        );
+        let script = export(&formulas).expect("export error").to_string();
+        assert_eq!(
+            imported(&script),
+            "\
+tff(type0,type,f_q:$i>$o).
+tff(type1,type,f_r:$real>$o).
+tff(type2,type,d_one:$i).
+tff(axiom0,axiom,![V_X:$i]:(f_q(V_X)|V_X=d_one)).
+tff(axiom1,axiom,![V_N:$int]:$ite($greatereq(V_N,0),$remainder_e(V_N,2),$uminus($remainder_e($uminus(V_N),2)))=0).
+tff(axiom2,axiom,~f_r($quotient(1.0,2.0))).
+"
+        );
+
+        let reals = "(set-logic QF_LRA) (assert (< (- 1) (/ 1 2) 0.5))";
+        assert_eq!(
+            imported(reals),
+            "tff(axiom0,axiom,$less(-1.0,$quotient(1.0,2.0))&\
+             $less($quotient(1.0,2.0),0.5)).\n"
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let error = |input: &str| {
+            let script = input.parse()?;
+            import(&script, &Arena::new()).map(|_| ())
+        };
+        assert_eq!(error("(assert (p)"), Err(ImportError::Syntax(0)));
+        assert_eq!(error("(assert |p)"), Err(ImportError::Syntax(8)));
+        assert_eq!(
+            error("(assert p)").unwrap_err().to_string(),
+            "undeclared symbol p"
+        );
+        assert_eq!(
+            error("(declare-const c Int) (assert (and c true))")
+                .unwrap_err()
+                .to_string(),
+            "ill-typed term (and c true)"
+        );
+        assert_eq!(
+            error("(declare-sort s 0) (declare-const s Bool)"),
+            Err(ImportError::Conflict("s".to_string()))
+        );
+        assert_eq!(
+            error("(assert (forall ((x Int)) (! (> x 0) :named a)))"),
+            Err(ImportError::Unsupported("(! (> x 0) :named a)".to_string()))
+        );
+        assert_eq!(
+            error("(push 1)"),
+            Err(ImportError::Unsupported("push".to_string()))
+        );
+    }
+}
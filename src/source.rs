@@ -0,0 +1,388 @@
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::top::*;
+
+fn items<'b, 'a>(list: &'b GeneralList<'a>) -> &'b [GeneralTerm<'a>] {
+    match &list.0 {
+        Some(terms) => &terms.0,
+        None => &[],
+    }
+}
+
+fn as_list<'b, 'a>(term: &'b GeneralTerm<'a>) -> Option<&'b GeneralList<'a>> {
+    match term {
+        GeneralTerm::List(list) => Some(list),
+        _ => None,
+    }
+}
+
+fn as_atomic<'b, 'a>(term: &'b GeneralTerm<'a>) -> Option<&'b AtomicWord<'a>> {
+    match term {
+        GeneralTerm::Data(GeneralData::Atomic(word)) => Some(word),
+        _ => None,
+    }
+}
+
+fn as_name<'a>(term: &GeneralTerm<'a>) -> Option<Name<'a>> {
+    match term {
+        GeneralTerm::Data(GeneralData::Atomic(word)) => {
+            Some(Name::AtomicWord(word.clone()))
+        }
+        GeneralTerm::Data(GeneralData::Number(Number::Integer(integer))) => {
+            Some(Name::Integer(integer.clone()))
+        }
+        _ => None,
+    }
+}
+
+fn optional_info<'a>(
+    terms: &[GeneralTerm<'a>],
+) -> Option<Vec<GeneralTerm<'a>>> {
+    match terms {
+        [] => Some(Vec::new()),
+        [list] => as_list(list).map(|list| items(list).to_vec()),
+        _ => None,
+    }
+}
+
+fn is_word(word: &AtomicWord, expected: &str) -> bool {
+    matches!(word, AtomicWord::Lower(LowerWord(w)) if *w == expected)
+}
+
+/// find `name(...)` in a list of useful info, e.g. `status(thm)`
+fn find_info<'b, 'a>(
+    info: &'b [GeneralTerm<'a>],
+    name: &str,
+) -> Option<&'b GeneralTerms<'a>> {
+    info.iter().find_map(|term| match term {
+        GeneralTerm::Data(GeneralData::Function(function))
+            if is_word(&function.word, name) =>
+        {
+            Some(&function.terms)
+        }
+        _ => None,
+    })
+}
+
+/// [`inference_record`](http://tptp.org/TPTP/SyntaxBNF.html#inference_record), e.g. `inference(rule, [status(thm)], [p, q])`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Inference<'a> {
+    pub rule: AtomicWord<'a>,
+    /// the useful-info list, e.g. `[status(thm)]`
+    pub info: Vec<GeneralTerm<'a>>,
+    pub parents: Vec<Parent<'a>>,
+}
+
+impl<'a> Inference<'a> {
+    /// the SZS status abbreviation from `status(...)` in the useful info, e.g. `thm`
+    pub fn status(&self) -> Option<&AtomicWord<'a>> {
+        find_info(&self.info, "status")
+            .and_then(|terms| terms.0.first())
+            .and_then(as_atomic)
+    }
+}
+
+/// [`parent_info`](http://tptp.org/TPTP/SyntaxBNF.html#parent_info), a parent source with optional `:[details]`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Parent<'a> {
+    pub source: SourceKind<'a>,
+    pub details: Option<GeneralList<'a>>,
+}
+
+/// a parsed view of a [`source`](http://tptp.org/TPTP/SyntaxBNF.html#source)
+///
+/// Sources are parsed as general terms, so this recognises the standard forms after the fact.
+/// Anything non-standard is kept as `Other`.
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum SourceKind<'a> {
+    /// a reference to another annotated formula
+    Name(Name<'a>),
+    Inference(Inference<'a>),
+    /// `introduced(definition, [...])`
+    Introduced {
+        intro_type: AtomicWord<'a>,
+        info: Vec<GeneralTerm<'a>>,
+    },
+    /// `file('file.p', name)`
+    File {
+        file_name: FileName<'a>,
+        name: Option<Name<'a>>,
+    },
+    /// `theory(equality, [...])`
+    Theory {
+        name: AtomicWord<'a>,
+        info: Vec<GeneralTerm<'a>>,
+    },
+    /// `creator(name, [...])`
+    Creator {
+        name: AtomicWord<'a>,
+        info: Vec<GeneralTerm<'a>>,
+    },
+    /// `unknown`
+    Unknown,
+    /// `[source, ...]`
+    Sources(Vec<SourceKind<'a>>),
+    Other(GeneralTerm<'a>),
+}
+
+impl<'a> SourceKind<'a> {
+    /// recognise a general term as a source
+    pub fn from_general_term(term: &GeneralTerm<'a>) -> Self {
+        Self::recognise(term).unwrap_or_else(|| Self::Other(term.clone()))
+    }
+
+    fn recognise(term: &GeneralTerm<'a>) -> Option<Self> {
+        let function = match term {
+            GeneralTerm::List(list) => {
+                let sources =
+                    items(list).iter().map(Self::from_general_term).collect();
+                return Some(Self::Sources(sources));
+            }
+            GeneralTerm::Data(GeneralData::Atomic(word))
+                if is_word(word, "unknown") =>
+            {
+                return Some(Self::Unknown)
+            }
+            GeneralTerm::Data(GeneralData::Function(function)) => function,
+            _ => return as_name(term).map(Self::Name),
+        };
+        let terms = function.terms.0.as_slice();
+        let word = match &function.word {
+            AtomicWord::Lower(LowerWord(word)) => *word,
+            AtomicWord::SingleQuoted(_) => return None,
+        };
+        match (word, terms) {
+            ("inference", [rule, info, parents]) => {
+                let rule = as_atomic(rule)?.clone();
+                let info = items(as_list(info)?).to_vec();
+                let parents = items(as_list(parents)?)
+                    .iter()
+                    .map(Parent::from_general_term)
+                    .collect();
+                Some(Self::Inference(Inference {
+                    rule,
+                    info,
+                    parents,
+                }))
+            }
+            ("introduced", [intro_type, info @ ..]) => {
+                Some(Self::Introduced {
+                    intro_type: as_atomic(intro_type)?.clone(),
+                    info: optional_info(info)?,
+                })
+            }
+            ("file", [file_name, name @ ..]) => {
+                let file_name = match as_atomic(file_name)? {
+                    AtomicWord::SingleQuoted(quoted) => {
+                        FileName(quoted.clone())
+                    }
+                    AtomicWord::Lower(_) => return None,
+                };
+                let name = match name {
+                    [] => None,
+                    [name] => Some(as_name(name)?),
+                    _ => return None,
+                };
+                Some(Self::File { file_name, name })
+            }
+            ("theory", [name, info @ ..]) => Some(Self::Theory {
+                name: as_atomic(name)?.clone(),
+                info: optional_info(info)?,
+            }),
+            ("creator", [name, info @ ..]) => Some(Self::Creator {
+                name: as_atomic(name)?.clone(),
+                info: optional_info(info)?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// the outermost inference rule, if this is an inference
+    pub fn rule(&self) -> Option<&AtomicWord<'a>> {
+        match self {
+            Self::Inference(inference) => Some(&inference.rule),
+            _ => None,
+        }
+    }
+
+    /// the SZS status of the outermost inference, e.g. `thm`
+    pub fn status(&self) -> Option<&AtomicWord<'a>> {
+        match self {
+            Self::Inference(inference) => inference.status(),
+            _ => None,
+        }
+    }
+
+    /// the names of all annotated formulas this source refers to
+    ///
+    /// Nested inferences are searched recursively.
+    /// Names are in order of appearance and may repeat.
+    pub fn parents(&self) -> Vec<Name<'a>> {
+        let mut parents = Vec::new();
+        self.collect_parents(&mut parents);
+        parents
+    }
+
+    fn collect_parents(&self, parents: &mut Vec<Name<'a>>) {
+        match self {
+            Self::Name(name) => parents.push(name.clone()),
+            Self::Inference(inference) => {
+                for parent in &inference.parents {
+                    parent.source.collect_parents(parents);
+                }
+            }
+            Self::Sources(sources) => {
+                for source in sources {
+                    source.collect_parents(parents);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Parent<'a> {
+    fn from_general_term(term: &GeneralTerm<'a>) -> Self {
+        match term {
+            GeneralTerm::Colon(data, details) => {
+                if let GeneralTerm::List(list) = &**details {
+                    let data = GeneralTerm::Data(data.clone());
+                    return Self {
+                        source: SourceKind::from_general_term(&data),
+                        details: Some(list.clone()),
+                    };
+                }
+                Self {
+                    source: SourceKind::Other(term.clone()),
+                    details: None,
+                }
+            }
+            _ => Self {
+                source: SourceKind::from_general_term(term),
+                details: None,
+            },
+        }
+    }
+}
+
+impl<'a> Source<'a> {
+    /// a structured view of this source
+    pub fn kind(&self) -> SourceKind<'a> {
+        SourceKind::from_general_term(&self.0)
+    }
+}
+
+impl<'a> Annotations<'a> {
+    /// the source, if present
+    pub fn source(&self) -> Option<&Source<'a>> {
+        self.0.as_ref().map(|boxed| &boxed.0)
+    }
+
+    /// the useful-info list following the source, if present
+    pub fn useful_info(&self) -> &[GeneralTerm<'a>] {
+        match &self.0 {
+            Some(boxed) => match &(boxed.1).0 {
+                Some(info) => items(&info.0),
+                None => &[],
+            },
+            None => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn kind(input: &[u8]) -> SourceKind<'_> {
+        check_parse(Source::parse, input).kind()
+    }
+
+    fn strings<T: ToString>(items: &[T]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_inference() {
+        let source = kind(
+            b"inference(rw, [status(thm)], [inference(cn, [status(esa)], [c_0_1, 2]), c_0_3:[bind(X, $fot(a))]])\0",
+        );
+        assert_eq!(source.rule().unwrap().to_string(), "rw");
+        assert_eq!(source.status().unwrap().to_string(), "thm");
+        assert_eq!(strings(&source.parents()), ["c_0_1", "2", "c_0_3"]);
+        match source {
+            SourceKind::Inference(inference) => {
+                assert_eq!(strings(&inference.info), ["status(thm)"]);
+                assert_eq!(inference.parents.len(), 2);
+                assert!(inference.parents[0].details.is_none());
+                assert_eq!(
+                    inference.parents[1].details.as_ref().unwrap().to_string(),
+                    "[bind(X,$fot(a))]"
+                );
+            }
+            _ => panic!("not an inference"),
+        }
+    }
+
+    #[test]
+    fn test_external() {
+        assert_eq!(
+            kind(b"file('SYN000+1.p', ax1)\0"),
+            SourceKind::File {
+                file_name: check_parse(FileName::parse, b"'SYN000+1.p'\0"),
+                name: Some(check_parse(Name::parse, b"ax1\0")),
+            }
+        );
+        match kind(b"theory(equality, [symmetry])\0") {
+            SourceKind::Theory { name, info } => {
+                assert_eq!(name.to_string(), "equality");
+                assert_eq!(strings(&info), ["symmetry"]);
+            }
+            _ => panic!("not a theory"),
+        }
+        match kind(b"creator(vampire)\0") {
+            SourceKind::Creator { name, info } => {
+                assert_eq!(name.to_string(), "vampire");
+                assert!(info.is_empty());
+            }
+            _ => panic!("not a creator"),
+        }
+    }
+
+    #[test]
+    fn test_other_sources() {
+        match kind(b"introduced(definition, [new_symbols(naming, [sP0])])\0") {
+            SourceKind::Introduced { intro_type, info } => {
+                assert_eq!(intro_type.to_string(), "definition");
+                assert_eq!(strings(&info), ["new_symbols(naming,[sP0])"]);
+            }
+            _ => panic!("not introduced"),
+        }
+        assert_eq!(kind(b"unknown\0"), SourceKind::Unknown);
+        assert_eq!(strings(&kind(b"[a, 1]\0").parents()), ["a", "1"]);
+        assert!(matches!(kind(b"inference(r)\0"), SourceKind::Other(_)));
+    }
+
+    #[test]
+    fn test_annotations() {
+        let annotated = check_parse(
+            AnnotatedFormula::parse,
+            b"cnf(c, plain, p, inference(r, [], [a]), [x, y]).\0",
+        );
+        assert_eq!(annotated.name().to_string(), "c");
+        assert_eq!(annotated.role().to_string(), "plain");
+        let annotations = annotated.annotations();
+        let source = annotations.source().unwrap().kind();
+        assert_eq!(strings(&source.parents()), ["a"]);
+        assert_eq!(strings(annotations.useful_info()), ["x", "y"]);
+    }
+}
@@ -0,0 +1,749 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::common::*;
+use crate::fof;
+
+/// the replacement for a variable, if any
+pub(crate) type Lookup<'a, 'l> =
+    dyn Fn(&Variable<'a>) -> Option<fof::Term<'a>> + 'l;
+
+fn apply_arguments<'a>(
+    arguments: &fof::Arguments<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> Box<fof::Arguments<'a>> {
+    let arguments = arguments
+        .0
+        .iter()
+        .map(|term| apply_term(term, lookup))
+        .collect();
+    Box::new(fof::Arguments(arguments))
+}
+
+fn apply_plain<'a>(
+    term: &fof::PlainTerm<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> fof::PlainTerm<'a> {
+    match term {
+        fof::PlainTerm::Function(functor, arguments) => {
+            let arguments = apply_arguments(arguments, lookup);
+            fof::PlainTerm::Function(functor.clone(), arguments)
+        }
+        constant => constant.clone(),
+    }
+}
+
+fn apply_defined<'a>(
+    term: &fof::DefinedPlainTerm<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> fof::DefinedPlainTerm<'a> {
+    match term {
+        fof::DefinedPlainTerm::Function(functor, arguments) => {
+            let arguments = apply_arguments(arguments, lookup);
+            fof::DefinedPlainTerm::Function(functor.clone(), arguments)
+        }
+        constant => constant.clone(),
+    }
+}
+
+fn apply_system<'a>(
+    term: &fof::SystemTerm<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> fof::SystemTerm<'a> {
+    match term {
+        fof::SystemTerm::Function(functor, arguments) => {
+            let arguments = apply_arguments(arguments, lookup);
+            fof::SystemTerm::Function(functor.clone(), arguments)
+        }
+        constant => constant.clone(),
+    }
+}
+
+pub(crate) fn apply_term<'a>(
+    term: &fof::Term<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> fof::Term<'a> {
+    let function = match term {
+        fof::Term::Variable(variable) => {
+            return lookup(variable).unwrap_or_else(|| term.clone())
+        }
+        fof::Term::Function(function) => function,
+    };
+    let function = match &**function {
+        fof::FunctionTerm::Plain(term) => {
+            fof::FunctionTerm::Plain(apply_plain(term, lookup))
+        }
+        fof::FunctionTerm::System(term) => {
+            fof::FunctionTerm::System(apply_system(term, lookup))
+        }
+        fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(
+            fof::DefinedAtomicTerm(term),
+        )) => fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(
+            fof::DefinedAtomicTerm(apply_defined(term, lookup)),
+        )),
+        defined => defined.clone(),
+    };
+    fof::Term::Function(Box::new(function))
+}
+
+pub(crate) fn apply_atom<'a>(
+    atom: &fof::AtomicFormula<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> fof::AtomicFormula<'a> {
+    use fof::{AtomicFormula, DefinedAtomicFormula};
+    match atom {
+        AtomicFormula::Plain(plain) => AtomicFormula::Plain(
+            fof::PlainAtomicFormula(apply_plain(&plain.0, lookup)),
+        ),
+        AtomicFormula::Defined(DefinedAtomicFormula::Plain(plain)) => {
+            AtomicFormula::Defined(DefinedAtomicFormula::Plain(
+                fof::DefinedPlainFormula(apply_defined(&plain.0, lookup)),
+            ))
+        }
+        AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix)) => {
+            AtomicFormula::Defined(DefinedAtomicFormula::Infix(
+                fof::DefinedInfixFormula {
+                    left: Box::new(apply_term(&infix.left, lookup)),
+                    op: infix.op,
+                    right: Box::new(apply_term(&infix.right, lookup)),
+                },
+            ))
+        }
+        AtomicFormula::System(system) => AtomicFormula::System(
+            fof::SystemAtomicFormula(apply_system(&system.0, lookup)),
+        ),
+    }
+}
+
+pub(crate) fn apply_literal<'a>(
+    literal: &cnf::Literal<'a>,
+    lookup: &Lookup<'a, '_>,
+) -> cnf::Literal<'a> {
+    match literal {
+        cnf::Literal::Atomic(atom) => {
+            cnf::Literal::Atomic(apply_atom(atom, lookup))
+        }
+        cnf::Literal::NegatedAtomic(atom) => {
+            cnf::Literal::NegatedAtomic(apply_atom(atom, lookup))
+        }
+        cnf::Literal::Infix(infix) => cnf::Literal::Infix(fof::InfixUnary {
+            left: Box::new(apply_term(&infix.left, lookup)),
+            op: infix.op,
+            right: Box::new(apply_term(&infix.right, lookup)),
+        }),
+    }
+}
+
+/// `functor(X, ...)`, or `functor` if there are no variables
+pub(crate) fn application<'a>(
+    functor: Functor<'a>,
+    variables: &[Variable<'a>],
+) -> fof::PlainTerm<'a> {
+    if variables.is_empty() {
+        return fof::PlainTerm::Constant(Constant(functor));
+    }
+    let arguments = variables.iter().cloned().map(fof::Term::Variable);
+    let arguments = fof::Arguments(arguments.collect());
+    fof::PlainTerm::Function(functor, Box::new(arguments))
+}
+
+/// the head symbol of a term or atom
+#[derive(PartialEq, Eq)]
+pub(crate) enum Head<'t, 'a> {
+    Plain(&'t Functor<'a>),
+    Defined(&'t DefinedFunctor<'a>),
+    System(&'t SystemFunctor<'a>),
+    Value(&'t DefinedTerm<'a>),
+    Equality,
+}
+
+/// the arguments of a term or atom
+pub(crate) enum Arguments<'t, 'a> {
+    Slice(&'t [fof::Term<'a>]),
+    Pair([&'t fof::Term<'a>; 2]),
+}
+
+impl<'t, 'a> Arguments<'t, 'a> {
+    pub(crate) fn iter<'s>(
+        &'s self,
+    ) -> impl Iterator<Item = &'t fof::Term<'a>> + 's {
+        let (slice, pair): (&[fof::Term], &[&fof::Term]) = match self {
+            Arguments::Slice(slice) => (slice, &[]),
+            Arguments::Pair(pair) => (&[], pair),
+        };
+        slice.iter().chain(pair.iter().copied())
+    }
+}
+
+const NO_ARGUMENTS: Arguments<'static, 'static> = Arguments::Slice(&[]);
+
+fn plain_view<'t, 'a>(
+    term: &'t fof::PlainTerm<'a>,
+) -> (Head<'t, 'a>, Arguments<'t, 'a>) {
+    match term {
+        fof::PlainTerm::Constant(constant) => {
+            (Head::Plain(&constant.0), NO_ARGUMENTS)
+        }
+        fof::PlainTerm::Function(functor, arguments) => {
+            (Head::Plain(functor), Arguments::Slice(&arguments.0))
+        }
+    }
+}
+
+fn defined_view<'t, 'a>(
+    term: &'t fof::DefinedPlainTerm<'a>,
+) -> (Head<'t, 'a>, Arguments<'t, 'a>) {
+    match term {
+        fof::DefinedPlainTerm::Constant(constant) => {
+            (Head::Defined(&constant.0), NO_ARGUMENTS)
+        }
+        fof::DefinedPlainTerm::Function(functor, arguments) => {
+            (Head::Defined(functor), Arguments::Slice(&arguments.0))
+        }
+    }
+}
+
+fn system_view<'t, 'a>(
+    term: &'t fof::SystemTerm<'a>,
+) -> (Head<'t, 'a>, Arguments<'t, 'a>) {
+    match term {
+        fof::SystemTerm::Constant(constant) => {
+            (Head::System(&constant.0), NO_ARGUMENTS)
+        }
+        fof::SystemTerm::Function(functor, arguments) => {
+            (Head::System(functor), Arguments::Slice(&arguments.0))
+        }
+    }
+}
+
+pub(crate) fn function_view<'t, 'a>(
+    function: &'t fof::FunctionTerm<'a>,
+) -> (Head<'t, 'a>, Arguments<'t, 'a>) {
+    match function {
+        fof::FunctionTerm::Plain(plain) => plain_view(plain),
+        fof::FunctionTerm::System(system) => system_view(system),
+        fof::FunctionTerm::Defined(fof::DefinedTerm::Atomic(defined)) => {
+            defined_view(&defined.0)
+        }
+        fof::FunctionTerm::Defined(fof::DefinedTerm::Defined(value)) => {
+            (Head::Value(value), NO_ARGUMENTS)
+        }
+    }
+}
+
+pub(crate) fn atom_view<'t, 'a>(
+    atom: &'t fof::AtomicFormula<'a>,
+) -> (Head<'t, 'a>, Arguments<'t, 'a>) {
+    use fof::{AtomicFormula, DefinedAtomicFormula};
+    match atom {
+        AtomicFormula::Plain(plain) => plain_view(&plain.0),
+        AtomicFormula::Defined(DefinedAtomicFormula::Plain(defined)) => {
+            defined_view(&defined.0)
+        }
+        AtomicFormula::Defined(DefinedAtomicFormula::Infix(infix)) => {
+            (Head::Equality, Arguments::Pair([&infix.left, &infix.right]))
+        }
+        AtomicFormula::System(system) => system_view(&system.0),
+    }
+}
+
+/// the polarity, head and arguments of a literal
+fn literal_view<'t, 'a>(
+    literal: &'t cnf::Literal<'a>,
+) -> (bool, Head<'t, 'a>, Arguments<'t, 'a>) {
+    match literal {
+        cnf::Literal::Atomic(atom) => {
+            let (head, arguments) = atom_view(atom);
+            (true, head, arguments)
+        }
+        cnf::Literal::NegatedAtomic(atom) => {
+            let (head, arguments) = atom_view(atom);
+            (false, head, arguments)
+        }
+        cnf::Literal::Infix(infix) => {
+            let arguments = Arguments::Pair([&infix.left, &infix.right]);
+            (false, Head::Equality, arguments)
+        }
+    }
+}
+
+/// a substitution of terms for variables
+///
+/// Substitutions are applied simultaneously: bindings are not applied to each other.
+/// Substitutions computed by unification are idempotent, so this makes no difference for them.
+#[derive(Clone, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Substitution<'a>(pub BTreeMap<Variable<'a>, fof::Term<'a>>);
+
+impl<'a> Substitution<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the term bound to `variable`, if any
+    pub fn get(&self, variable: &Variable<'a>) -> Option<&fof::Term<'a>> {
+        self.0.get(variable)
+    }
+
+    /// bind `variable` to `term`, returning the previous binding
+    ///
+    /// There is no occurs check: if `term` contains `variable`, unification will not expand the binding inside itself.
+    pub fn insert(
+        &mut self,
+        variable: Variable<'a>,
+        term: fof::Term<'a>,
+    ) -> Option<fof::Term<'a>> {
+        self.0.insert(variable, term)
+    }
+
+    /// the number of bound variables
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// whether no variables are bound
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// bound variables and their terms, ordered by variable
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (&Variable<'a>, &fof::Term<'a>)> {
+        self.0.iter()
+    }
+
+    /// apply the substitution to a term
+    pub fn apply(&self, term: &fof::Term<'a>) -> fof::Term<'a> {
+        apply_term(term, &|variable| self.0.get(variable).cloned())
+    }
+
+    /// apply the substitution to an atom
+    pub fn apply_atom(
+        &self,
+        atom: &fof::AtomicFormula<'a>,
+    ) -> fof::AtomicFormula<'a> {
+        apply_atom(atom, &|variable| self.0.get(variable).cloned())
+    }
+
+    /// apply the substitution to a literal
+    pub fn apply_literal(
+        &self,
+        literal: &cnf::Literal<'a>,
+    ) -> cnf::Literal<'a> {
+        apply_literal(literal, &|variable| self.0.get(variable).cloned())
+    }
+
+    /// apply the substitution to a term, repeatedly until no bound variables remain
+    ///
+    /// Variables in `expanding` are left alone, so that cyclic bindings such as `X -> f(X)` terminate.
+    fn resolve<'s>(
+        &'s self,
+        term: &fof::Term<'a>,
+        expanding: &[&'s Variable<'a>],
+    ) -> fof::Term<'a> {
+        apply_term(term, &|variable| {
+            if expanding.contains(&variable) {
+                return None;
+            }
+            let (variable, bound) = self.0.get_key_value(variable)?;
+            let mut expanding = expanding.to_vec();
+            expanding.push(variable);
+            Some(self.resolve(bound, &expanding))
+        })
+    }
+
+    /// whether `variable` occurs in `term` under the current bindings
+    ///
+    /// Bindings in `expanding` are not followed again, so cyclic bindings terminate.
+    fn occurs<'s>(
+        &'s self,
+        variable: &Variable<'a>,
+        term: &fof::Term<'a>,
+        expanding: &[&'s Variable<'a>],
+    ) -> bool {
+        match term {
+            fof::Term::Variable(other) => match self.0.get_key_value(other) {
+                Some(_) if expanding.contains(&other) => false,
+                Some((other, bound)) => {
+                    let mut expanding = expanding.to_vec();
+                    expanding.push(other);
+                    self.occurs(variable, bound, &expanding)
+                }
+                None => other == variable,
+            },
+            fof::Term::Function(function) => {
+                let (_, arguments) = function_view(function);
+                let occurs = arguments.iter().any(|argument| {
+                    self.occurs(variable, argument, expanding)
+                });
+                occurs
+            }
+        }
+    }
+
+    fn unify_arguments(
+        &mut self,
+        left: Arguments<'_, 'a>,
+        right: Arguments<'_, 'a>,
+        trail: &mut Vec<Variable<'a>>,
+    ) -> bool {
+        left.iter().count() == right.iter().count()
+            && left
+                .iter()
+                .zip(right.iter())
+                .all(|(left, right)| self.unify_terms(left, right, trail))
+    }
+
+    /// unify without the occurs check failing or restoring bindings
+    fn unify_terms(
+        &mut self,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+        trail: &mut Vec<Variable<'a>>,
+    ) -> bool {
+        for (term, other) in [(left, right), (right, left)] {
+            if let fof::Term::Variable(variable) = term {
+                if let Some(bound) = self.0.get(variable) {
+                    let bound = bound.clone();
+                    return self.unify_terms(&bound, other, trail);
+                }
+            }
+        }
+        match (left, right) {
+            (fof::Term::Variable(left), fof::Term::Variable(right))
+                if left == right =>
+            {
+                true
+            }
+            (fof::Term::Variable(variable), term)
+            | (term, fof::Term::Variable(variable)) => {
+                if self.occurs(variable, term, &[]) {
+                    return false;
+                }
+                self.0.insert(variable.clone(), term.clone());
+                trail.push(variable.clone());
+                true
+            }
+            (fof::Term::Function(left), fof::Term::Function(right)) => {
+                let (left_head, left) = function_view(left);
+                let (right_head, right) = function_view(right);
+                left_head == right_head
+                    && self.unify_arguments(left, right, trail)
+            }
+        }
+    }
+
+    /// run `step`, keeping its bindings only if it succeeds
+    ///
+    /// Returns the newly-bound variables on success.
+    fn transaction(
+        &mut self,
+        step: impl FnOnce(&mut Self, &mut Vec<Variable<'a>>) -> bool,
+    ) -> Option<Vec<Variable<'a>>> {
+        let mut trail = vec![];
+        if step(self, &mut trail) {
+            return Some(trail);
+        }
+        for variable in trail {
+            self.0.remove(&variable);
+        }
+        None
+    }
+
+    /// resolve the variables in `trail`, newly bound by unification, and substitute them into the other bindings
+    ///
+    /// Other bindings are not resolved against each other, so a substitution made by matching keeps its meaning.
+    fn normalise(&mut self, trail: &[Variable<'a>]) {
+        let resolved: BTreeMap<_, _> = trail
+            .iter()
+            .map(|variable| {
+                let (variable, bound) = self
+                    .0
+                    .get_key_value(variable)
+                    .expect("trail variables are bound");
+                (variable.clone(), self.resolve(bound, &[variable]))
+            })
+            .collect();
+        for (variable, term) in &mut self.0 {
+            *term = match resolved.get(variable) {
+                Some(resolved) => resolved.clone(),
+                None => apply_term(term, &|variable| {
+                    resolved.get(variable).cloned()
+                }),
+            };
+        }
+    }
+
+    /// run a unification `step` as a transaction, normalising on success
+    fn unification(
+        &mut self,
+        step: impl FnOnce(&mut Self, &mut Vec<Variable<'a>>) -> bool,
+    ) -> bool {
+        match self.transaction(step) {
+            Some(trail) => {
+                self.normalise(&trail);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// extend the substitution to a most general unifier of `left` and `right`
+    ///
+    /// If there is no unifier, the substitution is unchanged and `false` is returned.
+    pub fn unify(
+        &mut self,
+        left: &fof::Term<'a>,
+        right: &fof::Term<'a>,
+    ) -> bool {
+        self.unification(|substitution, trail| {
+            substitution.unify_terms(left, right, trail)
+        })
+    }
+
+    /// extend the substitution to a most general unifier of two atoms
+    ///
+    /// Equations `l = r` are unified as if written `=(l, r)`, so symmetry is not taken into account.
+    pub fn unify_atoms(
+        &mut self,
+        left: &fof::AtomicFormula<'a>,
+        right: &fof::AtomicFormula<'a>,
+    ) -> bool {
+        let (left_head, left) = atom_view(left);
+        let (right_head, right) = atom_view(right);
+        if left_head != right_head {
+            return false;
+        }
+        self.unification(|substitution, trail| {
+            substitution.unify_arguments(left, right, trail)
+        })
+    }
+
+    /// extend the substitution to a most general unifier of two literals of the same polarity
+    pub fn unify_literals(
+        &mut self,
+        left: &cnf::Literal<'a>,
+        right: &cnf::Literal<'a>,
+    ) -> bool {
+        let (left_polarity, left_head, left) = literal_view(left);
+        let (right_polarity, right_head, right) = literal_view(right);
+        if left_polarity != right_polarity || left_head != right_head {
+            return false;
+        }
+        self.unification(|substitution, trail| {
+            substitution.unify_arguments(left, right, trail)
+        })
+    }
+
+    fn match_arguments(
+        &mut self,
+        pattern: Arguments<'_, 'a>,
+        target: Arguments<'_, 'a>,
+        trail: &mut Vec<Variable<'a>>,
+    ) -> bool {
+        pattern.iter().count() == target.iter().count()
+            && pattern.iter().zip(target.iter()).all(|(pattern, target)| {
+                self.match_terms(pattern, target, trail)
+            })
+    }
+
+    fn match_terms(
+        &mut self,
+        pattern: &fof::Term<'a>,
+        target: &fof::Term<'a>,
+        trail: &mut Vec<Variable<'a>>,
+    ) -> bool {
+        match (pattern, target) {
+            (fof::Term::Variable(variable), _) => {
+                if let Some(bound) = self.0.get(variable) {
+                    return bound == target;
+                }
+                self.0.insert(variable.clone(), target.clone());
+                trail.push(variable.clone());
+                true
+            }
+            (fof::Term::Function(pattern), fof::Term::Function(target)) => {
+                let (pattern_head, pattern) = function_view(pattern);
+                let (target_head, target) = function_view(target);
+                pattern_head == target_head
+                    && self.match_arguments(pattern, target, trail)
+            }
+            (fof::Term::Function(_), fof::Term::Variable(_)) => false,
+        }
+    }
+
+    /// extend the substitution so that it maps `pattern` to `target`
+    ///
+    /// Variables in `target` are treated as constants.
+    /// If there is no such extension, the substitution is unchanged and `false` is returned.
+    pub fn match_term(
+        &mut self,
+        pattern: &fof::Term<'a>,
+        target: &fof::Term<'a>,
+    ) -> bool {
+        self.transaction(|substitution, trail| {
+            substitution.match_terms(pattern, target, trail)
+        })
+        .is_some()
+    }
+
+    /// extend the substitution so that it maps one atom to another
+    pub fn match_atom(
+        &mut self,
+        pattern: &fof::AtomicFormula<'a>,
+        target: &fof::AtomicFormula<'a>,
+    ) -> bool {
+        let (pattern_head, pattern) = atom_view(pattern);
+        let (target_head, target) = atom_view(target);
+        pattern_head == target_head
+            && self
+                .transaction(|substitution, trail| {
+                    substitution.match_arguments(pattern, target, trail)
+                })
+                .is_some()
+    }
+
+    /// extend the substitution so that it maps one literal to another of the same polarity
+    pub fn match_literal(
+        &mut self,
+        pattern: &cnf::Literal<'a>,
+        target: &cnf::Literal<'a>,
+    ) -> bool {
+        let (pattern_polarity, pattern_head, pattern) = literal_view(pattern);
+        let (target_polarity, target_head, target) = literal_view(target);
+        pattern_polarity == target_polarity
+            && pattern_head == target_head
+            && self
+                .transaction(|substitution, trail| {
+                    substitution.match_arguments(pattern, target, trail)
+                })
+                .is_some()
+    }
+}
+
+/// a most general unifier of two terms, if they are unifiable
+pub fn unify<'a>(
+    left: &fof::Term<'a>,
+    right: &fof::Term<'a>,
+) -> Option<Substitution<'a>> {
+    let mut substitution = Substitution::new();
+    if substitution.unify(left, right) {
+        Some(substitution)
+    } else {
+        None
+    }
+}
+
+/// the substitution mapping `pattern` to `target`, if there is one
+pub fn matching<'a>(
+    pattern: &fof::Term<'a>,
+    target: &fof::Term<'a>,
+) -> Option<Substitution<'a>> {
+    let mut substitution = Substitution::new();
+    if substitution.match_term(pattern, target) {
+        Some(substitution)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn term(input: &[u8]) -> fof::Term<'_> {
+        check_parse(fof::Term::parse, input)
+    }
+
+    fn show(substitution: &Substitution) -> Vec<String> {
+        substitution
+            .iter()
+            .map(|(variable, term)| alloc::format!("{}->{}", variable, term))
+            .collect()
+    }
+
+    #[test]
+    fn test_unify() {
+        let left = term(b"f(X, g(Y), Y)\0");
+        let right = term(b"f(g(Z), Z, a)\0");
+        let unifier = unify(&left, &right).expect("unifiable");
+        assert_eq!(show(&unifier), ["X->g(g(a))", "Y->a", "Z->g(a)"]);
+        assert_eq!(unifier.apply(&left), unifier.apply(&right));
+
+        assert!(unify(&term(b"X\0"), &term(b"f(X)\0")).is_none());
+        assert!(unify(&term(b"f(X, X)\0"), &term(b"f(a, b)\0")).is_none());
+        assert!(unify(&term(b"$sum(X, 1)\0"), &term(b"f(X, 1)\0")).is_none());
+        assert!(
+            unify(&term(b"$sum(X, 1)\0"), &term(b"$sum(2, Y)\0")).is_some()
+        );
+        assert!(unify(&term(b"1\0"), &term(b"2\0")).is_none());
+
+        let mut substitution = Substitution::new();
+        assert!(substitution.unify(&term(b"X\0"), &term(b"f(Y)\0")));
+        assert!(!substitution.unify(&term(b"Y\0"), &term(b"g(X)\0")));
+        assert_eq!(show(&substitution), ["X->f(Y)"]);
+    }
+
+    #[test]
+    fn test_matching() {
+        let pattern = term(b"f(X, g(Y), X)\0");
+        let matcher = matching(&pattern, &term(b"f(h(Y), g(a), h(Y))\0"))
+            .expect("match");
+        assert_eq!(show(&matcher), ["X->h(Y)", "Y->a"]);
+        assert!(matching(&pattern, &term(b"f(a, g(b), c)\0")).is_none());
+        assert!(matching(&term(b"f(a)\0"), &term(b"f(X)\0")).is_none());
+    }
+
+    #[test]
+    fn test_cyclic_bindings() {
+        let mut matcher =
+            matching(&term(b"X\0"), &term(b"f(X)\0")).expect("match");
+        assert!(matcher.unify(&term(b"Y\0"), &term(b"a\0")));
+        assert_eq!(show(&matcher), ["X->f(X)", "Y->a"]);
+
+        let mut matcher =
+            matching(&term(b"g(X, Y)\0"), &term(b"g(h(Y), Y)\0"))
+                .expect("match");
+        assert!(matcher.unify(&term(b"Z\0"), &term(b"b\0")));
+        assert_eq!(show(&matcher), ["X->h(Y)", "Y->Y", "Z->b"]);
+
+        let mut substitution = Substitution::new();
+        substitution.insert(Variable(UpperWord("X")), term(b"f(X)\0"));
+        assert!(substitution.unify(&term(b"Y\0"), &term(b"g(X)\0")));
+        assert!(!substitution.unify(&term(b"X\0"), &term(b"a\0")));
+        assert_eq!(show(&substitution), ["X->f(X)", "Y->g(f(X))"]);
+    }
+
+    #[test]
+    fn test_literals() {
+        let literal = |input| check_parse(cnf::Literal::parse, input);
+        let mut substitution = Substitution::new();
+        assert!(substitution
+            .unify_literals(&literal(b"~p(X, a)\0"), &literal(b"~p(b, Y)\0")));
+        assert_eq!(show(&substitution), ["X->b", "Y->a"]);
+        assert!(!Substitution::new()
+            .unify_literals(&literal(b"p(X)\0"), &literal(b"~p(X)\0")));
+        let mut substitution = Substitution::new();
+        assert!(substitution.match_literal(
+            &literal(b"X != f(Y)\0"),
+            &literal(b"a != f(b)\0")
+        ));
+        assert_eq!(
+            substitution
+                .apply_literal(&literal(b"X != f(Y)\0"))
+                .to_string(),
+            "a!=f(b)"
+        );
+        assert!(!Substitution::new()
+            .match_literal(&literal(b"X = Y\0"), &literal(b"a != b\0")));
+    }
+}
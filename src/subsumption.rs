@@ -0,0 +1,300 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::cnf;
+use crate::fof;
+use crate::nnf::truth_value;
+use crate::subst::Substitution;
+use crate::top::*;
+
+/// the truth value of `literal`, if it is `$true`, `$false` or a negation of either
+fn truth(literal: &cnf::Literal) -> Option<bool> {
+    let (positive, atom) = match literal {
+        cnf::Literal::Atomic(atom) => (true, atom),
+        cnf::Literal::NegatedAtomic(atom) => (false, atom),
+        cnf::Literal::Infix(_) => return None,
+    };
+    match atom {
+        fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Plain(
+            fof::DefinedPlainFormula(fof::DefinedPlainTerm::Constant(
+                constant,
+            )),
+        )) => truth_value(constant).map(|word| (word == "true") == positive),
+        _ => None,
+    }
+}
+
+/// the polarity and sides of an equational literal
+fn equation<'t, 'a>(
+    literal: &'t cnf::Literal<'a>,
+) -> Option<(bool, &'t fof::Term<'a>, &'t fof::Term<'a>)> {
+    match literal {
+        cnf::Literal::Atomic(fof::AtomicFormula::Defined(
+            fof::DefinedAtomicFormula::Infix(infix),
+        )) => Some((true, &infix.left, &infix.right)),
+        cnf::Literal::NegatedAtomic(fof::AtomicFormula::Defined(
+            fof::DefinedAtomicFormula::Infix(infix),
+        )) => Some((false, &infix.left, &infix.right)),
+        cnf::Literal::Infix(infix) => Some((false, &infix.left, &infix.right)),
+        _ => None,
+    }
+}
+
+/// whether two literals are syntactically complementary, up to symmetry of equality
+fn complementary(left: &cnf::Literal, right: &cnf::Literal) -> bool {
+    if let (Some(left), Some(right)) = (equation(left), equation(right)) {
+        let (left_positive, s, t) = left;
+        let (right_positive, u, v) = right;
+        return left_positive != right_positive
+            && ((s == u && t == v) || (s == v && t == u));
+    }
+    match (left, right) {
+        (
+            cnf::Literal::Atomic(positive),
+            cnf::Literal::NegatedAtomic(negative),
+        )
+        | (
+            cnf::Literal::NegatedAtomic(negative),
+            cnf::Literal::Atomic(positive),
+        ) => positive == negative,
+        _ => false,
+    }
+}
+
+/// whether `clause` is a tautology
+///
+/// A clause is a tautology if it contains `$true`, `~$false`, an equation `t = t`, or complementary literals.
+/// Only syntactic identity is detected, up to symmetry of equality.
+pub fn is_tautology(clause: &cnf::Disjunction) -> bool {
+    let literals = &clause.0;
+    literals.iter().enumerate().any(|(index, literal)| {
+        truth(literal) == Some(true)
+            || matches!(equation(literal), Some((true, s, t)) if s == t)
+            || literals[index + 1..]
+                .iter()
+                .any(|other| complementary(literal, other))
+    })
+}
+
+/// an equational literal with its sides swapped
+fn flip<'a>(literal: &cnf::Literal<'a>) -> Option<cnf::Literal<'a>> {
+    let flip_atom = |atom: &fof::AtomicFormula<'a>| match atom {
+        fof::AtomicFormula::Defined(fof::DefinedAtomicFormula::Infix(
+            infix,
+        )) => Some(fof::AtomicFormula::Defined(
+            fof::DefinedAtomicFormula::Infix(fof::DefinedInfixFormula {
+                left: infix.right.clone(),
+                op: infix.op,
+                right: infix.left.clone(),
+            }),
+        )),
+        _ => None,
+    };
+    match literal {
+        cnf::Literal::Atomic(atom) => {
+            flip_atom(atom).map(cnf::Literal::Atomic)
+        }
+        cnf::Literal::NegatedAtomic(atom) => {
+            flip_atom(atom).map(cnf::Literal::NegatedAtomic)
+        }
+        cnf::Literal::Infix(infix) => {
+            Some(cnf::Literal::Infix(fof::InfixUnary {
+                left: Box::new((*infix.right).clone()),
+                op: infix.op,
+                right: Box::new((*infix.left).clone()),
+            }))
+        }
+    }
+}
+
+/// match `patterns[next..]` injectively into `targets`
+fn match_literals<'a>(
+    patterns: &[(cnf::Literal<'a>, Option<cnf::Literal<'a>>)],
+    targets: &[cnf::Literal<'a>],
+    used: &mut [bool],
+    substitution: &Substitution<'a>,
+    next: usize,
+) -> bool {
+    let (pattern, flipped) = match patterns.get(next) {
+        Some(pattern) => pattern,
+        None => return true,
+    };
+    for (index, target) in targets.iter().enumerate() {
+        if used[index] {
+            continue;
+        }
+        for pattern in core::iter::once(pattern).chain(flipped) {
+            let mut extended = substitution.clone();
+            if !extended.match_literal(pattern, target) {
+                continue;
+            }
+            used[index] = true;
+            if match_literals(patterns, targets, used, &extended, next + 1) {
+                return true;
+            }
+            used[index] = false;
+        }
+    }
+    false
+}
+
+/// whether `general` subsumes `specific`
+///
+/// This is the case if there is a substitution mapping the literals of `general` to distinct literals of `specific`, up to symmetry of equality.
+/// Variables in `specific` are treated as constants, so the clauses may share variable names.
+pub fn subsumes(
+    general: &cnf::Disjunction,
+    specific: &cnf::Disjunction,
+) -> bool {
+    if general.0.len() > specific.0.len() {
+        return false;
+    }
+    let patterns: Vec<_> = general
+        .0
+        .iter()
+        .map(|literal| (literal.clone(), flip(literal)))
+        .collect();
+    let mut used = vec![false; specific.0.len()];
+    let substitution = Substitution::new();
+    match_literals(&patterns, &specific.0, &mut used, &substitution, 0)
+}
+
+/// why a clause is redundant
+#[derive(
+    Clone, Copy, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Redundant {
+    /// the clause is a tautology
+    #[display(fmt = "tautology")]
+    Tautology,
+    /// the clause is subsumed by the clause at this index
+    #[display(fmt = "subsumed by clause {}", _0)]
+    Subsumed(usize),
+}
+
+/// find redundant clauses in a set of clauses
+///
+/// Clauses are redundant if they are tautologies, or subsumed by another clause which is not redundant.
+/// Where clauses subsume each other, the first is kept.
+pub fn redundant_clauses(
+    clauses: &[&cnf::Disjunction],
+) -> Vec<Option<Redundant>> {
+    let mut order: Vec<usize> = (0..clauses.len()).collect();
+    order.sort_by_key(|index| clauses[*index].0.len());
+    let mut redundant = vec![None; clauses.len()];
+    let mut kept: Vec<usize> = vec![];
+    for index in order {
+        let clause = clauses[index];
+        redundant[index] = if is_tautology(clause) {
+            Some(Redundant::Tautology)
+        } else {
+            kept.iter()
+                .find(|general| subsumes(clauses[**general], clause))
+                .map(|general| Redundant::Subsumed(*general))
+        };
+        if redundant[index].is_none() {
+            kept.push(index);
+        }
+    }
+    redundant
+}
+
+fn disjunction<'t, 'a>(
+    annotated: &'t AnnotatedFormula<'a>,
+) -> Option<&'t cnf::Disjunction<'a>> {
+    match annotated {
+        AnnotatedFormula::Cnf(cnf) => match &*cnf.0.formula {
+            cnf::Formula::Disjunction(disjunction)
+            | cnf::Formula::Parenthesised(disjunction) => Some(disjunction),
+        },
+        _ => None,
+    }
+}
+
+/// remove redundant CNF clauses from a problem
+///
+/// Other formulas are kept, and the order of the problem is preserved.
+pub fn remove_redundant<'a>(
+    formulas: Vec<AnnotatedFormula<'a>>,
+) -> Vec<AnnotatedFormula<'a>> {
+    let clauses: Vec<_> = formulas.iter().filter_map(disjunction).collect();
+    let mut redundant = redundant_clauses(&clauses).into_iter();
+    formulas
+        .into_iter()
+        .filter(|annotated| {
+            disjunction(annotated).is_none()
+                || redundant.next().flatten().is_none()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+    use crate::Parse;
+    use alloc::string::{String, ToString};
+
+    fn clause(input: &[u8]) -> cnf::Disjunction<'_> {
+        check_parse(cnf::Disjunction::parse, input)
+    }
+
+    #[test]
+    fn test_subsumes() {
+        let general = clause(b"p(X) | q(X, Y)\0");
+        assert!(subsumes(&general, &clause(b"q(a, b) | r | p(a)\0")));
+        assert!(subsumes(&general, &clause(b"p(Y) | q(Y, X)\0")));
+        assert!(!subsumes(&general, &clause(b"p(a) | q(b, a)\0")));
+        assert!(!subsumes(&general, &clause(b"p(a)\0")));
+        assert!(!subsumes(&clause(b"p(X) | p(Y)\0"), &clause(b"p(a)\0")));
+        assert!(subsumes(
+            &clause(b"p(X) | p(a)\0"),
+            &clause(b"p(a) | p(a)\0")
+        ));
+        assert!(subsumes(&clause(b"f(X) = a\0"), &clause(b"a = f(b)\0")));
+        assert!(subsumes(&clause(b"X != a\0"), &clause(b"a != b | c\0")));
+        assert!(!subsumes(&clause(b"~p(X)\0"), &clause(b"p(a)\0")));
+    }
+
+    #[test]
+    fn test_tautology() {
+        assert!(is_tautology(&clause(b"p(X) | q | ~p(X)\0")));
+        assert!(is_tautology(&clause(b"p | f(X) = f(X)\0")));
+        assert!(is_tautology(&clause(b"a = b | b != a\0")));
+        assert!(is_tautology(&clause(b"p | $true\0")));
+        assert!(is_tautology(&clause(b"p | ~$false\0")));
+        assert!(!is_tautology(&clause(b"p(X) | ~p(Y)\0")));
+        assert!(!is_tautology(&clause(b"X != X\0")));
+    }
+
+    #[test]
+    fn test_remove_redundant() {
+        let input = b"
+            cnf(a, axiom, p(X) | q(X)).
+            cnf(b, axiom, p(a) | q(a) | r).
+            fof(c, axiom, p(a)).
+            cnf(d, axiom, q(Y) | p(Y)).
+            cnf(e, axiom, r | ~r).
+            cnf(f, axiom, p(a)).
+            cnf(g, axiom, q(b)).
+        ";
+        let names: Vec<String> = remove_redundant(problem(input))
+            .iter()
+            .map(|annotated| annotated.name().to_string())
+            .collect();
+        assert_eq!(names, ["a", "c", "f", "g"]);
+
+        let clauses = [&clause(b"p(X) | q(X)\0"), &clause(b"p(a)\0")];
+        assert_eq!(redundant_clauses(&clauses), [None, None]);
+        let clauses = [&clause(b"p(a) | q(a)\0"), &clause(b"p(a)\0")];
+        assert_eq!(
+            redundant_clauses(&clauses),
+            [Some(Redundant::Subsumed(1)), None]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::TPTPIterator;
+
+macro_rules! ontology {
+    (
+        $(#[$meta:meta])*
+        $Enum:ident {
+            $(
+                $Variant:ident = $abbreviation:literal
+                $(isa $($parent:ident),+)?;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize))]
+        pub enum $Enum {
+            $(
+                #[doc = $abbreviation]
+                $Variant,
+            )*
+        }
+
+        impl $Enum {
+            /// every member of the ontology
+            pub const ALL: &'static [Self] = &[$(Self::$Variant),*];
+
+            /// the full name, e.g. `Theorem`
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Self::$Variant => stringify!($Variant),)*
+                }
+            }
+
+            /// the three-letter abbreviation, e.g. `THM`
+            pub fn abbreviation(self) -> &'static str {
+                match self {
+                    $(Self::$Variant => $abbreviation,)*
+                }
+            }
+
+            /// the immediate generalisations of this member
+            pub fn parents(self) -> &'static [Self] {
+                match self {
+                    $(Self::$Variant => &[$($(Self::$parent),+)?],)*
+                }
+            }
+
+            /// look up a member by full name or abbreviation
+            ///
+            /// Abbreviations are case-insensitive, as in `status(thm)`.
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|member| {
+                    member.name() == name
+                        || member.abbreviation().eq_ignore_ascii_case(name)
+                })
+            }
+
+            /// whether this member is, possibly indirectly, a kind of `other`
+            ///
+            /// Every member is a kind of itself.
+            pub fn isa(self, other: Self) -> bool {
+                self == other
+                    || self.parents().iter().any(|parent| parent.isa(other))
+            }
+        }
+
+        impl fmt::Display for $Enum {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.name())
+            }
+        }
+    };
+}
+
+ontology! {
+    /// an [SZS status](http://tptp.org/Seminars/SZSOntologies/Summary.html), the result of an attempt to solve a problem
+    Status {
+        Success = "SUC";
+        UnsatisfiabilityPreserving = "UNP" isa Success;
+        SatisfiabilityPreserving = "SAP" isa Success;
+        EquiSatisfiable = "ESA"
+            isa UnsatisfiabilityPreserving, SatisfiabilityPreserving;
+        Satisfiable = "SAT" isa SatisfiabilityPreserving;
+        FinitelySatisfiable = "FSA" isa Satisfiable;
+        Theorem = "THM" isa SatisfiabilityPreserving;
+        Equivalent = "EQV" isa EquiSatisfiable, Theorem;
+        TautologousConclusion = "TAC" isa Theorem;
+        WeakerConclusion = "WEC" isa Theorem;
+        EquivalentTheorem = "ETH" isa Equivalent, WeakerConclusion;
+        Tautology = "TAU" isa TautologousConclusion, EquivalentTheorem;
+        WeakerTautologousConclusion = "WTC"
+            isa TautologousConclusion, WeakerConclusion;
+        WeakerTheorem = "WTH" isa WeakerConclusion;
+        ContradictoryAxioms = "CAX" isa Theorem;
+        SatisfiableConclusionContradictoryAxioms = "SCA"
+            isa ContradictoryAxioms;
+        TautologousConclusionContradictoryAxioms = "TCA"
+            isa SatisfiableConclusionContradictoryAxioms;
+        WeakerConclusionContradictoryAxioms = "WCA"
+            isa SatisfiableConclusionContradictoryAxioms;
+        CounterUnsatisfiabilityPreserving = "CUP" isa Success;
+        CounterSatisfiabilityPreserving = "CSP" isa Success;
+        EquiCounterSatisfiable = "ECS"
+            isa CounterUnsatisfiabilityPreserving,
+                CounterSatisfiabilityPreserving;
+        CounterSatisfiable = "CSA" isa CounterSatisfiabilityPreserving;
+        FinitelyUnsatisfiable = "FUN" isa CounterSatisfiable;
+        CounterTheorem = "CTH" isa CounterSatisfiabilityPreserving;
+        CounterEquivalent = "CEQ" isa EquiCounterSatisfiable, CounterTheorem;
+        UnsatisfiableConclusion = "UNC" isa CounterTheorem;
+        WeakerCounterConclusion = "WCC" isa CounterTheorem;
+        EquivalentCounterTheorem = "ECT"
+            isa CounterEquivalent, WeakerCounterConclusion;
+        Unsatisfiable = "UNS"
+            isa UnsatisfiableConclusion, EquivalentCounterTheorem;
+        WeakerUnsatisfiableConclusion = "WUC"
+            isa UnsatisfiableConclusion, WeakerCounterConclusion;
+        WeakerCounterTheorem = "WCT" isa WeakerCounterConclusion;
+        SatisfiableCounterConclusionContradictoryAxioms = "SCC"
+            isa ContradictoryAxioms;
+        UnsatisfiableConclusionContradictoryAxioms = "UCA"
+            isa SatisfiableCounterConclusionContradictoryAxioms;
+        NoConsequence = "NOC" isa Satisfiable, CounterSatisfiable;
+        NoSuccess = "NOS";
+        Open = "OPN" isa NoSuccess;
+        Unknown = "UNK" isa NoSuccess;
+        Assumed = "ASS" isa Unknown;
+        Stopped = "STP" isa Unknown;
+        Error = "ERR" isa Stopped;
+        OSError = "OSE" isa Error;
+        InputError = "INE" isa Error;
+        UsageError = "USE" isa InputError;
+        SyntaxError = "SYE" isa InputError;
+        SemanticError = "SEE" isa InputError;
+        TypeError = "TYE" isa SemanticError;
+        Forced = "FOR" isa Stopped;
+        User = "USR" isa Forced;
+        ResourceOut = "RSO" isa Forced;
+        Timeout = "TMO" isa ResourceOut;
+        MemoryOut = "MMO" isa ResourceOut;
+        GaveUp = "GUP" isa Stopped;
+        Incomplete = "INC" isa GaveUp;
+        Inappropriate = "IAP" isa GaveUp;
+        InProgress = "INP" isa Unknown;
+        NotTried = "NTT" isa Unknown;
+        NotTriedYet = "NTY" isa NotTried;
+    }
+}
+
+impl Status {
+    /// whether this is a kind of `Success`
+    pub fn is_success(self) -> bool {
+        self.isa(Self::Success)
+    }
+}
+
+ontology! {
+    /// an [SZS dataform](http://tptp.org/Seminars/SZSOntologies/Summary.html), the kind of output produced by a solver
+    Dataform {
+        LogicalData = "LDa";
+        Solution = "Sln" isa LogicalData;
+        Derivation = "Der" isa Solution;
+        Proof = "Prf" isa Derivation;
+        Refutation = "Ref" isa Derivation;
+        CNFRefutation = "CRf" isa Refutation;
+        Interpretation = "Int" isa Solution;
+        Model = "Mod" isa Interpretation;
+        FiniteModel = "FMo" isa Model;
+        InfiniteModel = "IMo" isa Model;
+        HerbrandModel = "HMo" isa Model;
+        Saturation = "Sat" isa Derivation, Model;
+        ListOfFormulae = "Lof" isa LogicalData;
+        Assurance = "Ass" isa Solution;
+        Answer = "Ans" isa Solution;
+        None = "Non" isa LogicalData;
+    }
+}
+
+/// a `% SZS status X for Y` line
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct StatusLine<'a> {
+    pub status: Status,
+    /// the problem name, if given
+    pub problem: Option<&'a str>,
+}
+
+/// the text between `% SZS output start X for Y` and `% SZS output end X for Y` lines
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct OutputBlock<'a> {
+    pub dataform: Dataform,
+    /// the problem name from the start line, if given
+    pub problem: Option<&'a str>,
+    /// the lines of the block, excluding start and end lines
+    pub text: &'a [u8],
+}
+
+impl<'a> OutputBlock<'a> {
+    /// parse the block as TSTP
+    pub fn inputs<E>(&self) -> TPTPIterator<'a, E> {
+        TPTPIterator::new(self.text)
+    }
+}
+
+/// the SZS lines of a solver's output
+#[derive(Clone, Debug, Default, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Output<'a> {
+    /// status lines, in order
+    pub statuses: Vec<StatusLine<'a>>,
+    /// complete output blocks, in order
+    pub blocks: Vec<OutputBlock<'a>>,
+}
+
+impl<'a> Output<'a> {
+    /// the last reported status, if any
+    pub fn status(&self) -> Option<Status> {
+        self.statuses.last().map(|line| line.status)
+    }
+}
+
+enum Line<'a> {
+    Status(StatusLine<'a>),
+    Start(Dataform, Option<&'a str>),
+    End(Dataform),
+}
+
+/// split `X for Y ...` into `X` and an optional `Y`
+fn subject<'a>(words: &mut str::SplitAsciiWhitespace<'a>) -> Option<&'a str> {
+    match words.next() {
+        Some("for") => words.next(),
+        _ => None,
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<Line<'_>> {
+    let line = str::from_utf8(line).ok()?;
+    let line = line.trim_start().trim_start_matches(['%', '#']);
+    let mut words = line.split_ascii_whitespace();
+    if words.next()? != "SZS" {
+        return None;
+    }
+    match words.next()? {
+        "status" => {
+            let status = Status::from_name(words.next()?)?;
+            let problem = subject(&mut words);
+            Some(Line::Status(StatusLine { status, problem }))
+        }
+        "output" => {
+            let start = match words.next()? {
+                "start" => true,
+                "end" => false,
+                _ => return None,
+            };
+            let dataform = Dataform::from_name(words.next()?)?;
+            if start {
+                Some(Line::Start(dataform, subject(&mut words)))
+            } else {
+                Some(Line::End(dataform))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// parse a single `% SZS status X for Y` line
+///
+/// Leading `%` or `#` comment characters are optional, as solvers differ.
+/// Unrecognised statuses are not parsed.
+pub fn parse_status_line(line: &[u8]) -> Option<StatusLine<'_>> {
+    match parse_line(line)? {
+        Line::Status(status) => Some(status),
+        _ => None,
+    }
+}
+
+/// find SZS status lines and output blocks in a solver's output
+///
+/// A block is only returned if its end line is found and has the same dataform as the start line.
+pub fn parse_output(output: &[u8]) -> Output<'_> {
+    let mut result = Output::default();
+    let mut open: Option<(Dataform, Option<&str>, usize)> = None;
+    let mut offset = 0;
+    for line in output.split(|c| *c == b'\n') {
+        let start = offset;
+        offset += line.len() + 1;
+        match parse_line(line) {
+            Some(Line::Status(status)) => result.statuses.push(status),
+            Some(Line::Start(dataform, problem)) => {
+                open = Some((dataform, problem, offset.min(output.len())));
+            }
+            Some(Line::End(dataform)) => {
+                if let Some((expected, problem, begin)) = open.take() {
+                    if expected == dataform {
+                        result.blocks.push(OutputBlock {
+                            dataform,
+                            problem,
+                            text: &output[begin..start],
+                        });
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    #[test]
+    fn test_ontology() {
+        assert_eq!(Status::from_name("Theorem"), Some(Status::Theorem));
+        assert_eq!(Status::from_name("thm"), Some(Status::Theorem));
+        assert_eq!(Status::from_name("Thing"), None);
+        assert_eq!(Status::GaveUp.abbreviation(), "GUP");
+        assert_eq!(
+            Status::CounterSatisfiable.to_string(),
+            "CounterSatisfiable"
+        );
+        assert!(Status::Tautology.isa(Status::Theorem));
+        assert!(Status::Unsatisfiable.isa(Status::CounterTheorem));
+        assert!(Status::Timeout.isa(Status::ResourceOut));
+        assert!(!Status::Theorem.isa(Status::Tautology));
+        assert!(Status::Theorem.is_success());
+        assert!(!Status::Timeout.is_success());
+        assert!(Dataform::CNFRefutation.isa(Dataform::Derivation));
+        assert!(Dataform::FiniteModel.isa(Dataform::Interpretation));
+        assert!(Status::ALL
+            .iter()
+            .all(|s| s.isa(Status::Success) != s.isa(Status::NoSuccess)));
+    }
+
+    #[test]
+    fn test_status_line() {
+        let line =
+            parse_status_line(b"% SZS status Theorem for SYN075+1").unwrap();
+        assert_eq!(line.status, Status::Theorem);
+        assert_eq!(line.problem, Some("SYN075+1"));
+        let line = parse_status_line(b"# SZS status GaveUp").unwrap();
+        assert_eq!(line.status, Status::GaveUp);
+        assert_eq!(line.problem, None);
+        assert!(parse_status_line(b"% SZS status Thing for X").is_none());
+        assert!(parse_status_line(b"% status Theorem").is_none());
+    }
+
+    #[test]
+    fn test_output() {
+        let log = b"\
+Running prover...
+% SZS status Unsatisfiable for PUZ001-1
+% SZS output start CNFRefutation for PUZ001-1
+cnf(c1, axiom, p).
+% a comment
+cnf(c2, axiom, ~p).
+cnf(c3, plain, $false, inference(sr, [], [c1, c2])).
+% SZS output end CNFRefutation for PUZ001-1
+% SZS output start Model for PUZ001-1
+unterminated
+";
+        let output = parse_output(log);
+        assert_eq!(output.status(), Some(Status::Unsatisfiable));
+        assert_eq!(output.statuses[0].problem, Some("PUZ001-1"));
+        assert_eq!(output.blocks.len(), 1);
+        let block = output.blocks[0];
+        assert_eq!(block.dataform, Dataform::CNFRefutation);
+        assert_eq!(block.problem, Some("PUZ001-1"));
+        let inputs: Vec<String> = block
+            .inputs::<()>()
+            .map(|input| input.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            inputs,
+            [
+                "cnf(c1,axiom,p).",
+                "cnf(c2,axiom,~p).",
+                "cnf(c3,plain,$false,inference(sr,[],[c1,c2])).",
+            ]
+        );
+    }
+}
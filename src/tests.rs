@@ -1,4 +1,7 @@
-use crate::Result;
+use alloc::vec::Vec;
+
+use crate::top::{AnnotatedFormula, TPTPInput};
+use crate::{Result, TPTPIterator};
 
 pub(crate) fn check_parse<'a, P, T: 'a>(parser: P, input: &'a [u8]) -> T
 where
@@ -11,6 +14,16 @@ where
     }
 }
 
+/// the annotated formulas of a problem without includes
+pub(crate) fn problem(input: &[u8]) -> Vec<AnnotatedFormula<'_>> {
+    TPTPIterator::<()>::new(input)
+        .map(|input| match input.expect("syntax error") {
+            TPTPInput::Annotated(annotated) => *annotated,
+            TPTPInput::Include(_) => panic!("include"),
+        })
+        .collect()
+}
+
 pub(crate) fn parse_unit<'a, P, T: 'a>(parser: P, input: &'a [u8])
 where
     P: FnOnce(&'a [u8]) -> Result<T, ()>,
@@ -22,7 +35,7 @@ macro_rules! parse_snapshot {
     ($T: ident, $input: expr) => {
         let parsed = crate::tests::check_parse($T::parse, $input);
         insta::assert_debug_snapshot!(parsed);
-        insta::assert_display_snapshot!(parsed);
+        insta::assert_snapshot!(parsed);
     };
 }
 
@@ -22,7 +22,7 @@ use crate::{Error, Parse, Result};
 pub struct TypeArguments<'a>(pub Vec<AtomicType<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TypeArguments<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -45,7 +45,7 @@ pub enum AtomicType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(DefinedType::parse, Self::Defined),
             map(common::Variable::parse, Self::Variable),
@@ -70,7 +70,7 @@ impl<'a> TypedVariableTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TypedVariableTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(
             delimited(ignored, tag(":"), ignored),
             map(AtomicType::parse, Self),
@@ -88,7 +88,7 @@ pub struct TypedVariable<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TypedVariable<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(common::Variable::parse, TypedVariableTail::parse),
             |(variable, tail)| tail.finish(variable),
@@ -105,7 +105,7 @@ pub enum Variable<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Variable<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(common::Variable::parse, opt(TypedVariableTail::parse)),
             |(var, tail)| match tail {
@@ -123,7 +123,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Variable<'a> {
 pub struct VariableList<'a>(pub Vec<Variable<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for VariableList<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -144,7 +144,7 @@ pub enum UnitaryType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(AtomicType::parse, Self::Atomic),
             map(parens, Self::Product),
@@ -159,7 +159,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryType<'a> {
 pub struct XprodType<'a>(pub Vec<UnitaryType<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for XprodType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag("*"), ignored),
@@ -180,7 +180,7 @@ pub struct MappingType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for MappingType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitaryType::parse,
@@ -207,7 +207,7 @@ pub struct QuantifiedType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for QuantifiedType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(
             tag("!>"),
             preceded(
@@ -245,7 +245,7 @@ pub enum Monotype<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Monotype<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(AtomicType::parse, Self::Atomic),
             map(parens, Self::Mapping),
@@ -265,7 +265,7 @@ pub enum NonAtomicType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for NonAtomicType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(MappingType::parse, Self::Mapping),
             map(QuantifiedType::parse, Self::Quantified),
@@ -283,7 +283,7 @@ pub enum TopLevelType<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TopLevelType<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(map(NonAtomicType::parse, Box::new), Self::NonAtomic),
             map(map(AtomicType::parse, Box::new), Self::Atomic),
@@ -302,7 +302,7 @@ pub enum AtomTyping<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomTyping<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -328,7 +328,7 @@ pub enum Term<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Term<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(map(LogicFormula::parse, Box::new), Self::Logic),
             map(DefinedTerm::parse, Self::Defined),
@@ -348,7 +348,7 @@ pub enum UnitaryTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(AtomicFormula::parse, Self::Atomic),
             map(DefinedTerm::parse, Self::Defined),
@@ -365,7 +365,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryTerm<'a> {
 pub struct Arguments<'a>(pub Vec<Term<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Arguments<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -386,7 +386,7 @@ pub enum SystemAtomic<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for SystemAtomic<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(SystemFunctor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -407,7 +407,7 @@ pub enum PlainAtomic<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for PlainAtomic<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(Functor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -428,7 +428,7 @@ pub enum DefinedPlain<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedPlain<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(DefinedFunctor::parse, opt(preceded(ignored, parens))),
             |(f, args)| match args {
@@ -445,7 +445,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for DefinedPlain<'a> {
 pub struct DefinedAtomic<'a>(pub DefinedPlain<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedAtomic<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(DefinedPlain::parse, Self)(x)
     }
 }
@@ -460,7 +460,7 @@ pub enum AtomicFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AtomicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(PlainAtomic::parse, Self::Plain),
             map(SystemAtomic::parse, Self::System),
@@ -481,7 +481,7 @@ impl<'a> DefinedInfixTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfixTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 preceded(ignored, DefinedInfixPred::parse),
@@ -503,7 +503,7 @@ pub struct DefinedInfix<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for DefinedInfix<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitaryTerm::parse, DefinedInfixTail::parse),
             |(left, tail)| tail.finish(left),
@@ -523,7 +523,7 @@ impl<'a> InfixUnaryTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixUnaryTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 preceded(ignored, InfixInequality::parse),
@@ -545,7 +545,7 @@ pub struct InfixUnary<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for InfixUnary<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitaryTerm::parse, InfixUnaryTail::parse),
             |(left, tail)| tail.finish(left),
@@ -563,7 +563,7 @@ pub struct PrefixUnary<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for PrefixUnary<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnaryConnective::parse,
@@ -586,7 +586,7 @@ pub enum UnaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(PrefixUnary::parse, Self::Prefix),
             map(InfixUnary::parse, Self::Infix),
@@ -603,7 +603,7 @@ pub enum PreunitFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for PreunitFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(PrefixUnary::parse, Self::Prefix),
             map(UnitaryFormula::parse, Self::Unitary),
@@ -622,7 +622,7 @@ pub struct QuantifiedFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for QuantifiedFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             tuple((
                 fof::Quantifier::parse,
@@ -650,7 +650,7 @@ pub enum UnitaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(QuantifiedFormula::parse, Self::Quantified),
             map(AtomicFormula::parse, Self::Atomic),
@@ -679,7 +679,7 @@ impl<'a> UnitFormulaTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitFormulaTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(DefinedInfixTail::parse, Self::Equality),
             map(InfixUnaryTail::parse, Self::Inequality),
@@ -694,7 +694,7 @@ enum UnitaryTermOrFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitaryTermOrFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(AtomicFormula::parse, Self::Atomic),
             map(common::Variable::parse, Self::Variable),
@@ -733,7 +733,7 @@ pub enum UnitFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UnitFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(UnitaryTermOrFormula::parse, opt(UnitFormulaTail::parse)),
@@ -790,7 +790,7 @@ impl<'a> OrTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for OrTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(assoc_tail(b'|'), Self)(x)
     }
 }
@@ -802,7 +802,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for OrTail<'a> {
 pub struct OrFormula<'a>(pub Vec<UnitFormula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for OrFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitFormula::parse, preceded(ignored, OrTail::parse)),
             |(first, tail)| tail.finish(first),
@@ -819,7 +819,7 @@ impl<'a> AndTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AndTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(assoc_tail(b'&'), Self)(x)
     }
 }
@@ -831,7 +831,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for AndTail<'a> {
 pub struct AndFormula<'a>(pub Vec<UnitFormula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for AndFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(UnitFormula::parse, preceded(ignored, AndTail::parse)),
             |(first, tail)| tail.finish(first),
@@ -845,7 +845,7 @@ enum BinaryAssocTail<'a> {
 }
 
 impl<'a> BinaryAssocTail<'a> {
-    fn finish(self, left: UnitFormula<'a>) -> BinaryAssoc {
+    fn finish(self, left: UnitFormula<'a>) -> BinaryAssoc<'a> {
         match self {
             Self::Or(tail) => BinaryAssoc::Or(tail.finish(left)),
             Self::And(tail) => BinaryAssoc::And(tail.finish(left)),
@@ -854,7 +854,7 @@ impl<'a> BinaryAssocTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryAssocTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(OrTail::parse, BinaryAssocTail::Or),
             map(AndTail::parse, BinaryAssocTail::And),
@@ -871,7 +871,7 @@ pub enum BinaryAssoc<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryAssoc<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -894,7 +894,7 @@ impl<'a> BinaryNonassocTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryNonassocTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 NonassocConnective::parse,
@@ -916,7 +916,7 @@ pub struct BinaryNonassoc<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryNonassoc<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -942,7 +942,7 @@ impl<'a> BinaryFormulaTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryFormulaTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(BinaryAssocTail::parse, Self::Assoc),
             map(BinaryNonassocTail::parse, Self::Nonassoc),
@@ -959,7 +959,7 @@ pub enum BinaryFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for BinaryFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -981,7 +981,7 @@ pub enum LogicFormula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for LogicFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 UnitFormula::parse,
@@ -1008,7 +1008,7 @@ pub enum Formula<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Formula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(map(AtomTyping::parse, Box::new), Self::AtomTyping),
             map(map(LogicFormula::parse, Box::new), Self::Logic),
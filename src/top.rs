@@ -23,7 +23,7 @@ use crate::{Error, Parse, Result};
 pub struct FileName<'a>(pub SingleQuoted<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FileName<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(SingleQuoted::parse, Self)(x)
     }
 }
@@ -35,7 +35,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for FileName<'a> {
 pub struct NameList<'a>(pub Vec<Name<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for NameList<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -52,7 +52,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for NameList<'a> {
 pub struct FormulaRole<'a>(pub LowerWord<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FormulaRole<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(LowerWord::parse, FormulaRole)(x)
     }
 }
@@ -72,7 +72,7 @@ pub enum FormulaData<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FormulaData<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(
             tag("$"),
             alt((
@@ -107,7 +107,7 @@ impl<'a> GeneralFunctionTail<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralFunctionTail<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(parens, Self)(x)
     }
 }
@@ -122,7 +122,7 @@ pub struct GeneralFunction<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralFunction<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             pair(
                 AtomicWord::parse,
@@ -146,7 +146,7 @@ pub enum GeneralData<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralData<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(
                 pair(
@@ -176,7 +176,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for GeneralData<'a> {
 pub struct GeneralTerms<'a>(pub Vec<GeneralTerm<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralTerms<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             separated_list1(
                 delimited(ignored, tag(","), ignored),
@@ -202,7 +202,7 @@ impl<'a> fmt::Display for GeneralList<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralList<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             delimited(
                 tag("["),
@@ -225,7 +225,7 @@ pub enum GeneralTerm<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for GeneralTerm<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(GeneralList::parse, Self::List),
             map(
@@ -254,7 +254,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for GeneralTerm<'a> {
 pub struct Source<'a>(pub GeneralTerm<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Source<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(GeneralTerm::parse, Self)(x)
     }
 }
@@ -265,7 +265,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for Source<'a> {
 pub struct UsefulInfo<'a>(pub GeneralList<'a>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for UsefulInfo<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(GeneralList::parse, Self)(x)
     }
 }
@@ -285,7 +285,7 @@ impl<'a> fmt::Display for OptionalInfo<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for OptionalInfo<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             opt(preceded(tag(","), preceded(ignored, UsefulInfo::parse))),
             Self,
@@ -308,7 +308,7 @@ impl<'a> fmt::Display for Annotations<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Annotations<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             opt(preceded(
                 pair(tag(","), ignored),
@@ -343,7 +343,7 @@ impl<'a> fmt::Display for FormulaSelection<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FormulaSelection<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             opt(preceded(tag(","), delimited(ignored, brackets, ignored))),
             Self,
@@ -361,7 +361,7 @@ pub struct Include<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for Include<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         map(
             preceded(
                 tag("include"),
@@ -446,7 +446,7 @@ impl<'a, E: Error<'a>, T: Parse<'a, E>> Parse<'a, E> for Annotated<'a, T> {
 pub struct TfxAnnotated<'a>(pub Annotated<'a, tfx::Formula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TfxAnnotated<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(tag("tff"), preceded(ignored, map(Annotated::parse, Self)))(x)
     }
 }
@@ -458,7 +458,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for TfxAnnotated<'a> {
 pub struct FofAnnotated<'a>(pub Annotated<'a, fof::Formula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for FofAnnotated<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(tag("fof"), preceded(ignored, map(Annotated::parse, Self)))(x)
     }
 }
@@ -470,7 +470,7 @@ impl<'a, E: Error<'a>> Parse<'a, E> for FofAnnotated<'a> {
 pub struct CnfAnnotated<'a>(pub Annotated<'a, cnf::Formula<'a>>);
 
 impl<'a, E: Error<'a>> Parse<'a, E> for CnfAnnotated<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         preceded(tag("cnf"), preceded(ignored, map(Annotated::parse, Self)))(x)
     }
 }
@@ -484,8 +484,37 @@ pub enum AnnotatedFormula<'a> {
     Cnf(Box<CnfAnnotated<'a>>),
 }
 
+impl<'a> AnnotatedFormula<'a> {
+    /// the name of this formula
+    pub fn name(&self) -> &Name<'a> {
+        match self {
+            Self::Tfx(tfx) => &tfx.0.name,
+            Self::Fof(fof) => &fof.0.name,
+            Self::Cnf(cnf) => &cnf.0.name,
+        }
+    }
+
+    /// the role of this formula
+    pub fn role(&self) -> &FormulaRole<'a> {
+        match self {
+            Self::Tfx(tfx) => &tfx.0.role,
+            Self::Fof(fof) => &fof.0.role,
+            Self::Cnf(cnf) => &cnf.0.role,
+        }
+    }
+
+    /// the annotations of this formula
+    pub fn annotations(&self) -> &Annotations<'a> {
+        match self {
+            Self::Tfx(tfx) => &tfx.0.annotations,
+            Self::Fof(fof) => &fof.0.annotations,
+            Self::Cnf(cnf) => &cnf.0.annotations,
+        }
+    }
+}
+
 impl<'a, E: Error<'a>> Parse<'a, E> for AnnotatedFormula<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(map(TfxAnnotated::parse, Box::new), Self::Tfx),
             map(map(FofAnnotated::parse, Box::new), Self::Fof),
@@ -503,7 +532,7 @@ pub enum TPTPInput<'a> {
 }
 
 impl<'a, E: Error<'a>> Parse<'a, E> for TPTPInput<'a> {
-    fn parse(x: &'a [u8]) -> Result<Self, E> {
+    fn parse(x: &'a [u8]) -> Result<'a, Self, E> {
         alt((
             map(map(AnnotatedFormula::parse, Box::new), Self::Annotated),
             map(map(Include::parse, Box::new), Self::Include),
@@ -511,6 +540,15 @@ impl<'a, E: Error<'a>> Parse<'a, E> for TPTPInput<'a> {
     }
 }
 
+/// an annotated formula which an exporter cannot handle, and why
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[display(fmt = "{}: {}", name, kind)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExportError<'a, K> {
+    pub name: Name<'a>,
+    pub kind: K,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1053 @@
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::iter::once;
+use core::ops::Range;
+use derive_more::Display;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::common::*;
+use crate::tfx;
+use crate::tfx::AtomicType;
+use crate::top::*;
+use crate::utils::Separated;
+use crate::visitor::Visitor;
+
+/// the declared type of a symbol, e.g. `!>[A:$tType]: ((list(A) * A) > $o)`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Signature<'a> {
+    /// type variables bound by `!>`, which become leading type arguments
+    pub parameters: Vec<Variable<'a>>,
+    /// the types of the term arguments
+    pub arguments: Vec<AtomicType<'a>>,
+    /// the result type, `$o` for predicates
+    pub result: AtomicType<'a>,
+}
+
+/// what went wrong in a `TypeError`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum TypeErrorKind<'a> {
+    /// a function or predicate symbol without a declaration
+    #[display(fmt = "undeclared symbol {}", _0)]
+    UndeclaredSymbol(String),
+    /// a type constructor without a declaration
+    #[display(fmt = "undeclared type {}", _0)]
+    UndeclaredType(String),
+    /// a variable which no enclosing quantifier binds
+    #[display(fmt = "unbound variable {}", _0)]
+    UnboundVariable(Variable<'a>),
+    /// a symbol or type constructor declared twice, differently
+    #[display(fmt = "conflicting declarations of {}", _0)]
+    Redeclared(String),
+    /// a symbol or type constructor applied to the wrong number of arguments
+    #[display(
+        fmt = "{} expects {} arguments, found {}",
+        symbol,
+        expected,
+        found
+    )]
+    Arity {
+        symbol: String,
+        expected: usize,
+        found: usize,
+    },
+    /// a term used where a type is expected
+    #[display(fmt = "{} is not a type", _0)]
+    NotAType(String),
+    /// a term or type variable of the wrong type, e.g. a non-Boolean formula
+    #[display(fmt = "expected {}, found {}", expected, found)]
+    Mismatch {
+        expected: AtomicType<'a>,
+        found: AtomicType<'a>,
+    },
+    /// an equality between terms of different types
+    #[display(fmt = "ill-typed equality between {} and {}", left, right)]
+    Equality {
+        left: AtomicType<'a>,
+        right: AtomicType<'a>,
+    },
+    /// an arithmetic symbol applied to arguments which fit none of its instances
+    #[display(
+        fmt = "no instance of {} for ({})",
+        symbol,
+        "Separated(',', arguments)"
+    )]
+    Overload {
+        symbol: String,
+        arguments: Vec<AtomicType<'a>>,
+    },
+}
+
+/// a type error found by `TypeChecker`
+#[derive(Clone, Debug, Display, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[display(fmt = "{}: {}", name, kind)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TypeError<'a> {
+    /// the name of the annotated formula containing the error
+    pub name: Name<'a>,
+    pub kind: TypeErrorKind<'a>,
+    /// the text of the symbol at which the error was detected
+    pub at: &'a str,
+}
+
+impl<'a> TypeError<'a> {
+    /// the byte range of the offending symbol in `input`
+    ///
+    /// Parsers are zero-copy, so if the problem was parsed from `input`, `at` points into it.
+    /// The range is widened to cover the `$`/`$$` of defined and system words and the quotes of a single-quoted word.
+    /// Returns `None` if `at` was not borrowed from `input`.
+    pub fn span(&self, input: &[u8]) -> Option<Range<usize>> {
+        let base = input.as_ptr() as usize;
+        let position = self.at.as_ptr() as usize;
+        if self.at.is_empty()
+            || position < base
+            || position + self.at.len() > base + input.len()
+        {
+            return None;
+        }
+        let mut start = position - base;
+        let mut end = start + self.at.len();
+        if start > 0
+            && end < input.len()
+            && input[start - 1] == b'\''
+            && input[end] == b'\''
+        {
+            start -= 1;
+            end += 1;
+        }
+        while start > 0 && input[start - 1] == b'$' {
+            start -= 1;
+        }
+        Some(start..end)
+    }
+}
+
+fn defined(name: &'static str) -> AtomicType<'static> {
+    let word = DollarWord(LowerWord(name));
+    AtomicType::Defined(DefinedType(AtomicDefinedWord(word)))
+}
+
+fn defined_name<'a>(typ: &AtomicType<'a>) -> Option<&'a str> {
+    match typ {
+        AtomicType::Defined(DefinedType(AtomicDefinedWord(word))) => {
+            Some((word.0).0)
+        }
+        _ => None,
+    }
+}
+
+fn is_numeric(typ: &AtomicType) -> bool {
+    matches!(defined_name(typ), Some("int" | "rat" | "real"))
+}
+
+fn is_kind(typ: &AtomicType) -> bool {
+    defined_name(typ) == Some("tType")
+}
+
+fn word_text<'a>(word: &AtomicWord<'a>) -> &'a str {
+    match word {
+        AtomicWord::Lower(lower) => lower.0,
+        AtomicWord::SingleQuoted(quoted) => quoted.0,
+    }
+}
+
+fn flatten_unitary<'a>(
+    unitary: &tfx::UnitaryType<'a>,
+    arguments: &mut Vec<AtomicType<'a>>,
+) {
+    match unitary {
+        tfx::UnitaryType::Atomic(atomic) => arguments.push(atomic.clone()),
+        tfx::UnitaryType::Product(product) => {
+            for unitary in &product.0 {
+                flatten_unitary(unitary, arguments);
+            }
+        }
+    }
+}
+
+fn substitute<'a>(
+    typ: &AtomicType<'a>,
+    substitution: &[(Variable<'a>, AtomicType<'a>)],
+) -> AtomicType<'a> {
+    match typ {
+        AtomicType::Variable(variable) => substitution
+            .iter()
+            .find(|(v, _)| v == variable)
+            .map(|(_, t)| t.clone())
+            .unwrap_or_else(|| typ.clone()),
+        AtomicType::Function(functor, arguments) => {
+            let arguments = arguments
+                .0
+                .iter()
+                .map(|t| substitute(t, substitution))
+                .collect();
+            AtomicType::Function(
+                functor.clone(),
+                Box::new(tfx::TypeArguments(arguments)),
+            )
+        }
+        _ => typ.clone(),
+    }
+}
+
+#[derive(Default)]
+struct FirstToken<'a>(Option<&'a str>);
+
+impl<'a> Visitor<'a> for FirstToken<'a> {
+    fn visit_lower_word(&mut self, lower_word: &LowerWord<'a>) {
+        self.0.get_or_insert(lower_word.0);
+    }
+
+    fn visit_upper_word(&mut self, upper_word: &UpperWord<'a>) {
+        self.0.get_or_insert(upper_word.0);
+    }
+
+    fn visit_single_quoted(&mut self, single_quoted: &SingleQuoted<'a>) {
+        self.0.get_or_insert(single_quoted.0);
+    }
+
+    fn visit_distinct_object(&mut self, distinct_object: &DistinctObject<'a>) {
+        self.0.get_or_insert(distinct_object.0);
+    }
+
+    fn visit_integer(&mut self, integer: &Integer<'a>) {
+        self.0.get_or_insert(integer.0);
+    }
+
+    fn visit_rational(&mut self, rational: &Rational<'a>) {
+        self.0.get_or_insert(rational.0);
+    }
+
+    fn visit_real(&mut self, real: &Real<'a>) {
+        self.0.get_or_insert(real.0);
+    }
+}
+
+enum Returns {
+    Boolean,
+    Argument,
+    Fixed(&'static str),
+}
+
+/// type checker for TFX problems
+///
+/// Declarations from `type`-role formulas are collected with `declare`, then each formula is checked with `check`.
+/// `check_problem` does both passes over a whole problem, so declarations may appear in any order.
+/// TF1 polymorphic symbols take their type arguments explicitly, as leading arguments.
+/// Untyped quantified variables have type `$i`.
+/// FOF and CNF formulas are untyped and are ignored.
+#[derive(Default)]
+pub struct TypeChecker<'a> {
+    types: BTreeMap<AtomicWord<'a>, usize>,
+    symbols: BTreeMap<AtomicWord<'a>, Signature<'a>>,
+    bound: Vec<(Variable<'a>, AtomicType<'a>)>,
+    /// errors in the formula being declared or checked, named by `report`
+    pending: Vec<(&'a str, TypeErrorKind<'a>)>,
+    errors: Vec<TypeError<'a>>,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the arity of a declared type constructor
+    pub fn type_arity(&self, word: &AtomicWord<'a>) -> Option<usize> {
+        self.types.get(word).copied()
+    }
+
+    /// the declared signature of a symbol
+    pub fn signature(&self, word: &AtomicWord<'a>) -> Option<&Signature<'a>> {
+        self.symbols.get(word)
+    }
+
+    /// errors found so far
+    pub fn errors(&self) -> &[TypeError<'a>] {
+        &self.errors
+    }
+
+    /// finish checking, returning all errors found
+    pub fn finish(self) -> Vec<TypeError<'a>> {
+        self.errors
+    }
+
+    fn error(&mut self, at: &'a str, kind: TypeErrorKind<'a>) {
+        self.pending.push((at, kind));
+    }
+
+    /// record pending errors as errors in the formula `name`
+    fn report(&mut self, name: &Name<'a>) {
+        for (at, kind) in self.pending.drain(..) {
+            let name = name.clone();
+            self.errors.push(TypeError { name, kind, at });
+        }
+    }
+
+    fn lookup(&self, variable: &Variable<'a>) -> Option<&AtomicType<'a>> {
+        self.bound
+            .iter()
+            .rev()
+            .find(|(v, _)| v == variable)
+            .map(|(_, t)| t)
+    }
+
+    /// add the declaration in a `type`-role formula, if any
+    pub fn declare(&mut self, annotated: &AnnotatedFormula<'a>) {
+        if let AnnotatedFormula::Tfx(tfx) = annotated {
+            if (tfx.0.role.0).0 != "type" {
+                return;
+            }
+            if let tfx::Formula::AtomTyping(typing) = &*tfx.0.formula {
+                self.declare_typing(typing);
+                self.report(&tfx.0.name);
+            }
+        }
+    }
+
+    fn declare_typing(&mut self, typing: &tfx::AtomTyping<'a>) {
+        let (atom, typ) = match typing {
+            tfx::AtomTyping::Typing(atom, typ) => (atom, typ),
+            tfx::AtomTyping::Parenthesised(typing) => {
+                return self.declare_typing(typing)
+            }
+        };
+        let word = match atom {
+            UntypedAtom::Constant(constant) => &(constant.0).0,
+            UntypedAtom::System(_) => return,
+        };
+        let at = word_text(word);
+        let signature = self.signature_of(typ);
+        if is_kind(&signature.result)
+            && signature.parameters.is_empty()
+            && signature.arguments.iter().all(is_kind)
+        {
+            let arity = signature.arguments.len();
+            if self.types.insert(word.clone(), arity).unwrap_or(arity) != arity
+            {
+                self.error(at, TypeErrorKind::Redeclared(word.to_string()));
+            }
+        } else if let Some(previous) =
+            self.symbols.insert(word.clone(), signature.clone())
+        {
+            if previous != signature {
+                self.error(at, TypeErrorKind::Redeclared(word.to_string()));
+            }
+        }
+    }
+
+    fn signature_of(&mut self, typ: &tfx::TopLevelType<'a>) -> Signature<'a> {
+        let mut signature = Signature {
+            parameters: Vec::new(),
+            arguments: Vec::new(),
+            result: defined("i"),
+        };
+        match typ {
+            tfx::TopLevelType::Atomic(atomic) => {
+                signature.result = (**atomic).clone()
+            }
+            tfx::TopLevelType::NonAtomic(non_atomic) => {
+                self.non_atomic_signature(non_atomic, &mut signature)
+            }
+        }
+        signature
+    }
+
+    fn non_atomic_signature(
+        &mut self,
+        typ: &tfx::NonAtomicType<'a>,
+        signature: &mut Signature<'a>,
+    ) {
+        match typ {
+            tfx::NonAtomicType::Mapping(mapping) => {
+                Self::mapping_signature(mapping, signature)
+            }
+            tfx::NonAtomicType::Quantified(quantified) => {
+                self.quantified_signature(quantified, signature)
+            }
+            tfx::NonAtomicType::Parenthesised(typ) => {
+                self.non_atomic_signature(typ, signature)
+            }
+        }
+    }
+
+    fn mapping_signature(
+        mapping: &tfx::MappingType<'a>,
+        signature: &mut Signature<'a>,
+    ) {
+        flatten_unitary(&mapping.domain, &mut signature.arguments);
+        signature.result = mapping.range.clone();
+    }
+
+    fn quantified_signature(
+        &mut self,
+        quantified: &tfx::QuantifiedType<'a>,
+        signature: &mut Signature<'a>,
+    ) {
+        for variable in &quantified.bound.0 {
+            let variable = match variable {
+                tfx::Variable::Typed(typed) => {
+                    if !is_kind(&typed.typ) {
+                        self.error(
+                            (typed.variable.0).0,
+                            TypeErrorKind::Mismatch {
+                                expected: defined("tType"),
+                                found: typed.typ.clone(),
+                            },
+                        );
+                    }
+                    &typed.variable
+                }
+                tfx::Variable::Untyped(variable) => variable,
+            };
+            signature.parameters.push(variable.clone());
+        }
+        match &*quantified.typ {
+            tfx::Monotype::Atomic(atomic) => signature.result = atomic.clone(),
+            tfx::Monotype::Mapping(mapping) => {
+                Self::mapping_signature(mapping, signature)
+            }
+            tfx::Monotype::Quantified(quantified) => {
+                self.quantified_signature(quantified, signature)
+            }
+        }
+    }
+
+    /// check an annotated formula against the declarations seen so far
+    pub fn check(&mut self, annotated: &AnnotatedFormula<'a>) {
+        let tfx = match annotated {
+            AnnotatedFormula::Tfx(tfx) => &tfx.0,
+            _ => return,
+        };
+        match &*tfx.formula {
+            tfx::Formula::AtomTyping(typing) => self.check_typing(typing),
+            tfx::Formula::Logic(formula) => {
+                let typ = self.infer_logic(formula);
+                self.expect_boolean(typ, |v| {
+                    v.visit_tfx_logic_formula(formula)
+                });
+            }
+        }
+        self.bound.clear();
+        self.report(&tfx.name);
+    }
+
+    fn check_typing(&mut self, typing: &tfx::AtomTyping<'a>) {
+        let (atom, typ) = match typing {
+            tfx::AtomTyping::Typing(atom, typ) => (atom, typ),
+            tfx::AtomTyping::Parenthesised(typing) => {
+                return self.check_typing(typing)
+            }
+        };
+        if let UntypedAtom::System(_) = atom {
+            return;
+        }
+        let errors = self.pending.len();
+        let signature = self.signature_of(typ);
+        self.pending.truncate(errors);
+        for parameter in &signature.parameters {
+            self.bound.push((parameter.clone(), defined("tType")));
+        }
+        for typ in signature.arguments.iter().chain(once(&signature.result)) {
+            if !is_kind(typ) {
+                self.check_type(typ);
+            }
+        }
+    }
+
+    fn check_type(&mut self, typ: &AtomicType<'a>) {
+        match typ {
+            AtomicType::Defined(DefinedType(AtomicDefinedWord(word))) => {
+                if !matches!(
+                    (word.0).0,
+                    "i" | "o" | "int" | "rat" | "real" | "tType"
+                ) {
+                    self.error(
+                        (word.0).0,
+                        TypeErrorKind::UndeclaredType(typ.to_string()),
+                    );
+                }
+            }
+            AtomicType::Variable(variable) => match self.lookup(variable) {
+                Some(kind) if is_kind(kind) => {}
+                Some(kind) => {
+                    let found = kind.clone();
+                    self.error(
+                        (variable.0).0,
+                        TypeErrorKind::Mismatch {
+                            expected: defined("tType"),
+                            found,
+                        },
+                    );
+                }
+                None => self.error(
+                    (variable.0).0,
+                    TypeErrorKind::UnboundVariable(variable.clone()),
+                ),
+            },
+            AtomicType::Constant(constant) => {
+                self.check_type_application(&(constant.0).0, 0)
+            }
+            AtomicType::Function(functor, arguments) => {
+                self.check_type_application(&functor.0, arguments.0.len());
+                for argument in &arguments.0 {
+                    self.check_type(argument);
+                }
+            }
+        }
+    }
+
+    fn check_type_application(&mut self, word: &AtomicWord<'a>, found: usize) {
+        let at = word_text(word);
+        match self.types.get(word) {
+            None => {
+                self.error(at, TypeErrorKind::UndeclaredType(word.to_string()))
+            }
+            Some(&expected) if expected != found => self.error(
+                at,
+                TypeErrorKind::Arity {
+                    symbol: word.to_string(),
+                    expected,
+                    found,
+                },
+            ),
+            Some(_) => {}
+        }
+    }
+
+    fn bind(&mut self, list: &tfx::VariableList<'a>) {
+        for variable in &list.0 {
+            match variable {
+                tfx::Variable::Typed(typed) => {
+                    self.check_type(&typed.typ);
+                    self.bound
+                        .push((typed.variable.clone(), typed.typ.clone()));
+                }
+                tfx::Variable::Untyped(variable) => {
+                    self.bound.push((variable.clone(), defined("i")));
+                }
+            }
+        }
+    }
+
+    fn expect_boolean<F>(&mut self, found: Option<AtomicType<'a>>, anchor: F)
+    where
+        F: FnOnce(&mut FirstToken<'a>),
+    {
+        if let Some(found) = found {
+            if defined_name(&found) != Some("o") {
+                let mut first = FirstToken::default();
+                anchor(&mut first);
+                self.error(
+                    first.0.unwrap_or(""),
+                    TypeErrorKind::Mismatch {
+                        expected: defined("o"),
+                        found,
+                    },
+                );
+            }
+        }
+    }
+
+    fn check_unit_boolean(&mut self, formula: &tfx::UnitFormula<'a>) {
+        let typ = self.infer_unit(formula);
+        self.expect_boolean(typ, |v| v.visit_tfx_unit_formula(formula));
+    }
+
+    fn check_equality(
+        &mut self,
+        left: &tfx::UnitaryTerm<'a>,
+        right: &tfx::UnitaryTerm<'a>,
+    ) {
+        let left_type = self.infer_unitary_term(left);
+        let right_type = self.infer_unitary_term(right);
+        if let (Some(left_type), Some(right_type)) = (left_type, right_type) {
+            if left_type != right_type {
+                let mut first = FirstToken::default();
+                first.visit_tfx_unitary_term(left);
+                self.error(
+                    first.0.unwrap_or(""),
+                    TypeErrorKind::Equality {
+                        left: left_type,
+                        right: right_type,
+                    },
+                );
+            }
+        }
+    }
+
+    fn infer_logic(
+        &mut self,
+        formula: &tfx::LogicFormula<'a>,
+    ) -> Option<AtomicType<'a>> {
+        match formula {
+            tfx::LogicFormula::Unary(unary) => {
+                self.check_unary(unary);
+                Some(defined("o"))
+            }
+            tfx::LogicFormula::Unitary(unitary) => {
+                self.infer_unitary_formula(unitary)
+            }
+            tfx::LogicFormula::Binary(binary) => {
+                self.check_binary(binary);
+                Some(defined("o"))
+            }
+            tfx::LogicFormula::DefinedInfix(infix) => {
+                self.check_equality(&infix.left, &infix.right);
+                Some(defined("o"))
+            }
+        }
+    }
+
+    fn check_binary(&mut self, binary: &tfx::BinaryFormula<'a>) {
+        match binary {
+            tfx::BinaryFormula::Assoc(assoc) => {
+                let units = match assoc {
+                    tfx::BinaryAssoc::Or(or) => &or.0,
+                    tfx::BinaryAssoc::And(and) => &and.0,
+                };
+                for unit in units {
+                    self.check_unit_boolean(unit);
+                }
+            }
+            tfx::BinaryFormula::Nonassoc(nonassoc) => {
+                self.check_unit_boolean(&nonassoc.left);
+                self.check_unit_boolean(&nonassoc.right);
+            }
+        }
+    }
+
+    fn check_unary(&mut self, unary: &tfx::UnaryFormula<'a>) {
+        match unary {
+            tfx::UnaryFormula::Prefix(prefix) => self.check_prefix(prefix),
+            tfx::UnaryFormula::Infix(infix) => {
+                self.check_equality(&infix.left, &infix.right)
+            }
+        }
+    }
+
+    fn check_prefix(&mut self, prefix: &tfx::PrefixUnary<'a>) {
+        match &*prefix.formula {
+            tfx::PreunitFormula::Unitary(unitary) => {
+                let typ = self.infer_unitary_formula(unitary);
+                self.expect_boolean(typ, |v| {
+                    v.visit_tfx_unitary_formula(unitary)
+                });
+            }
+            tfx::PreunitFormula::Prefix(prefix) => self.check_prefix(prefix),
+        }
+    }
+
+    fn infer_unit(
+        &mut self,
+        formula: &tfx::UnitFormula<'a>,
+    ) -> Option<AtomicType<'a>> {
+        match formula {
+            tfx::UnitFormula::Unitary(unitary) => {
+                self.infer_unitary_formula(unitary)
+            }
+            tfx::UnitFormula::Unary(unary) => {
+                self.check_unary(unary);
+                Some(defined("o"))
+            }
+            tfx::UnitFormula::DefinedInfix(infix) => {
+                self.check_equality(&infix.left, &infix.right);
+                Some(defined("o"))
+            }
+        }
+    }
+
+    fn infer_unitary_formula(
+        &mut self,
+        formula: &tfx::UnitaryFormula<'a>,
+    ) -> Option<AtomicType<'a>> {
+        match formula {
+            tfx::UnitaryFormula::Quantified(quantified) => {
+                let depth = self.bound.len();
+                self.bind(&quantified.bound);
+                self.check_unit_boolean(&quantified.formula);
+                self.bound.truncate(depth);
+                Some(defined("o"))
+            }
+            tfx::UnitaryFormula::Atomic(atomic) => self.infer_atomic(atomic),
+            tfx::UnitaryFormula::Variable(variable) => {
+                self.infer_variable(variable)
+            }
+            tfx::UnitaryFormula::Logic(logic) => self.infer_logic(logic),
+        }
+    }
+
+    fn infer_unitary_term(
+        &mut self,
+        term: &tfx::UnitaryTerm<'a>,
+    ) -> Option<AtomicType<'a>> {
+        match term {
+            tfx::UnitaryTerm::Atomic(atomic) => self.infer_atomic(atomic),
+            tfx::UnitaryTerm::Defined(defined) => {
+                Some(Self::infer_defined(defined))
+            }
+            tfx::UnitaryTerm::Variable(variable) => {
+                self.infer_variable(variable)
+            }
+            tfx::UnitaryTerm::Logic(logic) => self.infer_logic(logic),
+        }
+    }
+
+    fn infer_term(&mut self, term: &tfx::Term<'a>) -> Option<AtomicType<'a>> {
+        match term {
+            tfx::Term::Logic(logic) => self.infer_logic(logic),
+            tfx::Term::Defined(defined) => Some(Self::infer_defined(defined)),
+        }
+    }
+
+    fn infer_defined(term: &DefinedTerm<'a>) -> AtomicType<'a> {
+        match term {
+            DefinedTerm::Number(Number::Integer(_)) => defined("int"),
+            DefinedTerm::Number(Number::Rational(_)) => defined("rat"),
+            DefinedTerm::Number(Number::Real(_)) => defined("real"),
+            DefinedTerm::Distinct(_) => defined("i"),
+        }
+    }
+
+    fn infer_variable(
+        &mut self,
+        variable: &Variable<'a>,
+    ) -> Option<AtomicType<'a>> {
+        let typ = self.lookup(variable).cloned();
+        if typ.is_none() {
+            self.error(
+                (variable.0).0,
+                TypeErrorKind::UnboundVariable(variable.clone()),
+            );
+        }
+        typ
+    }
+
+    fn infer_atomic(
+        &mut self,
+        atomic: &tfx::AtomicFormula<'a>,
+    ) -> Option<AtomicType<'a>> {
+        match atomic {
+            tfx::AtomicFormula::Plain(tfx::PlainAtomic::Constant(
+                constant,
+            )) => self.apply(&constant.0, &[]),
+            tfx::AtomicFormula::Plain(tfx::PlainAtomic::Function(
+                functor,
+                arguments,
+            )) => self.apply(functor, &arguments.0),
+            tfx::AtomicFormula::Defined(tfx::DefinedAtomic(
+                tfx::DefinedPlain::Constant(constant),
+            )) => self.apply_defined(&constant.0, &[]),
+            tfx::AtomicFormula::Defined(tfx::DefinedAtomic(
+                tfx::DefinedPlain::Function(functor, arguments),
+            )) => self.apply_defined(functor, &arguments.0),
+            tfx::AtomicFormula::System(_) => None,
+        }
+    }
+
+    fn term_as_type(&self, term: &tfx::Term<'a>) -> Option<AtomicType<'a>> {
+        let unitary = match term {
+            tfx::Term::Logic(logic) => match &**logic {
+                tfx::LogicFormula::Unitary(unitary) => unitary,
+                _ => return None,
+            },
+            tfx::Term::Defined(_) => return None,
+        };
+        match unitary {
+            tfx::UnitaryFormula::Variable(variable) => self
+                .lookup(variable)
+                .filter(|kind| is_kind(kind))
+                .map(|_| AtomicType::Variable(variable.clone())),
+            tfx::UnitaryFormula::Atomic(tfx::AtomicFormula::Plain(
+                tfx::PlainAtomic::Constant(constant),
+            )) if self.types.contains_key(&(constant.0).0) => {
+                let functor = TypeFunctor((constant.0).0.clone());
+                Some(AtomicType::Constant(TypeConstant(functor)))
+            }
+            tfx::UnitaryFormula::Atomic(tfx::AtomicFormula::Plain(
+                tfx::PlainAtomic::Function(functor, arguments),
+            )) if self.types.contains_key(&functor.0) => {
+                let arguments = arguments
+                    .0
+                    .iter()
+                    .map(|argument| self.term_as_type(argument))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(AtomicType::Function(
+                    TypeFunctor(functor.0.clone()),
+                    Box::new(tfx::TypeArguments(arguments)),
+                ))
+            }
+            tfx::UnitaryFormula::Atomic(tfx::AtomicFormula::Defined(
+                tfx::DefinedAtomic(tfx::DefinedPlain::Constant(constant)),
+            )) => {
+                let word = &(constant.0).0;
+                let typ = AtomicType::Defined(DefinedType(word.clone()));
+                match ((word.0).0).0 {
+                    "i" | "o" | "int" | "rat" | "real" => Some(typ),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn apply(
+        &mut self,
+        functor: &Functor<'a>,
+        arguments: &[tfx::Term<'a>],
+    ) -> Option<AtomicType<'a>> {
+        let at = word_text(&functor.0);
+        let signature = match self.symbols.get(&functor.0) {
+            Some(signature) => signature.clone(),
+            None => {
+                self.error(
+                    at,
+                    TypeErrorKind::UndeclaredSymbol(functor.to_string()),
+                );
+                for argument in arguments {
+                    self.infer_term(argument);
+                }
+                return None;
+            }
+        };
+        let expected = signature.parameters.len() + signature.arguments.len();
+        if arguments.len() != expected {
+            self.error(
+                at,
+                TypeErrorKind::Arity {
+                    symbol: functor.to_string(),
+                    expected,
+                    found: arguments.len(),
+                },
+            );
+            for argument in arguments {
+                self.infer_term(argument);
+            }
+            return if signature.parameters.is_empty() {
+                Some(signature.result)
+            } else {
+                None
+            };
+        }
+
+        let (type_arguments, arguments) =
+            arguments.split_at(signature.parameters.len());
+        let mut substitution = Vec::new();
+        for (parameter, argument) in
+            signature.parameters.iter().zip(type_arguments)
+        {
+            match self.term_as_type(argument) {
+                Some(typ) => {
+                    self.check_type(&typ);
+                    substitution.push((parameter.clone(), typ));
+                }
+                None => {
+                    let mut first = FirstToken::default();
+                    first.visit_tfx_term(argument);
+                    self.error(
+                        first.0.unwrap_or(at),
+                        TypeErrorKind::NotAType(argument.to_string()),
+                    );
+                    return None;
+                }
+            }
+        }
+        for (expected, argument) in signature.arguments.iter().zip(arguments) {
+            let expected = substitute(expected, &substitution);
+            if let Some(found) = self.infer_term(argument) {
+                if found != expected {
+                    let mut first = FirstToken::default();
+                    first.visit_tfx_term(argument);
+                    self.error(
+                        first.0.unwrap_or(at),
+                        TypeErrorKind::Mismatch { expected, found },
+                    );
+                }
+            }
+        }
+        Some(substitute(&signature.result, &substitution))
+    }
+
+    fn apply_defined(
+        &mut self,
+        functor: &DefinedFunctor<'a>,
+        arguments: &[tfx::Term<'a>],
+    ) -> Option<AtomicType<'a>> {
+        let at = (((functor.0).0).0).0;
+        let (arity, numeric, returns) = match at {
+            "true" | "false" => (Some(0), false, Returns::Boolean),
+            "distinct" => (None, false, Returns::Boolean),
+            "less" | "lesseq" | "greater" | "greatereq" => {
+                (Some(2), true, Returns::Boolean)
+            }
+            "is_int" | "is_rat" => (Some(1), true, Returns::Boolean),
+            "uminus" | "floor" | "ceiling" | "truncate" | "round" => {
+                (Some(1), true, Returns::Argument)
+            }
+            "sum" | "difference" | "product" | "quotient" | "quotient_e"
+            | "quotient_t" | "quotient_f" | "remainder_e" | "remainder_t"
+            | "remainder_f" => (Some(2), true, Returns::Argument),
+            "to_int" => (Some(1), true, Returns::Fixed("int")),
+            "to_rat" => (Some(1), true, Returns::Fixed("rat")),
+            "to_real" => (Some(1), true, Returns::Fixed("real")),
+            _ => {
+                self.error(
+                    at,
+                    TypeErrorKind::UndeclaredSymbol(functor.to_string()),
+                );
+                for argument in arguments {
+                    self.infer_term(argument);
+                }
+                return None;
+            }
+        };
+
+        let types = arguments
+            .iter()
+            .map(|argument| self.infer_term(argument))
+            .collect::<Vec<_>>();
+        let result = match returns {
+            Returns::Boolean => Some(defined("o")),
+            Returns::Fixed(name) => Some(defined(name)),
+            Returns::Argument => None,
+        };
+        if let Some(expected) = arity {
+            if expected != arguments.len() {
+                self.error(
+                    at,
+                    TypeErrorKind::Arity {
+                        symbol: functor.to_string(),
+                        expected,
+                        found: arguments.len(),
+                    },
+                );
+                return result;
+            }
+        }
+        let types = types.into_iter().collect::<Option<Vec<_>>>()?;
+        let well_typed = types.windows(2).all(|pair| pair[0] == pair[1])
+            && (!numeric || types.iter().all(is_numeric))
+            && !(at == "quotient" && defined_name(&types[0]) == Some("int"));
+        if !well_typed {
+            self.error(
+                at,
+                TypeErrorKind::Overload {
+                    symbol: functor.to_string(),
+                    arguments: types,
+                },
+            );
+            return result;
+        }
+        result.or_else(|| types.into_iter().next())
+    }
+}
+
+/// type-check a whole problem
+///
+/// Declarations are collected from every `type`-role formula first, then every formula is checked.
+pub fn check_problem<'a>(
+    problem: &[AnnotatedFormula<'a>],
+) -> Vec<TypeError<'a>> {
+    let mut checker = TypeChecker::new();
+    for annotated in problem {
+        checker.declare(annotated);
+    }
+    for annotated in problem {
+        checker.check(annotated);
+    }
+    checker.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::problem;
+
+    fn errors(input: &[u8]) -> Vec<String> {
+        check_problem(&problem(input))
+            .iter()
+            .map(|e| e.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_well_typed() {
+        let input = b"
+            tff(list, type, list: $tType > $tType).
+            tff(nil, type, nil: !>[A: $tType]: list(A)).
+            tff(cons, type, cons: !>[A: $tType]: ((A * list(A)) > list(A))).
+            tff(p, type, p: $int > $o).
+            tff(a1, axiom, ![X: $int]: p($sum(X, 1))).
+            tff(a2, axiom, cons($int, 2, nil($int)) != nil($int)).
+            tff(a3, axiom, ![A: $tType, X: A]: cons(A, X, nil(A)) = cons(A, X, nil(A))).
+            tff(a4, axiom, $less(1.5, $to_real(2)) & $true).
+            fof(f, axiom, q(X)).
+        ";
+        assert!(errors(input).is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let input = b"
+            tff(p, type, p: $int > $o).
+            tff(c, type, c: $i).
+            tff(a1, axiom, q(c)).
+            tff(a2, axiom, p(c)).
+            tff(a3, axiom, p(1, 2)).
+            tff(a4, axiom, c = 1).
+            tff(a5, axiom, $sum(1, 2.0) = 3).
+            tff(a6, axiom, c).
+            tff(a7, axiom, ![X: t]: p(X)).
+        ";
+        assert_eq!(
+            errors(input),
+            [
+                "a1: undeclared symbol q",
+                "a2: expected $int, found $i",
+                "a3: p expects 1 arguments, found 2",
+                "a4: ill-typed equality between $i and $int",
+                "a5: no instance of $sum for ($int,$real)",
+                "a6: expected $o, found $i",
+                "a7: undeclared type t",
+                "a7: expected $int, found t",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_polymorphic_errors() {
+        let input = b"
+            tff(list, type, list: $tType > $tType).
+            tff(nil, type, nil: !>[A: $tType]: list(A)).
+            tff(a1, axiom, nil(1) = nil(1)).
+            tff(a2, axiom, nil($int) = nil($real)).
+            tff(a3, axiom, nil = nil).
+        ";
+        assert_eq!(
+            errors(input),
+            [
+                "a1: 1 is not a type",
+                "a1: 1 is not a type",
+                "a2: ill-typed equality between list($int) and list($real)",
+                "a3: nil expects 1 arguments, found 0",
+                "a3: nil expects 1 arguments, found 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let input =
+            b"tff(c, type, c: $i).\ntff(a, axiom, $less(c, c) | 'd').\n";
+        let problem = problem(input);
+        let errors = check_problem(&problem);
+        let spans = errors
+            .iter()
+            .map(|e| &input[e.span(input).unwrap()])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, [&b"$less"[..], b"'d'"]);
+    }
+}
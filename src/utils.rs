@@ -1,9 +1,11 @@
 use alloc::fmt;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 use core::mem;
 use nom::Parser;
 
+use crate::common::AtomicWord;
 use crate::{Error, Result};
 
 pub(crate) struct Separated<'a, T>(pub(crate) char, pub(crate) &'a [T]);
@@ -70,3 +72,25 @@ impl<T> GarbageFirstVec<T> {
         unsafe { Vec::from_raw_parts(ptr, len, cap) }
     }
 }
+
+/// `text` with backslash escapes removed
+pub(crate) fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' {
+            chars.next().unwrap_or(c)
+        } else {
+            c
+        });
+    }
+    unescaped
+}
+
+/// the text of `word`, without quotes or escapes
+pub(crate) fn word_text(word: &AtomicWord) -> String {
+    match word {
+        AtomicWord::Lower(word) => word.0.to_string(),
+        AtomicWord::SingleQuoted(quoted) => unescape(quoted.0),
+    }
+}
@@ -0,0 +1,267 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::io::{Read, Write};
+use std::process::exit;
+use tptp::pretty::Pretty;
+use tptp::top::TPTPInput;
+use tptp::Parse;
+
+const USAGE: &str = "\
+usage: tptp-fmt [OPTIONS] [FILE...]
+
+Reformat TPTP files in place, or standard input to standard output.
+
+options:
+    --check                 report unformatted files and exit 1 if any
+    --stdout                write to standard output instead of in place
+    --width N               preferred maximum line width (default 80)
+    --indent N              indentation of formulas (default 4)
+    --no-spaces             no spaces around connectives
+    --no-break-quantifiers  never break lines after quantifiers";
+
+struct Options {
+    check: bool,
+    stdout: bool,
+    pretty: Pretty,
+    files: Vec<String>,
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    exit(2)
+}
+
+fn number(arg: Option<String>) -> usize {
+    arg.and_then(|arg| arg.parse().ok())
+        .unwrap_or_else(|| usage())
+}
+
+fn options() -> Options {
+    let mut options = Options {
+        check: false,
+        stdout: false,
+        pretty: Pretty::default(),
+        files: vec![],
+    };
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => options.check = true,
+            "--stdout" => options.stdout = true,
+            "--width" => options.pretty.width = number(args.next()),
+            "--indent" => options.pretty.indent = number(args.next()),
+            "--no-spaces" => options.pretty.spaces = false,
+            "--no-break-quantifiers" => {
+                options.pretty.break_quantifiers = false
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                exit(0)
+            }
+            _ if arg.starts_with('-') => usage(),
+            _ => options.files.push(arg),
+        }
+    }
+    options
+}
+
+/// the comment at the start of `bytes`, if any
+fn comment(bytes: &[u8]) -> Option<Result<&[u8], usize>> {
+    if bytes.starts_with(b"%") {
+        let end = bytes
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .unwrap_or(bytes.len());
+        let line = &bytes[..end];
+        Some(Ok(line.strip_suffix(b"\r").unwrap_or(line)))
+    } else if bytes.starts_with(b"/*") {
+        let end = bytes[2..]
+            .windows(2)
+            .position(|window| window == b"*/")
+            .map(|end| end + 4);
+        Some(end.map(|end| &bytes[..end]).ok_or(0))
+    } else {
+        None
+    }
+}
+
+/// whether `text` and `input` differ other than in whitespace, i.e. `text` contains comments
+fn has_comments(text: &[u8], input: &TPTPInput) -> bool {
+    let printed = input.to_string();
+    let mut printed =
+        printed.bytes().filter(|byte| !byte.is_ascii_whitespace());
+    let mut text = text
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace());
+    !printed.by_ref().eq(text.by_ref())
+}
+
+/// reformat a TPTP file, or return the byte offset of a syntax error
+///
+/// Comments and blank lines between inputs are kept, but runs of blank lines are collapsed.
+/// Inputs containing comments are left as they are.
+fn format(bytes: &[u8], pretty: &Pretty) -> Result<String, usize> {
+    let mut output = String::new();
+    let mut newlines = 0;
+    let mut position = 0;
+    let separate = |output: &mut String, newlines: usize| {
+        if !output.is_empty() {
+            output.push_str(if newlines > 1 { "\n\n" } else { "\n" });
+        }
+    };
+    while position < bytes.len() {
+        let remaining = &bytes[position..];
+        let byte = remaining[0];
+        if byte.is_ascii_whitespace() {
+            if byte == b'\n' {
+                newlines += 1;
+            }
+            position += 1;
+            continue;
+        }
+
+        if let Some(comment) = comment(remaining) {
+            let comment = comment.map_err(|_| position)?;
+            if newlines == 0 && !output.is_empty() {
+                output.push(' ');
+            } else {
+                separate(&mut output, newlines);
+            }
+            output.push_str(&String::from_utf8_lossy(comment));
+            position += comment.len();
+            newlines = 0;
+            continue;
+        }
+
+        let (rest, input) = <TPTPInput as Parse<()>>::parse(remaining)
+            .map_err(|_| position)?;
+        let text = &remaining[..remaining.len() - rest.len()];
+        separate(&mut output, newlines);
+        if has_comments(text, &input) {
+            output.push_str(&String::from_utf8_lossy(text));
+        } else {
+            output.push_str(&pretty.input(&input));
+        }
+        position += text.len();
+        newlines = 0;
+    }
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn syntax_error(name: &str, offset: usize) -> ! {
+    eprintln!("{}: syntax error at byte {}", name, offset);
+    exit(2)
+}
+
+fn io_error(name: &str, error: io::Error) -> ! {
+    eprintln!("{}: {}", name, error);
+    exit(2)
+}
+
+fn write_stdout(bytes: &[u8]) {
+    io::stdout()
+        .lock()
+        .write_all(bytes)
+        .unwrap_or_else(|error| io_error("<stdout>", error));
+}
+
+fn main() {
+    let options = options();
+    if options.files.is_empty() {
+        let mut bytes = vec![];
+        io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .unwrap_or_else(|error| io_error("<stdin>", error));
+        let formatted = format(&bytes, &options.pretty)
+            .unwrap_or_else(|offset| syntax_error("<stdin>", offset));
+        if options.check {
+            if formatted.as_bytes() != bytes {
+                eprintln!("<stdin>: not formatted");
+                exit(1)
+            }
+        } else {
+            write_stdout(formatted.as_bytes());
+        }
+        return;
+    }
+
+    let mut unformatted = false;
+    for name in &options.files {
+        let bytes =
+            fs::read(name).unwrap_or_else(|error| io_error(name, error));
+        let formatted = format(&bytes, &options.pretty)
+            .unwrap_or_else(|offset| syntax_error(name, offset));
+        if options.check {
+            if formatted.as_bytes() != bytes {
+                eprintln!("{}: not formatted", name);
+                unformatted = true;
+            }
+        } else if options.stdout {
+            write_stdout(formatted.as_bytes());
+        } else if formatted.as_bytes() != bytes {
+            fs::write(name, formatted)
+                .unwrap_or_else(|error| io_error(name, error));
+        }
+    }
+    if unformatted {
+        exit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatted(input: &str) -> String {
+        format(input.as_bytes(), &Pretty::default()).expect("syntax error")
+    }
+
+    #[test]
+    fn test_format() {
+        let input = "\
+% header
+
+
+fof(a,axiom,p). % trailing
+/* block */ cnf(b,axiom,q|r).
+fof(c, axiom, p /* inner */ & q).
+";
+        assert_eq!(
+            formatted(input),
+            "\
+% header
+
+fof(a,axiom,p). % trailing
+/* block */
+cnf(b,axiom,q | r).
+fof(c, axiom, p /* inner */ & q).
+"
+        );
+        assert_eq!(formatted(&formatted(input)), formatted(input));
+        assert_eq!(formatted(""), "");
+        assert_eq!(
+            format(b"fof(a,axiom,p).\n/* open", &Pretty::default()),
+            Err(16)
+        );
+        assert_eq!(format(b"fof(a,axiom,(p).", &Pretty::default()), Err(0));
+    }
+
+    #[test]
+    fn test_has_comments() {
+        let has = |text: &str| {
+            let bytes = text.as_bytes();
+            let (rest, input) =
+                <TPTPInput as Parse<()>>::parse(bytes).expect("syntax error");
+            has_comments(&bytes[..bytes.len() - rest.len()], &input)
+        };
+        assert!(!has("fof(a, axiom,\n    p & q).\0"));
+        assert!(has("fof(a, axiom, p % why\n & q).\0"));
+        assert!(has("fof(a, axiom, /* p */ q).\0"));
+    }
+}